@@ -24,6 +24,17 @@ pub fn get_confirmation_password() -> Result<String> {
     Ok(Password::new().with_prompt("Confirm password").interact()?)
 }
 
+pub fn get_passphrase() -> Result<String> {
+    Ok(Password::new().with_prompt("Passphrase").interact()?)
+}
+
+pub fn get_new_passphrase() -> Result<String> {
+    Ok(Password::new()
+        .with_prompt("Passphrase")
+        .with_confirmation("Confirm passphrase", "Passphrases don't match")
+        .interact()?)
+}
+
 pub fn ask<S>(prompt: S) -> Result<bool>
 where
     S: Into<String> + std::marker::Copy,
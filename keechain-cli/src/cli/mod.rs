@@ -3,13 +3,19 @@
 
 use std::path::PathBuf;
 
-use clap::{Parser, Subcommand};
-use keechain_core::bdk::miniscript::Descriptor;
+use clap::{ArgAction, Parser, Subcommand};
+use keechain_core::bdk::miniscript::{Descriptor, DescriptorPublicKey};
+use keechain_core::bips::bip129::Token;
+use keechain_core::bips::bip32::DerivationPath;
+use keechain_core::descriptors;
 use keechain_core::types::Index;
+use keechain_core::SigningDescriptor;
 
 pub mod io;
 
-use crate::types::{CliElectrumSupportedScripts, CliNetwork, CliWordCount};
+use crate::types::{
+    CliElectrumSupportedScripts, CliLanguage, CliNetwork, CliPurpose, CliScriptType, CliWordCount,
+};
 
 #[derive(Debug, Parser)]
 #[command(name = "keechain")]
@@ -37,12 +43,21 @@ pub enum Command {
         #[arg(long, default_value_t = false)]
         dice_roll: bool,
     },
-    /// Restore mnemonic (BIP39)
+    /// Restore mnemonic (BIP39 or Electrum)
     #[command(arg_required_else_help = true)]
     Restore {
         /// Keychain name
         #[arg(required = true)]
         name: String,
+        /// Electrum seed (standard or segwit)
+        #[arg(long, default_value_t = false, conflicts_with_all = ["raw_seed", "xprv"])]
+        electrum: bool,
+        /// BIP32 seed (hex, between 16 and 64 bytes)
+        #[arg(long, default_value_t = false, conflicts_with = "xprv")]
+        raw_seed: bool,
+        /// BIP32 root extended private key
+        #[arg(long, default_value_t = false)]
+        xprv: bool,
     },
     /// List keychains
     List,
@@ -79,8 +94,70 @@ pub enum Command {
         /// PSBT file
         #[arg(required = true)]
         file: PathBuf,
-        /// Descriptor (optional)
-        descriptor: Option<Descriptor<String>>,
+        /// Descriptor or BIP388 wallet policy JSON (optional, multisig PSBTs are signed with the registered wallet)
+        descriptor: Option<SigningDescriptor>,
+    },
+    /// Derive addresses of a single-sig account or of a registered multisig wallet
+    #[command(arg_required_else_help = true)]
+    Addresses {
+        /// Keychain name (or watch-only keychain name)
+        #[arg(required = true)]
+        name: String,
+        /// Address type
+        #[arg(long, value_enum, default_value_t = CliPurpose::BIP84)]
+        purpose: CliPurpose,
+        /// Account number
+        #[arg(long, default_value_t = 0)]
+        account: u32,
+        /// Registered multisig wallet name (instead of purpose and account)
+        #[arg(long, conflicts_with_all = ["purpose", "account"])]
+        wallet: Option<String>,
+        /// Change addresses
+        #[arg(long, default_value_t = false, action = ArgAction::Set)]
+        change: bool,
+        /// First index
+        #[arg(long, default_value_t = 0)]
+        from: u32,
+        /// Number of addresses
+        #[arg(long, default_value_t = 20)]
+        count: u32,
+        /// Print a QR code for each address
+        #[arg(long, default_value_t = false)]
+        qr: bool,
+    },
+    /// Search which account, chain and index (or multisig wallet) produced an address
+    #[command(arg_required_else_help = true)]
+    VerifyAddress {
+        /// Keychain name
+        #[arg(required = true)]
+        name: String,
+        /// Address
+        #[arg(required = true)]
+        address: String,
+        /// Number of accounts to search
+        #[arg(long, default_value_t = 10)]
+        accounts: u32,
+        /// Number of indexes to search, for both receive and change
+        #[arg(long, default_value_t = 1000)]
+        indexes: u32,
+        /// Number of threads (default: all cores)
+        #[arg(long)]
+        threads: Option<usize>,
+    },
+    /// Watch-only keychains (no secrets)
+    WatchOnly {
+        #[command(subcommand)]
+        command: WatchOnlyCommand,
+    },
+    /// Multisig wallets (coordinated with the cosigners)
+    Multisig {
+        #[command(subcommand)]
+        command: MultisigCommand,
+    },
+    /// Passphrases
+    Passphrase {
+        #[command(subcommand)]
+        command: PassphraseCommand,
     },
     /// Advanced
     Advanced {
@@ -94,11 +171,353 @@ pub enum Command {
     },
 }
 
+#[derive(Debug, Subcommand)]
+pub enum WatchOnlyCommand {
+    /// Import watch-only keychain from Coldcard generic JSON, descriptors or account key
+    #[command(arg_required_else_help = true)]
+    Import {
+        /// Keychain name
+        #[arg(required = true)]
+        name: String,
+        /// Coldcard generic JSON file
+        #[arg(
+            long,
+            conflicts_with_all = ["descriptor", "key"],
+            required_unless_present_any = ["descriptor", "key"]
+        )]
+        coldcard: Option<PathBuf>,
+        /// Descriptor (can be repeated, ex. for external and internal)
+        #[arg(long, conflicts_with = "key", value_parser = descriptors::parse_descriptor)]
+        descriptor: Vec<Descriptor<DescriptorPublicKey>>,
+        /// Account key with origin (ex. `[fingerprint/84'/0'/0']xpub...`, also as `ypub` or `zpub`)
+        #[arg(long, value_parser = descriptors::parse_key)]
+        key: Option<DescriptorPublicKey>,
+    },
+    /// Derive addresses
+    #[command(arg_required_else_help = true)]
+    Addresses {
+        /// Keychain name
+        #[arg(required = true)]
+        name: String,
+        /// Address type
+        #[arg(long, value_enum, default_value_t = CliPurpose::BIP84)]
+        purpose: CliPurpose,
+        /// Change addresses
+        #[arg(long, default_value_t = false)]
+        change: bool,
+        /// First index
+        #[arg(long, default_value_t = 0)]
+        from: u32,
+        /// Number of addresses
+        #[arg(long, default_value_t = 20)]
+        count: u32,
+    },
+    /// Decode PSBT and check which inputs and outputs belong to the keychain
+    #[command(arg_required_else_help = true)]
+    Review {
+        /// Keychain name
+        #[arg(required = true)]
+        name: String,
+        /// PSBT file
+        #[arg(required = true)]
+        file: PathBuf,
+    },
+    /// Remove watch-only keychain
+    #[command(arg_required_else_help = true)]
+    Remove {
+        /// Keychain name
+        #[arg(required = true)]
+        name: String,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum MultisigCommand {
+    /// Create a multisig wallet from our BIP48 key and the cosigners and save it in the keychain
+    #[command(arg_required_else_help = true)]
+    Create {
+        /// Keychain name
+        #[arg(required = true)]
+        name: String,
+        /// Wallet name
+        #[arg(long, required = true)]
+        wallet: String,
+        /// Script type
+        #[arg(long, value_enum, default_value_t = CliScriptType::P2wsh)]
+        script: CliScriptType,
+        /// Account number
+        #[arg(long, default_value_t = 0)]
+        account: u32,
+        /// Number of required signatures
+        #[arg(long, required = true)]
+        threshold: usize,
+        /// Cosigner: Coldcard generic JSON file, descriptor file, descriptor or key expression (can be repeated)
+        #[arg(long, required = true)]
+        cosigner: Vec<String>,
+    },
+    /// Compile a miniscript policy (ex. `or(pk(@me),and(pk(@heir),after(52560)))`) and optionally save it as a wallet
+    #[command(arg_required_else_help = true)]
+    Policy {
+        /// Keychain name
+        #[arg(required = true)]
+        name: String,
+        /// Policy: `@me` is our key, the other aliases are set with `--key`
+        #[arg(required = true)]
+        policy: String,
+        /// Script type
+        #[arg(long, value_enum, default_value_t = CliScriptType::P2wsh)]
+        script: CliScriptType,
+        /// Account number of our BIP48 key
+        #[arg(long, default_value_t = 0)]
+        account: u32,
+        /// Derivation path of our key (instead of the BIP48 account)
+        #[arg(long)]
+        path: Option<DerivationPath>,
+        /// Key alias: `alias=cosigner` (Coldcard generic JSON file, descriptor file, descriptor or key expression) (can be repeated)
+        #[arg(long)]
+        key: Vec<String>,
+        /// Save as wallet with this name
+        #[arg(long)]
+        wallet: Option<String>,
+    },
+    /// Bitcoin Secure Multisig Setup (BIP129)
+    Bsms {
+        #[command(subcommand)]
+        command: BsmsCommand,
+    },
+    /// List multisig wallets
+    #[command(arg_required_else_help = true)]
+    List {
+        /// Keychain name
+        #[arg(required = true)]
+        name: String,
+    },
+    /// Remove multisig wallet
+    #[command(arg_required_else_help = true)]
+    Remove {
+        /// Keychain name
+        #[arg(required = true)]
+        name: String,
+        /// Wallet name
+        #[arg(required = true)]
+        wallet: String,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum BsmsCommand {
+    /// Generate a random setup token to share with the signers (coordinator)
+    Token {
+        /// 128-bit token (instead of 64-bit)
+        #[arg(long, default_value_t = false)]
+        long: bool,
+    },
+    /// Export the key record of our BIP48 key (round 1, signer)
+    #[command(arg_required_else_help = true)]
+    KeyRecord {
+        /// Keychain name
+        #[arg(required = true)]
+        name: String,
+        /// Script type
+        #[arg(long, value_enum, default_value_t = CliScriptType::P2wsh)]
+        script: CliScriptType,
+        /// Account number
+        #[arg(long, default_value_t = 0)]
+        account: u32,
+        /// Setup token (`00` for no encryption)
+        #[arg(long, default_value_t = Token::default())]
+        token: Token,
+        /// Description of the signer (max 80 chars)
+        #[arg(long, default_value_t = String::new())]
+        description: String,
+    },
+    /// Verify the key records and export the descriptor record of the wallet (round 2, coordinator)
+    #[command(arg_required_else_help = true)]
+    Coordinator {
+        /// Setup token (`00` for no encryption)
+        #[arg(long, default_value_t = Token::default())]
+        token: Token,
+        /// Script type
+        #[arg(long, value_enum, default_value_t = CliScriptType::P2wsh)]
+        script: CliScriptType,
+        /// Number of required signatures
+        #[arg(long, required = true)]
+        threshold: usize,
+        /// Key record: file or record (can be repeated)
+        #[arg(long, required = true)]
+        record: Vec<String>,
+    },
+    /// Verify the descriptor record and save the wallet in the keychain (round 2, signer)
+    #[command(arg_required_else_help = true)]
+    Register {
+        /// Keychain name
+        #[arg(required = true)]
+        name: String,
+        /// Descriptor record: file or record
+        #[arg(required = true)]
+        record: String,
+        /// Wallet name
+        #[arg(long, required = true)]
+        wallet: String,
+        /// Setup token (`00` for no encryption)
+        #[arg(long, default_value_t = Token::default())]
+        token: Token,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum PassphraseCommand {
+    /// List saved passphrases (label and fingerprint)
+    #[command(arg_required_else_help = true)]
+    List {
+        /// Keychain name
+        #[arg(required = true)]
+        name: String,
+        /// Show passphrases
+        #[arg(long, default_value_t = false)]
+        show: bool,
+    },
+    /// Save passphrase
+    #[command(arg_required_else_help = true)]
+    Add {
+        /// Keychain name
+        #[arg(required = true)]
+        name: String,
+        /// Label
+        #[arg(required = true)]
+        label: String,
+    },
+    /// Remove saved passphrase
+    #[command(arg_required_else_help = true)]
+    Remove {
+        /// Keychain name
+        #[arg(required = true)]
+        name: String,
+        /// Label
+        #[arg(required = true)]
+        label: String,
+    },
+    /// Apply passphrase and check it against the saved ones
+    #[command(arg_required_else_help = true)]
+    Apply {
+        /// Keychain name
+        #[arg(required = true)]
+        name: String,
+    },
+    /// Search a forgotten passphrase and apply it
+    #[command(arg_required_else_help = true)]
+    Search {
+        /// Keychain name
+        #[arg(required = true)]
+        name: String,
+        /// Target: root fingerprint, xpub or address
+        #[arg(required = true)]
+        target: String,
+        /// Wordlist file (one candidate per line)
+        #[arg(long, conflicts_with = "mask", required_unless_present = "mask")]
+        wordlist: Option<PathBuf>,
+        /// Mask (`?l` lowercase, `?u` uppercase, `?d` digit, `?s` symbol, `?a` any, `??` literal `?`)
+        #[arg(long)]
+        mask: Option<String>,
+        /// Also try the case mutations
+        #[arg(long, default_value_t = false)]
+        case: bool,
+        /// Also try the typos (one character deleted, swapped, replaced or added)
+        #[arg(long, default_value_t = false)]
+        typos: bool,
+        /// Address type (for address target)
+        #[arg(long, value_enum, default_value_t = CliPurpose::BIP84)]
+        purpose: CliPurpose,
+        /// Number of receive addresses to check (for address target)
+        #[arg(long, default_value_t = 20)]
+        gap: u32,
+        /// Number of threads (default: all cores)
+        #[arg(long)]
+        threads: Option<usize>,
+        /// Save the found passphrase with this label
+        #[arg(long)]
+        label: Option<String>,
+    },
+}
+
 #[derive(Debug, Subcommand)]
 pub enum AdvancedCommand {
     /// Deterministic entropy (BIP85)
-    #[command(arg_required_else_help = true)]
     Derive {
+        #[command(subcommand)]
+        command: DeriveCommand,
+    },
+    /// Recover a mnemonic with missing or misspelled words
+    #[command(arg_required_else_help = true)]
+    RecoverMnemonic {
+        /// Mnemonic (`?` for unknown words, `word1|word2` for alternatives)
+        #[arg(required = true)]
+        mnemonic: String,
+        /// Target: root fingerprint, xpub or address
+        #[arg(required = true)]
+        target: String,
+        /// Address type (for address target)
+        #[arg(long, value_enum, default_value_t = CliPurpose::BIP84)]
+        purpose: CliPurpose,
+        /// Number of receive addresses to check (for address target)
+        #[arg(long, default_value_t = 20)]
+        gap: u32,
+        /// Ask for the BIP39 passphrase
+        #[arg(long, default_value_t = false)]
+        passphrase: bool,
+        /// Number of threads (default: all cores)
+        #[arg(long)]
+        threads: Option<usize>,
+    },
+    /// Search a vanity address (prefix, suffix or regex)
+    #[command(arg_required_else_help = true)]
+    Vanity {
+        /// Keychain name
+        #[arg(required = true)]
+        name: String,
+        /// Address type
+        #[arg(long, value_enum, default_value_t = CliPurpose::BIP84)]
+        purpose: CliPurpose,
+        /// Account number
+        #[arg(long, default_value_t = 0)]
+        account: u32,
+        /// Change addresses
+        #[arg(long, default_value_t = false, action = ArgAction::Set)]
+        change: bool,
+        /// Address prefix (for bech32 addresses `bc1q`/`bc1p` can be omitted)
+        #[arg(
+            long,
+            conflicts_with_all = ["suffix", "regex"],
+            required_unless_present_any = ["suffix", "regex"]
+        )]
+        prefix: Option<String>,
+        /// Address suffix
+        #[arg(long, conflicts_with = "regex")]
+        suffix: Option<String>,
+        /// Regex (matched anywhere in the address, use `^`/`$` to anchor it)
+        #[arg(long)]
+        regex: Option<String>,
+        /// Number of threads (default: all cores)
+        #[arg(long)]
+        threads: Option<usize>,
+    },
+    /// Seed XOR (Coldcard compatible)
+    SeedXor {
+        #[command(subcommand)]
+        command: SeedXorCommand,
+    },
+    /// Danger
+    Danger {
+        #[command(subcommand)]
+        command: DangerCommand,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum DeriveCommand {
+    /// BIP39 mnemonic
+    #[command(arg_required_else_help = true)]
+    Mnemonic {
         /// Keychain name
         #[arg(required = true)]
         name: String,
@@ -108,11 +527,108 @@ pub enum AdvancedCommand {
         /// Index (must be between 0 and 2^31 - 1)
         #[arg(required = true)]
         index: Index,
+        /// Mnemonic language
+        #[arg(long, value_enum, default_value_t = CliLanguage::English)]
+        language: CliLanguage,
     },
-    /// Danger
-    Danger {
-        #[command(subcommand)]
-        command: DangerCommand,
+    /// Hex entropy
+    #[command(arg_required_else_help = true)]
+    Hex {
+        /// Keychain name
+        #[arg(required = true)]
+        name: String,
+        /// Number of bytes (must be between 16 and 64)
+        #[arg(required = true)]
+        num_bytes: u32,
+        /// Index (must be between 0 and 2^31 - 1)
+        #[arg(required = true)]
+        index: Index,
+    },
+    /// WIF private key
+    #[command(arg_required_else_help = true)]
+    Wif {
+        /// Keychain name
+        #[arg(required = true)]
+        name: String,
+        /// Index (must be between 0 and 2^31 - 1)
+        #[arg(required = true)]
+        index: Index,
+    },
+    /// Extended private key
+    #[command(arg_required_else_help = true)]
+    Xprv {
+        /// Keychain name
+        #[arg(required = true)]
+        name: String,
+        /// Index (must be between 0 and 2^31 - 1)
+        #[arg(required = true)]
+        index: Index,
+    },
+    /// Base64 password
+    #[command(arg_required_else_help = true)]
+    PwdBase64 {
+        /// Keychain name
+        #[arg(required = true)]
+        name: String,
+        /// Password length (must be between 20 and 86)
+        #[arg(required = true)]
+        length: u32,
+        /// Index (must be between 0 and 2^31 - 1)
+        #[arg(required = true)]
+        index: Index,
+    },
+    /// Base85 password
+    #[command(arg_required_else_help = true)]
+    PwdBase85 {
+        /// Keychain name
+        #[arg(required = true)]
+        name: String,
+        /// Password length (must be between 10 and 80)
+        #[arg(required = true)]
+        length: u32,
+        /// Index (must be between 0 and 2^31 - 1)
+        #[arg(required = true)]
+        index: Index,
+    },
+    /// Dice rolls
+    #[command(arg_required_else_help = true)]
+    Dice {
+        /// Keychain name
+        #[arg(required = true)]
+        name: String,
+        /// Number of sides
+        #[arg(required = true)]
+        sides: u32,
+        /// Number of rolls
+        #[arg(required = true)]
+        rolls: u32,
+        /// Index (must be between 0 and 2^31 - 1)
+        #[arg(required = true)]
+        index: Index,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum SeedXorCommand {
+    /// Split mnemonic into parts
+    #[command(arg_required_else_help = true)]
+    Split {
+        /// Keychain name
+        #[arg(required = true)]
+        name: String,
+        /// Number of parts
+        #[arg(default_value_t = 3)]
+        parts: usize,
+    },
+    /// Restore keychain by combining the parts
+    #[command(arg_required_else_help = true)]
+    Combine {
+        /// Keychain name
+        #[arg(required = true)]
+        name: String,
+        /// Number of parts
+        #[arg(default_value_t = 3)]
+        parts: usize,
     },
 }
 
@@ -125,6 +641,16 @@ pub enum DangerCommand {
         #[arg(required = true)]
         name: String,
     },
+    /// Export extended private key and WIF at any derivation path
+    #[command(arg_required_else_help = true)]
+    ExportPrivateKey {
+        /// Keychain name
+        #[arg(required = true)]
+        name: String,
+        /// Derivation path (ex. `m/0'/0'/0'`)
+        #[arg(required = true)]
+        path: DerivationPath,
+    },
     /// Delete keychain
     #[command(arg_required_else_help = true)]
     Wipe {
@@ -166,6 +692,54 @@ pub enum ExportTypes {
         /// Account number
         #[arg(default_value_t = 0)]
         account: u32,
+        /// Export multipath descriptors (`/<0;1>/*`, BIP389)
+        #[arg(long)]
+        multipath: bool,
+    },
+    /// Export BIP48 multisig key (with origin), to share with cosigners
+    #[command(arg_required_else_help = true)]
+    MultisigKey {
+        /// Keychain name
+        #[arg(required = true)]
+        name: String,
+        /// Script type
+        #[arg(long, value_enum, default_value_t = CliScriptType::P2wsh)]
+        script: CliScriptType,
+        /// Account number
+        #[arg(long, default_value_t = 0)]
+        account: u32,
+        /// Export as SLIP-132 extended key (`Ypub`/`Zpub`, `Upub`/`Vpub` on testnet; P2SH-P2WSH and P2WSH only)
+        #[arg(long)]
+        slip132: bool,
+    },
+    /// Export blinded BIP48 multisig key (with the full blinded origin), to share with a collaborative custody provider
+    #[command(arg_required_else_help = true)]
+    BlindedKey {
+        /// Keychain name
+        #[arg(required = true)]
+        name: String,
+        /// Label of the blinded key (the same label exports the same key)
+        #[arg(long, required = true)]
+        label: String,
+        /// Script type
+        #[arg(long, value_enum, default_value_t = CliScriptType::P2wsh)]
+        script: CliScriptType,
+        /// Account number
+        #[arg(long, default_value_t = 0)]
+        account: u32,
+    },
+    /// Export extended public key (with origin) at any derivation path
+    #[command(arg_required_else_help = true)]
+    Xpub {
+        /// Keychain name
+        #[arg(required = true)]
+        name: String,
+        /// Derivation path (ex. `m/45'/0'/0'`)
+        #[arg(required = true)]
+        path: DerivationPath,
+        /// Also print the SLIP-132 encoding for this address type
+        #[arg(long, value_enum)]
+        slip132: Option<CliPurpose>,
     },
     /// Export Bitcoin Core descriptors
     #[command(arg_required_else_help = true)]
@@ -176,6 +750,9 @@ pub enum ExportTypes {
         /// Account number
         #[arg(default_value_t = 0)]
         account: u32,
+        /// Export multipath descriptors (`/<0;1>/*`, BIP389)
+        #[arg(long)]
+        multipath: bool,
     },
     /// Export Electrum file
     #[command(arg_required_else_help = true)]
@@ -3,22 +3,45 @@
 
 use std::path::PathBuf;
 use std::str::FromStr;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
 
 use clap::Parser;
 use console::Term;
+use keechain_core::bdk::miniscript::DescriptorPublicKey;
+use keechain_core::bips::bip129::{DescriptorRecord, KeyRecord, Token};
+use keechain_core::bips::bip32::ExtendedPrivKey;
 use keechain_core::bips::bip39::Mnemonic;
+use keechain_core::bips::bip85::Application;
 use keechain_core::bitcoin::psbt::PartiallySignedTransaction;
 use keechain_core::bitcoin::secp256k1::Secp256k1;
-use keechain_core::bitcoin::Network;
+use keechain_core::bitcoin::{Address, Network};
+use keechain_core::descriptors::{DerivedAddress, Descriptors};
+use keechain_core::export::coldcard::ColdcardGenericJson;
+use keechain_core::multisig::Cosigner;
+use keechain_core::recovery::address::{AddressOrigin, Bounds};
+use keechain_core::recovery::mnemonic::MnemonicTemplate;
+use keechain_core::recovery::passphrase::PassphraseGenerator;
+use keechain_core::recovery::{self, Target};
+use keechain_core::seeds::electrum::ElectrumMnemonic;
+use keechain_core::seeds::xor;
+use keechain_core::slips::slip132::ToSlip132;
 use keechain_core::util::dir;
-use keechain_core::{BitcoinCore, Electrum, KeeChain, PsbtUtility, Result, Wasabi};
+use keechain_core::vanity::Pattern;
+use keechain_core::{
+    BitcoinCore, Electrum, KeeChain, Passphrase, PsbtUtility, Purpose, Result, SeedKind, Wasabi,
+    WatchOnly,
+};
 
 mod cli;
 mod types;
 mod util;
 
 use self::cli::io;
-use self::cli::{AdvancedCommand, Cli, Command, DangerCommand, ExportTypes, SettingCommand};
+use self::cli::{
+    AdvancedCommand, BsmsCommand, Cli, Command, DangerCommand, DeriveCommand, ExportTypes,
+    MultisigCommand, PassphraseCommand, SeedXorCommand, SettingCommand, WatchOnlyCommand,
+};
 
 fn main() -> Result<()> {
     let args = Cli::parse();
@@ -55,38 +78,69 @@ fn main() -> Result<()> {
 
             println!("\n!!! WRITE DOWN YOUT SEED PHRASE !!!");
             println!("\n################################################################\n");
-            println!("{}", keechain.keychain(password)?.seed.mnemonic());
+            println!("{}", keechain.keychain(password)?.seed.kind());
             println!("\n################################################################\n");
 
             Ok(())
         }
-        Command::Restore { name } => {
+        Command::Restore {
+            name,
+            electrum,
+            raw_seed,
+            xprv,
+        } => {
             KeeChain::restore(
                 keychain_path,
                 name,
                 io::get_password,
                 io::get_confirmation_password,
-                || Ok(Mnemonic::from_str(&io::get_input("Seed")?)?),
+                || {
+                    let seed: String = io::get_input("Seed")?;
+                    if electrum {
+                        Ok(SeedKind::Electrum(ElectrumMnemonic::from_str(&seed)?))
+                    } else if raw_seed {
+                        Ok(SeedKind::from_raw_seed_hex(&seed)?)
+                    } else if xprv {
+                        Ok(SeedKind::from_str(&seed)?)
+                    } else {
+                        Ok(SeedKind::BIP39(Mnemonic::from_str(&seed)?))
+                    }
+                },
                 network,
                 &secp,
             )?;
             Ok(())
         }
         Command::List => {
-            let names = dir::get_keychains_list(keychain_path)?;
+            let names = dir::get_keychains_list(&keychain_path)?;
             for (index, name) in names.iter().enumerate() {
                 println!("{}. {name}", index + 1);
             }
+            let watch_only = dir::get_watch_only_list(&keychain_path)?;
+            for (index, name) in watch_only.iter().enumerate() {
+                println!("{}. {name} (watch-only)", names.len() + index + 1);
+            }
             Ok(())
         }
         Command::Identity { name } => {
-            let keechain = KeeChain::open(keychain_path, name, io::get_password, network, &secp)?;
-            let fingerprint = keechain.identity();
+            let fingerprint = if dir::is_watch_only(&keychain_path, name.clone())? {
+                WatchOnly::open(keychain_path, name, network)?.identity()
+            } else {
+                KeeChain::open(keychain_path, name, io::get_password, network, &secp)?.identity()
+            };
             println!("Fingerprint: {fingerprint}");
             Ok(())
         }
         Command::Export { export_type } => match export_type {
-            ExportTypes::Descriptors { name, account } => {
+            ExportTypes::Descriptors {
+                name,
+                account,
+                multipath,
+            } => {
+                if dir::is_watch_only(&keychain_path, name.clone())? {
+                    let watch_only = WatchOnly::open(keychain_path, name, network)?;
+                    return util::print_descriptors(watch_only.descriptors(), multipath);
+                }
                 let password: String = io::get_password()?;
                 let keechain =
                     KeeChain::open(keychain_path, name, || Ok(password.clone()), network, &secp)?;
@@ -94,22 +148,73 @@ fn main() -> Result<()> {
                     keechain
                         .keychain(password)?
                         .descriptors(network, Some(account), &secp)?;
-                println!("Extenrals:");
-                for desc in descriptors.external().iter() {
-                    println!("- {desc}");
-                }
-                println!("Internals:");
-                for desc in descriptors.internal().iter() {
-                    println!("- {desc}");
+                util::print_descriptors(&descriptors, multipath)
+            }
+            ExportTypes::MultisigKey {
+                name,
+                script,
+                account,
+                slip132,
+            } => {
+                let password: String = io::get_password()?;
+                let keechain =
+                    KeeChain::open(keychain_path, name, || Ok(password.clone()), network, &secp)?;
+                let descriptors =
+                    keechain
+                        .keychain(password)?
+                        .descriptors(network, Some(account), &secp)?;
+                match descriptors.multisig_key(script.into())? {
+                    DescriptorPublicKey::XPub(xkey) if slip132 => match &xkey.origin {
+                        Some((_, path)) => println!("{}", xkey.xkey.to_slip132(path)?),
+                        None => return Err("Key origin not found".into()),
+                    },
+                    key => println!("{key}"),
                 }
                 Ok(())
             }
-            ExportTypes::BitcoinCore { name, account } => {
+            ExportTypes::BlindedKey {
+                name,
+                label,
+                script,
+                account,
+            } => {
+                let password: String = io::get_password()?;
+                let mut keechain =
+                    KeeChain::open(keychain_path, name, || Ok(password.clone()), network, &secp)?;
+                let key =
+                    keechain.blinded_key(password, label, script.into(), Some(account), &secp)?;
+                println!("{}", key.key());
+                Ok(())
+            }
+            ExportTypes::Xpub {
+                name,
+                path,
+                slip132,
+            } => {
                 let password: String = io::get_password()?;
                 let keechain =
                     KeeChain::open(keychain_path, name, || Ok(password.clone()), network, &secp)?;
-                let descriptors =
-                    BitcoinCore::new(&keechain.seed(password)?, network, Some(account), &secp)?;
+                let key: DescriptorPublicKey = keechain
+                    .keychain(password)?
+                    .extended_public_key(network, &path, &secp)?;
+                util::print_extended_public_key(key, slip132.map(Purpose::from), network)?;
+                Ok(())
+            }
+            ExportTypes::BitcoinCore {
+                name,
+                account,
+                multipath,
+            } => {
+                let password: String = io::get_password()?;
+                let keechain =
+                    KeeChain::open(keychain_path, name, || Ok(password.clone()), network, &secp)?;
+                let descriptors = BitcoinCore::new(
+                    &keechain.seed(password)?,
+                    network,
+                    Some(account),
+                    multipath,
+                    &secp,
+                )?;
                 println!("{}", descriptors.to_string());
                 Ok(())
             }
@@ -159,13 +264,16 @@ fn main() -> Result<()> {
             let password: String = io::get_password()?;
             let keechain =
                 KeeChain::open(keychain_path, name, || Ok(password.clone()), network, &secp)?;
-            let seed = &keechain.seed(password)?;
             let mut psbt: PartiallySignedTransaction =
                 PartiallySignedTransaction::from_file(&file)?;
-            let finalized = match descriptor {
-                Some(descriptor) => psbt.sign_with_descriptor(seed, descriptor, network, &secp)?,
-                None => psbt.sign_with_seed(seed, network, &secp)?,
-            };
+            if let Some(review) = keechain.review_multisig(&password, &psbt)? {
+                util::print_multisig_review(review, &psbt, network);
+                if !io::ask("Sign?")? {
+                    return Ok(());
+                }
+            }
+            let finalized =
+                keechain.sign_psbt(password, &mut psbt, descriptor, Vec::new(), &secp)?;
             println!("Signed.");
             let mut renamed_file: PathBuf = file;
             dir::rename_psbt(&mut renamed_file, finalized)?;
@@ -177,23 +285,578 @@ fn main() -> Result<()> {
             }
             Ok(())
         }
+        Command::Addresses {
+            name,
+            purpose,
+            account,
+            wallet,
+            change,
+            from,
+            count,
+            qr,
+        } => {
+            let purpose: Purpose = purpose.into();
+            let addresses = if dir::is_watch_only(&keychain_path, name.clone())? {
+                if wallet.is_some() {
+                    return Err(
+                        "Multisig wallets are not available for watch-only keychains".into(),
+                    );
+                }
+                let watch_only = WatchOnly::open(keychain_path, name, network)?;
+                watch_only
+                    .descriptors()
+                    .addresses(purpose, change, from, count, network)?
+            } else {
+                let password: String = io::get_password()?;
+                let keechain =
+                    KeeChain::open(keychain_path, name, || Ok(password.clone()), network, &secp)?;
+                match wallet {
+                    Some(wallet) => {
+                        keechain.multisig_addresses(password, wallet, change, from, count, &secp)?
+                    }
+                    None => keechain.addresses(
+                        password,
+                        purpose,
+                        Some(account),
+                        change,
+                        from,
+                        count,
+                        &secp,
+                    )?,
+                }
+            };
+            util::print_addresses(addresses, qr)
+        }
+        Command::VerifyAddress {
+            name,
+            address,
+            accounts,
+            indexes,
+            threads,
+        } => {
+            let address: Address = Address::from_str(&address)?.require_network(network)?;
+            let password: String = io::get_password()?;
+            let keechain =
+                KeeChain::open(keychain_path, name, || Ok(password.clone()), network, &secp)?;
+            let bounds = Bounds { accounts, indexes };
+            let found: Option<AddressOrigin> = keechain.verify_address(
+                password,
+                &address,
+                bounds,
+                threads,
+                util::print_progress,
+                &secp,
+            )?;
+            println!();
+            match found {
+                Some(origin) => {
+                    if let Some(wallet) = origin.wallet {
+                        println!("Multisig wallet: {wallet}");
+                    }
+                    println!("Purpose: BIP{}", origin.purpose.as_u32());
+                    println!(
+                        "Chain: {}",
+                        if origin.change { "change" } else { "receive" }
+                    );
+                    println!("Index: {}", origin.index);
+                    println!("Path: {}", origin.path);
+                }
+                None => println!(
+                    "Not found within bounds ({accounts} accounts, {indexes} indexes per chain)"
+                ),
+            }
+            Ok(())
+        }
+        Command::WatchOnly { command } => match command {
+            WatchOnlyCommand::Import {
+                name,
+                coldcard,
+                descriptor,
+                key,
+            } => {
+                let watch_only = match (coldcard, key) {
+                    (Some(path), _) => WatchOnly::from_coldcard(
+                        keychain_path,
+                        name,
+                        &ColdcardGenericJson::from_file(path)?,
+                        network,
+                    )?,
+                    (None, Some(key)) => WatchOnly::from_key(keychain_path, name, key, network)?,
+                    (None, None) => WatchOnly::new(
+                        keychain_path,
+                        name,
+                        Descriptors::from_descriptors(descriptor)?,
+                        network,
+                    )?,
+                };
+                println!("Fingerprint: {}", watch_only.identity());
+                util::print_descriptors(watch_only.descriptors(), false)
+            }
+            WatchOnlyCommand::Addresses {
+                name,
+                purpose,
+                change,
+                from,
+                count,
+            } => {
+                let watch_only = WatchOnly::open(keychain_path, name, network)?;
+                let purpose: Purpose = purpose.into();
+                for index in from..from.saturating_add(count) {
+                    let address = watch_only.address(purpose, change, index)?;
+                    println!("{index}: {address}");
+                }
+                Ok(())
+            }
+            WatchOnlyCommand::Review { name, file } => {
+                let watch_only = WatchOnly::open(keychain_path, name, network)?;
+                let psbt = PartiallySignedTransaction::from_file(file)?;
+                util::print_psbt_review(watch_only.review_psbt(&psbt));
+                Ok(())
+            }
+            WatchOnlyCommand::Remove { name } => {
+                if io::ask("Are you sure?")? {
+                    WatchOnly::open(keychain_path, name, network)?.remove()?;
+                } else {
+                    println!("Aborted.");
+                }
+                Ok(())
+            }
+        },
+        Command::Multisig { command } => match command {
+            MultisigCommand::Create {
+                name,
+                wallet,
+                script,
+                account,
+                threshold,
+                cosigner,
+            } => {
+                let mut cosigners: Vec<Cosigner> = Vec::with_capacity(cosigner.len());
+                for cosigner in cosigner.iter() {
+                    cosigners.push(util::parse_cosigner(cosigner)?);
+                }
+                let password: String = io::get_password()?;
+                let mut keechain =
+                    KeeChain::open(keychain_path, name, || Ok(password.clone()), network, &secp)?;
+                let wallet = keechain.create_multisig_wallet(
+                    password,
+                    wallet,
+                    script.into(),
+                    Some(account),
+                    threshold,
+                    &cosigners,
+                    &secp,
+                )?;
+                util::print_multisig_wallet(&wallet, network)
+            }
+            MultisigCommand::Policy {
+                name,
+                policy,
+                script,
+                account,
+                path,
+                key,
+                wallet,
+            } => {
+                let mut cosigners: Vec<(String, Cosigner)> = Vec::with_capacity(key.len());
+                for key in key.iter() {
+                    let (alias, cosigner) = key
+                        .split_once('=')
+                        .ok_or_else(|| format!("Invalid key {key}: expected `alias=cosigner`"))?;
+                    let alias: &str = alias.trim().trim_start_matches('@');
+                    cosigners.push((alias.to_string(), util::parse_cosigner(cosigner)?));
+                }
+                let password: String = io::get_password()?;
+                let mut keechain =
+                    KeeChain::open(keychain_path, name, || Ok(password.clone()), network, &secp)?;
+                let compiled = keechain.compile_policy(
+                    &password,
+                    policy,
+                    script.into(),
+                    Some(account),
+                    path,
+                    &cosigners,
+                    &secp,
+                )?;
+                util::print_compiled_policy(&compiled);
+                if let Some(wallet) = wallet {
+                    println!();
+                    let wallet = keechain.save_policy_wallet(password, wallet, &compiled)?;
+                    util::print_multisig_wallet(&wallet, network)?;
+                }
+                Ok(())
+            }
+            MultisigCommand::Bsms { command } => match command {
+                BsmsCommand::Token { long } => {
+                    let token: Token = if long {
+                        Token::random_128()
+                    } else {
+                        Token::random_64()
+                    };
+                    println!("{token}");
+                    Ok(())
+                }
+                BsmsCommand::KeyRecord {
+                    name,
+                    script,
+                    account,
+                    token,
+                    description,
+                } => {
+                    let password: String = io::get_password()?;
+                    let keechain = KeeChain::open(
+                        keychain_path,
+                        name,
+                        || Ok(password.clone()),
+                        network,
+                        &secp,
+                    )?;
+                    let record: KeyRecord = keechain.bsms_key_record(
+                        password,
+                        script.into(),
+                        Some(account),
+                        token,
+                        description,
+                        &secp,
+                    )?;
+                    println!("{}", record.export());
+                    Ok(())
+                }
+                BsmsCommand::Coordinator {
+                    token,
+                    script,
+                    threshold,
+                    record,
+                } => {
+                    let mut records: Vec<KeyRecord> = Vec::with_capacity(record.len());
+                    for record in record.iter() {
+                        let record: String = util::read_record(record)?;
+                        records.push(KeyRecord::parse(record, &token, &secp)?);
+                    }
+                    for record in records.iter() {
+                        println!("Verified: {} {}", record.key(), record.description());
+                    }
+                    println!();
+                    let record = DescriptorRecord::from_key_records(
+                        token,
+                        &records,
+                        script.into(),
+                        threshold,
+                        network,
+                    )?;
+                    println!("{}", record.export());
+                    Ok(())
+                }
+                BsmsCommand::Register {
+                    name,
+                    record,
+                    wallet,
+                    token,
+                } => {
+                    let record: String = util::read_record(&record)?;
+                    let record = DescriptorRecord::parse(record, &token, network)?;
+                    let password: String = io::get_password()?;
+                    let mut keechain = KeeChain::open(
+                        keychain_path,
+                        name,
+                        || Ok(password.clone()),
+                        network,
+                        &secp,
+                    )?;
+                    let wallet = keechain.bsms_register(password, wallet, &record)?;
+                    util::print_multisig_wallet(&wallet, network)
+                }
+            },
+            MultisigCommand::List { name } => {
+                let password: String = io::get_password()?;
+                let keechain =
+                    KeeChain::open(keychain_path, name, || Ok(password.clone()), network, &secp)?;
+                let wallets = keechain.multisig_wallets(password)?;
+                if wallets.is_empty() {
+                    println!("No multisig wallets");
+                }
+                for wallet in wallets.iter() {
+                    util::print_multisig_wallet(wallet, network)?;
+                    println!();
+                }
+                Ok(())
+            }
+            MultisigCommand::Remove { name, wallet } => {
+                let password: String = io::get_password()?;
+                let mut keechain =
+                    KeeChain::open(keychain_path, name, || Ok(password.clone()), network, &secp)?;
+                if io::ask("Are you sure?")? {
+                    keechain.remove_multisig_wallet(password, wallet)?;
+                } else {
+                    println!("Aborted.");
+                }
+                Ok(())
+            }
+        },
+        Command::Passphrase { command } => match command {
+            PassphraseCommand::List { name, show } => {
+                let password: String = io::get_password()?;
+                let keechain =
+                    KeeChain::open(keychain_path, name, || Ok(password.clone()), network, &secp)?;
+                let passphrases: Vec<Passphrase> = keechain.passphrases(password)?;
+                if passphrases.is_empty() {
+                    println!("No saved passphrases");
+                }
+                for (index, passphrase) in passphrases.iter().enumerate() {
+                    if show {
+                        println!(
+                            "{}. {} ({}): {}",
+                            index + 1,
+                            passphrase.label(),
+                            passphrase.fingerprint(),
+                            passphrase.passphrase()
+                        );
+                    } else {
+                        println!(
+                            "{}. {} ({})",
+                            index + 1,
+                            passphrase.label(),
+                            passphrase.fingerprint()
+                        );
+                    }
+                }
+                Ok(())
+            }
+            PassphraseCommand::Add { name, label } => {
+                let password: String = io::get_password()?;
+                let mut keechain =
+                    KeeChain::open(keychain_path, name, || Ok(password.clone()), network, &secp)?;
+                let passphrase: Passphrase =
+                    keechain.add_passphrase(password, label, io::get_new_passphrase()?, &secp)?;
+                println!(
+                    "Saved '{}' (fingerprint: {})",
+                    passphrase.label(),
+                    passphrase.fingerprint()
+                );
+                Ok(())
+            }
+            PassphraseCommand::Remove { name, label } => {
+                let password: String = io::get_password()?;
+                let mut keechain =
+                    KeeChain::open(keychain_path, name, || Ok(password.clone()), network, &secp)?;
+                Ok(keechain.remove_passphrase(password, label)?)
+            }
+            PassphraseCommand::Apply { name } => {
+                let password: String = io::get_password()?;
+                let mut keechain =
+                    KeeChain::open(keychain_path, name, || Ok(password.clone()), network, &secp)?;
+                let saved: Option<Passphrase> =
+                    keechain.apply_passphrase(password, Some(io::get_passphrase()?), &secp)?;
+                println!("Fingerprint: {}", keechain.identity());
+                match saved {
+                    Some(passphrase) => println!("Matches saved passphrase '{}'", passphrase.label()),
+                    None => println!(
+                        "WARNING: unknown fingerprint, it doesn't match any saved passphrase (typo?)"
+                    ),
+                }
+                Ok(())
+            }
+            PassphraseCommand::Search {
+                name,
+                target,
+                wordlist,
+                mask,
+                case,
+                typos,
+                purpose,
+                gap,
+                threads,
+                label,
+            } => {
+                let password: String = io::get_password()?;
+                let mut keechain =
+                    KeeChain::open(keychain_path, name, || Ok(password.clone()), network, &secp)?;
+                let target: Target = util::parse_target(&target, purpose.into(), gap, network)?;
+                let generator: PassphraseGenerator = match wordlist {
+                    Some(path) => PassphraseGenerator::wordlist(util::read_lines(path)?),
+                    None => PassphraseGenerator::mask(mask.unwrap_or_default())?,
+                }
+                .case_mutations(case)
+                .typo_mutations(typos);
+                println!("Candidates: {}", generator.len());
+                let found: Option<String> = keechain.search_passphrase(
+                    password.clone(),
+                    generator,
+                    target,
+                    threads,
+                    util::print_progress,
+                    &secp,
+                )?;
+                println!();
+                match found {
+                    Some(passphrase) => {
+                        println!("Passphrase: {passphrase}");
+                        println!("Fingerprint: {}", keechain.identity());
+                        if let Some(label) = label {
+                            keechain.add_passphrase(password, label, passphrase, &secp)?;
+                        }
+                    }
+                    None => println!("No match found"),
+                }
+                Ok(())
+            }
+        },
         Command::Advanced { command } => match command {
-            AdvancedCommand::Derive {
+            AdvancedCommand::Derive { command } => {
+                let (name, application, index) = match command {
+                    DeriveCommand::Mnemonic {
+                        name,
+                        word_count,
+                        index,
+                        language,
+                    } => (
+                        name,
+                        Application::Mnemonic {
+                            language: language.into(),
+                            word_count: word_count.into(),
+                        },
+                        index,
+                    ),
+                    DeriveCommand::Hex {
+                        name,
+                        num_bytes,
+                        index,
+                    } => (name, Application::Hex { num_bytes }, index),
+                    DeriveCommand::Wif { name, index } => (name, Application::Wif, index),
+                    DeriveCommand::Xprv { name, index } => (name, Application::Xprv, index),
+                    DeriveCommand::PwdBase64 {
+                        name,
+                        length,
+                        index,
+                    } => (name, Application::PwdBase64 { length }, index),
+                    DeriveCommand::PwdBase85 {
+                        name,
+                        length,
+                        index,
+                    } => (name, Application::PwdBase85 { length }, index),
+                    DeriveCommand::Dice {
+                        name,
+                        sides,
+                        rolls,
+                        index,
+                    } => (name, Application::Dice { sides, rolls }, index),
+                };
+                let password: String = io::get_password()?;
+                let keechain =
+                    KeeChain::open(keychain_path, name, || Ok(password.clone()), network, &secp)?;
+                let derived: String = keechain.bip85(password, application, index, &secp)?;
+                println!("{application}: {derived}");
+                Ok(())
+            }
+            AdvancedCommand::RecoverMnemonic {
+                mnemonic,
+                target,
+                purpose,
+                gap,
+                passphrase,
+                threads,
+            } => {
+                let template = MnemonicTemplate::from_str(&mnemonic)?;
+                let target: Target = util::parse_target(&target, purpose.into(), gap, network)?;
+                let passphrase: Option<String> = if passphrase {
+                    Some(io::get_passphrase()?)
+                } else {
+                    None
+                };
+                println!("Combinations: {}", template.combinations());
+                let result: Option<Mnemonic> = recovery::mnemonic::recover(
+                    template,
+                    passphrase,
+                    target,
+                    network,
+                    threads,
+                    util::print_progress,
+                )?;
+                println!();
+                match result {
+                    Some(mnemonic) => println!("Mnemonic: {mnemonic}"),
+                    None => println!("No match found"),
+                }
+                Ok(())
+            }
+            AdvancedCommand::Vanity {
                 name,
-                word_count,
-                index,
+                purpose,
+                account,
+                change,
+                prefix,
+                suffix,
+                regex,
+                threads,
             } => {
+                let pattern: Pattern = if let Some(prefix) = prefix {
+                    Pattern::Prefix(prefix)
+                } else if let Some(suffix) = suffix {
+                    Pattern::Suffix(suffix)
+                } else {
+                    Pattern::regex(regex.unwrap_or_default())?
+                };
                 let password: String = io::get_password()?;
                 let keechain =
                     KeeChain::open(keychain_path, name, || Ok(password.clone()), network, &secp)?;
-                let mnemonic: Mnemonic = keechain.keychain(password)?.deterministic_entropy(
-                    word_count.into(),
-                    index,
+                let found: Option<DerivedAddress> = keechain.vanity_address(
+                    password,
+                    purpose.into(),
+                    Some(account),
+                    change,
+                    pattern,
+                    threads,
+                    Arc::new(AtomicBool::new(false)),
+                    util::print_vanity_progress,
                     &secp,
                 )?;
-                println!("Mnemonic: {mnemonic}");
+                println!();
+                match found {
+                    Some(derived) => {
+                        println!("Index: {}", derived.index);
+                        println!("Path: {}", derived.path);
+                        println!("Address: {}", derived.address);
+                    }
+                    None => println!("No match found"),
+                }
                 Ok(())
             }
+            AdvancedCommand::SeedXor { command } => match command {
+                SeedXorCommand::Split { name, parts } => {
+                    let password: String = io::get_password()?;
+                    let keechain = KeeChain::open(
+                        keychain_path,
+                        name,
+                        || Ok(password.clone()),
+                        network,
+                        &secp,
+                    )?;
+                    let parts: Vec<Mnemonic> = keechain.seed_xor(password, parts)?;
+                    for (index, part) in parts.iter().enumerate() {
+                        println!("Part {}: {part}", index + 1);
+                    }
+                    Ok(())
+                }
+                SeedXorCommand::Combine { name, parts } => {
+                    KeeChain::restore(
+                        keychain_path,
+                        name,
+                        io::get_password,
+                        io::get_confirmation_password,
+                        || {
+                            let mut mnemonics: Vec<Mnemonic> = Vec::with_capacity(parts);
+                            for index in 1..=parts {
+                                let part: String = io::get_input(format!("Part {index}"))?;
+                                mnemonics.push(Mnemonic::from_str(&part)?);
+                            }
+                            Ok(SeedKind::BIP39(xor::combine(&mnemonics)?))
+                        },
+                        network,
+                        &secp,
+                    )?;
+                    Ok(())
+                }
+            },
             AdvancedCommand::Danger { command } => match command {
                 DangerCommand::ViewSecrets { name } => {
                     let password: String = io::get_password()?;
@@ -208,6 +871,21 @@ fn main() -> Result<()> {
                     util::print_secrets(secrets);
                     Ok(())
                 }
+                DangerCommand::ExportPrivateKey { name, path } => {
+                    let password: String = io::get_password()?;
+                    let keechain = KeeChain::open(
+                        keychain_path,
+                        name,
+                        || Ok(password.clone()),
+                        network,
+                        &secp,
+                    )?;
+                    let xprv: ExtendedPrivKey = keechain
+                        .keychain(password)?
+                        .extended_private_key(network, &path, &secp)?;
+                    util::print_extended_private_key(path, xprv);
+                    Ok(())
+                }
                 DangerCommand::Wipe { name } => {
                     if io::ask("Are you really sure? This action is permanent!")? && io::ask("Again, are you really sure? THIS ACTION IS PERMANENT AND YOU MAY LOSE ALL YOUR FUNDS!")? {
                         let keechain = KeeChain::open(keychain_path, name, io::get_password, network, &secp)?;
@@ -2,8 +2,10 @@
 // Distributed under the MIT software license
 
 use clap::ValueEnum;
+use keechain_core::bips::bip39::Language;
+use keechain_core::bips::bip48::ScriptType;
 use keechain_core::bitcoin::Network;
-use keechain_core::{ElectrumSupportedScripts, WordCount};
+use keechain_core::{ElectrumSupportedScripts, Purpose, WordCount};
 
 #[derive(Debug, Clone, ValueEnum)]
 pub enum CliNetwork {
@@ -60,3 +62,77 @@ impl From<CliWordCount> for WordCount {
         }
     }
 }
+
+#[derive(Debug, Clone, ValueEnum)]
+pub enum CliPurpose {
+    /// P2PKH
+    BIP44,
+    /// P2SH-WPKH
+    BIP49,
+    /// P2WPKH
+    BIP84,
+    /// P2TR
+    BIP86,
+}
+
+impl From<CliPurpose> for Purpose {
+    fn from(value: CliPurpose) -> Self {
+        match value {
+            CliPurpose::BIP44 => Self::BIP44,
+            CliPurpose::BIP49 => Self::BIP49,
+            CliPurpose::BIP84 => Self::BIP84,
+            CliPurpose::BIP86 => Self::BIP86,
+        }
+    }
+}
+
+/// BIP48 script types
+#[derive(Debug, Clone, ValueEnum)]
+pub enum CliScriptType {
+    /// P2SH-P2WSH
+    P2shP2wsh,
+    /// P2WSH
+    P2wsh,
+    /// P2TR
+    P2tr,
+}
+
+impl From<CliScriptType> for ScriptType {
+    fn from(value: CliScriptType) -> Self {
+        match value {
+            CliScriptType::P2shP2wsh => Self::P2SHWSH,
+            CliScriptType::P2wsh => Self::P2WSH,
+            CliScriptType::P2tr => Self::P2TR,
+        }
+    }
+}
+
+/// BIP39 languages with a BIP85 code
+#[derive(Debug, Clone, ValueEnum)]
+pub enum CliLanguage {
+    English,
+    Japanese,
+    Korean,
+    Spanish,
+    ChineseSimplified,
+    ChineseTraditional,
+    French,
+    Italian,
+    Czech,
+}
+
+impl From<CliLanguage> for Language {
+    fn from(value: CliLanguage) -> Self {
+        match value {
+            CliLanguage::English => Self::English,
+            CliLanguage::Japanese => Self::Japanese,
+            CliLanguage::Korean => Self::Korean,
+            CliLanguage::Spanish => Self::Spanish,
+            CliLanguage::ChineseSimplified => Self::SimplifiedChinese,
+            CliLanguage::ChineseTraditional => Self::TraditionalChinese,
+            CliLanguage::French => Self::French,
+            CliLanguage::Italian => Self::Italian,
+            CliLanguage::Czech => Self::Czech,
+        }
+    }
+}
@@ -1,6 +1,12 @@
 // Copyright (c) 2022-2023 Yuki Kishimoto
 // Distributed under the MIT software license
 
+use std::time::Duration;
+
+use qrcode::render::unicode::Dense1x2;
+use qrcode::types::QrError;
+use qrcode::QrCode;
+
 pub fn number(num: usize) -> String {
     let mut number: String = num.to_string();
 
@@ -19,6 +25,41 @@ pub fn number(num: usize) -> String {
     number
 }
 
+/// Format duration as `1d 2h 3m 4s` (without the leading zero units)
+pub fn duration(duration: Duration) -> String {
+    let seconds: u64 = duration.as_secs();
+    let units: [(u64, &str); 4] = [
+        (seconds / 86400, "d"),
+        (seconds % 86400 / 3600, "h"),
+        (seconds % 3600 / 60, "m"),
+        (seconds % 60, "s"),
+    ];
+    let parts: Vec<String> = units
+        .iter()
+        .skip_while(|(value, _)| *value == 0)
+        .map(|(value, unit)| format!("{value}{unit}"))
+        .collect();
+    if parts.is_empty() {
+        String::from("0s")
+    } else {
+        parts.join(" ")
+    }
+}
+
+/// Render a QR code with unicode half blocks (two modules per char)
+pub fn qr_code<T>(data: T) -> Result<String, QrError>
+where
+    T: AsRef<[u8]>,
+{
+    let code = QrCode::new(data)?;
+    Ok(code
+        .render::<Dense1x2>()
+        .dark_color(Dense1x2::Light)
+        .light_color(Dense1x2::Dark)
+        .quiet_zone(true)
+        .build())
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -32,4 +73,14 @@ mod test {
         assert_eq!(number(1000000), "1 000 000".to_string());
         assert_eq!(number(1000000000), "1 000 000 000".to_string());
     }
+
+    #[test]
+    fn format_duration() {
+        assert_eq!(duration(Duration::from_millis(500)), "0s".to_string());
+        assert_eq!(duration(Duration::from_secs(75)), "1m 15s".to_string());
+        assert_eq!(
+            duration(Duration::from_secs(90061)),
+            "1d 1h 1m 1s".to_string()
+        );
+    }
 }
@@ -1,9 +1,24 @@
 // Copyright (c) 2022-2023 Yuki Kishimoto
 // Distributed under the MIT software license
 
+use std::fs::File;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use keechain_core::bdk::miniscript::DescriptorPublicKey;
+use keechain_core::bips::bip32::{DerivationPath, ExtendedPrivKey, ExtendedPubKey, Fingerprint};
 use keechain_core::bitcoin::psbt::PartiallySignedTransaction;
 use keechain_core::bitcoin::{Address, Network, TxOut};
-use keechain_core::types::Secrets;
+use keechain_core::descriptors::{DerivedAddress, Descriptors};
+use keechain_core::multisig::Cosigner;
+use keechain_core::policy::CompiledPolicy;
+use keechain_core::psbt::{MultisigReview, PsbtReview};
+use keechain_core::recovery::Target;
+use keechain_core::slips::slip132;
+use keechain_core::types::{Secrets, SeedKind};
+use keechain_core::vanity::Progress as VanityProgress;
+use keechain_core::{MultisigWallet, Purpose, Result};
 use prettytable::format::FormatBuilder;
 use prettytable::{row, Table};
 
@@ -12,17 +27,30 @@ mod format;
 pub fn print_secrets(secrets: Secrets) {
     let mut table = Table::new();
 
-    table.add_row(row![
-        format!("Entropy ({} bits)", secrets.entropy.len() / 2 * 8),
-        secrets.entropy
-    ]);
-    table.add_row(row!["Mnemonic (BIP39)", secrets.mnemonic]);
+    let kind: String = secrets.mnemonic.name();
+
+    if let Some(entropy) = &secrets.entropy {
+        table.add_row(row![
+            format!("Entropy ({} bits)", entropy.len() / 2 * 8),
+            entropy
+        ]);
+    }
+
+    if secrets.mnemonic.is_mnemonic() {
+        table.add_row(row![format!("Mnemonic ({kind})"), secrets.mnemonic]);
+    }
+
+    if let SeedKind::Aezeed(seed) = &secrets.mnemonic {
+        table.add_row(row![format!("Birthday ({kind})"), seed.birthday_date()]);
+    }
 
     if let Some(passphrase) = &secrets.passphrase {
-        table.add_row(row!["Passphrase (BIP39)", passphrase]);
+        table.add_row(row![format!("Passphrase ({kind})"), passphrase]);
     }
 
-    table.add_row(row!["Seed HEX (BIP39)", secrets.seed_hex]);
+    if let Some(seed_hex) = &secrets.seed_hex {
+        table.add_row(row![format!("Seed HEX ({kind})"), seed_hex]);
+    }
     table.add_row(row!["Network", secrets.network]);
     table.add_row(row!["Root Key (BIP32)", secrets.root_key]);
     table.add_row(row!["Fingerprint (BIP32)", secrets.fingerprint]);
@@ -82,3 +110,268 @@ pub fn print_psbt(psbt: PartiallySignedTransaction, network: Network) {
 
     table.printstd();
 }
+
+pub fn print_descriptors(descriptors: &Descriptors, multipath: bool) -> Result<()> {
+    if multipath {
+        println!("Multipath:");
+        for desc in descriptors.multipath()?.iter() {
+            println!("- {desc}");
+        }
+    } else {
+        println!("Externals:");
+        for desc in descriptors.external().iter() {
+            println!("- {desc}");
+        }
+        println!("Internals:");
+        for desc in descriptors.internal().iter() {
+            println!("- {desc}");
+        }
+    }
+    let multisig = descriptors.multisig_keys();
+    if !multisig.is_empty() {
+        println!("Multisig keys (BIP48):");
+        for (script, key) in multisig.iter() {
+            println!("- {script}: {key}");
+        }
+    }
+    Ok(())
+}
+
+pub fn print_extended_public_key(
+    key: DescriptorPublicKey,
+    slip132: Option<Purpose>,
+    network: Network,
+) -> Result<()> {
+    let mut table = Table::new();
+    if let DescriptorPublicKey::XPub(xkey) = &key {
+        if let Some((fingerprint, path)) = &xkey.origin {
+            table.add_row(row!["Fingerprint (BIP32)", fingerprint]);
+            table.add_row(row!["Path", path]);
+        }
+        table.add_row(row!["Extended public key", xkey.xkey]);
+        if let Some(purpose) = slip132 {
+            let encoded: String =
+                slip132::encode(&xkey.xkey, purpose.as_u32(), network == Network::Bitcoin)?;
+            table.add_row(row![format!("SLIP-132 ({purpose:?})"), encoded]);
+        }
+    }
+    table.add_row(row!["Key (with origin)", key]);
+    table.printstd();
+    Ok(())
+}
+
+pub fn print_extended_private_key(path: DerivationPath, xprv: ExtendedPrivKey) {
+    let mut table = Table::new();
+    table.add_row(row!["Path", path]);
+    table.add_row(row!["Extended private key", xprv]);
+    table.add_row(row!["WIF", xprv.to_priv().to_wif()]);
+    table.printstd();
+}
+
+pub fn print_addresses(addresses: Vec<DerivedAddress>, qr: bool) -> Result<()> {
+    if qr {
+        for derived in addresses.into_iter() {
+            println!("{}: {} ({})", derived.index, derived.address, derived.path);
+            println!("{}", format::qr_code(derived.address.to_qr_uri())?);
+        }
+    } else {
+        let mut table = Table::new();
+        table.set_titles(row!["#", "Path", "Address"]);
+        for derived in addresses.into_iter() {
+            table.add_row(row![derived.index, derived.path, derived.address]);
+        }
+        table.printstd();
+    }
+    Ok(())
+}
+
+pub fn print_multisig_wallet(wallet: &MultisigWallet, network: Network) -> Result<()> {
+    match wallet.policy() {
+        Some(policy) => println!("{} ({}, policy {policy})", wallet.name(), wallet.script()),
+        None => println!(
+            "{} ({}, {} of {})",
+            wallet.name(),
+            wallet.script(),
+            wallet.threshold(),
+            wallet.keys().len()
+        ),
+    }
+    println!("Cosigners:");
+    for key in wallet.keys().iter() {
+        println!("- {key}");
+    }
+    println!("External: {}", wallet.descriptor(false)?);
+    println!("Internal: {}", wallet.descriptor(true)?);
+    println!("First address: {}", wallet.address(false, 0, network)?);
+    Ok(())
+}
+
+pub fn print_compiled_policy(compiled: &CompiledPolicy) {
+    println!("Policy: {}", compiled.policy);
+    println!("Keys:");
+    for (alias, key) in compiled.keys.iter() {
+        println!("- @{alias}: {key}");
+    }
+    println!("Descriptor: {}", compiled.descriptor);
+
+    let mut table = Table::new();
+    table.set_titles(row![
+        "#",
+        "Conditions",
+        "Spend",
+        "Script size",
+        "Witness size (WU)"
+    ]);
+    for (index, path) in compiled.paths.iter().enumerate() {
+        table.add_row(row![
+            index + 1,
+            path.conditions.join(", "),
+            if path.key_path { "key path" } else { "script" },
+            path.script_size,
+            path.witness_size
+        ]);
+    }
+    table.printstd();
+}
+
+pub fn print_psbt_review(review: PsbtReview) {
+    let mut table = Table::new();
+
+    table.set_titles(row!["#", "Input", "Amount", "Owner"]);
+    for (index, input) in review.inputs.iter().enumerate() {
+        table.add_row(row![
+            index,
+            input.outpoint,
+            match input.amount {
+                Some(amount) => format!("{} sat", format::number(amount as usize)),
+                None => String::from("unknown"),
+            },
+            input.ownership
+        ]);
+    }
+    table.printstd();
+
+    let mut table = Table::new();
+    table.set_titles(row!["#", "Output", "Amount", "Owner"]);
+    for (index, output) in review.outputs.iter().enumerate() {
+        table.add_row(row![
+            index,
+            match &output.address {
+                Some(address) => address.to_string(),
+                None => String::from("unknown script"),
+            },
+            format!("{} sat", format::number(output.amount as usize)),
+            output.ownership
+        ]);
+    }
+    table.printstd();
+
+    println!(
+        "Spending: {} sat",
+        format::number(review.spending() as usize)
+    );
+    match review.fee() {
+        Some(fee) => println!("Fee: {} sat", format::number(fee as usize)),
+        None => println!("Fee: unknown"),
+    }
+}
+
+pub fn print_multisig_review(
+    review: MultisigReview,
+    psbt: &PartiallySignedTransaction,
+    network: Network,
+) {
+    println!("Wallet: {}", review.wallet.name());
+
+    let mut table = Table::new();
+    table.set_titles(row!["#", "Output", "Amount", "Owner"]);
+    for (index, txout) in psbt.unsigned_tx.output.iter().enumerate() {
+        table.add_row(row![
+            index,
+            match Address::from_script(&txout.script_pubkey, network) {
+                Ok(address) => address.to_string(),
+                Err(_) => String::from("unknown script"),
+            },
+            format!("{} sat", format::number(txout.value as usize)),
+            if review.is_change(index) {
+                "change"
+            } else {
+                "external"
+            }
+        ]);
+    }
+    table.printstd();
+}
+
+/// Parse a cosigner: file path or inline Coldcard generic JSON, descriptor or key expression
+pub fn parse_cosigner(cosigner: &str) -> Result<Cosigner> {
+    let path = PathBuf::from(cosigner);
+    if path.is_file() {
+        Ok(Cosigner::from_file(path)?)
+    } else {
+        Ok(Cosigner::from_str(cosigner)?)
+    }
+}
+
+/// Read a BIP129 record: file path or inline record
+pub fn read_record(record: &str) -> Result<String> {
+    let path = PathBuf::from(record);
+    if path.is_file() {
+        Ok(std::fs::read_to_string(path)?)
+    } else {
+        Ok(record.to_string())
+    }
+}
+
+/// Parse a recovery target: root fingerprint, xpub or address
+pub fn parse_target(target: &str, purpose: Purpose, gap: u32, network: Network) -> Result<Target> {
+    if let Ok(fingerprint) = Fingerprint::from_str(target) {
+        return Ok(Target::Fingerprint(fingerprint));
+    }
+    if let Ok(xpub) = ExtendedPubKey::from_str(target) {
+        return Ok(Target::Xpub(xpub));
+    }
+    Ok(Target::Address {
+        address: Address::from_str(target)?.require_network(network)?,
+        purpose,
+        gap,
+    })
+}
+
+pub fn print_progress(tested: u64, total: u64) {
+    let percentage: f64 = if total == 0 {
+        100.0
+    } else {
+        tested as f64 / total as f64 * 100.0
+    };
+    print!("\rTested {tested}/{total} ({percentage:.2}%)");
+    let _ = std::io::stdout().flush();
+}
+
+pub fn print_vanity_progress(progress: VanityProgress) {
+    let expected: String = match progress.expected {
+        Some(expected) => format::duration(expected),
+        None => String::from("unknown"),
+    };
+    print!(
+        "\rTested {} ({} addr/s), elapsed {}, expected {expected}    ",
+        format::number(progress.tested as usize),
+        format::number(progress.rate as usize),
+        format::duration(progress.elapsed)
+    );
+    let _ = std::io::stdout().flush();
+}
+
+/// Read a file line by line (without the line endings)
+pub fn read_lines<P>(path: P) -> Result<Vec<String>>
+where
+    P: AsRef<Path>,
+{
+    let reader = BufReader::new(File::open(path)?);
+    let mut lines: Vec<String> = Vec::new();
+    for line in reader.lines() {
+        let line: String = line?;
+        lines.push(line.trim_end_matches('\r').to_string());
+    }
+    Ok(lines)
+}
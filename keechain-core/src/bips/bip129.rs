@@ -0,0 +1,686 @@
+// Copyright (c) 2022-2023 Yuki Kishimoto
+// Distributed under the MIT software license
+
+//! BIP129
+//!
+//! Bitcoin Secure Multisig Setup (BSMS): the signers send a signed key record to the coordinator,
+//! that replies with the descriptor record of the wallet. Records can be encrypted with the setup token.
+//!
+//! <https://github.com/bitcoin/bips/blob/master/bip-0129.mediawiki>
+
+use core::fmt;
+use core::str::FromStr;
+
+use aes::cipher::{BlockEncrypt, KeyInit};
+use aes::{Aes256, Block};
+use bdk::bitcoin::hashes::hmac::{Hmac, HmacEngine};
+use bdk::bitcoin::hashes::{sha256, Hash, HashEngine};
+use bdk::bitcoin::secp256k1::{self, rand, Message, Secp256k1, Signing, Verification};
+use bdk::bitcoin::sign_message::{self, MessageSignature, MessageSignatureError};
+use bdk::bitcoin::Network;
+use bdk::miniscript::descriptor::{ConversionError, Descriptor, DescriptorPublicKey, Wildcard};
+
+use super::bip32::{self, DerivationPath, ExtendedPrivKey, ExtendedPubKey, Fingerprint};
+use super::bip48::ScriptType;
+use crate::crypto::hash;
+use crate::descriptors;
+use crate::multisig::{self, MultisigWallet};
+use crate::util::{base64, hex};
+
+const VERSION: &str = "BSMS 1.0";
+/// Token of unencrypted records (`0x00`)
+const NO_TOKEN: &str = "00";
+/// Short form of [`NO_TOKEN`], accepted when parsing
+const NO_TOKEN_SHORT: &str = "0";
+const PATH_RESTRICTIONS: &str = "/0/*,/1/*";
+const NO_PATH_RESTRICTIONS: &str = "No path restrictions";
+const MAX_DESCRIPTION_LEN: usize = 80;
+/// Wallets are named at registration: the descriptor record only carries the descriptor
+const COORDINATOR_WALLET_NAME: &str = "BSMS";
+
+#[derive(Debug)]
+pub enum Error {
+    Hex(hex::Error),
+    Base64(base64::DecodeError),
+    BIP32(bip32::Error),
+    Secp256k1(secp256k1::Error),
+    Signature(MessageSignatureError),
+    Miniscript(bdk::miniscript::Error),
+    Conversion(ConversionError),
+    Descriptors(descriptors::Error),
+    Multisig(multisig::Error),
+    InvalidToken,
+    TokenMismatch,
+    InvalidMac,
+    InvalidRecord,
+    UnsupportedVersion(String),
+    InvalidDescription,
+    UnsupportedKey(String),
+    InvalidSignature(String),
+    PathRestrictions(String),
+    AddressMismatch { expected: String, found: String },
+    NotACosigner(Fingerprint),
+}
+
+impl std::error::Error for Error {}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Hex(e) => write!(f, "Hex: {e}"),
+            Self::Base64(e) => write!(f, "Base64: {e}"),
+            Self::BIP32(e) => write!(f, "BIP32: {e}"),
+            Self::Secp256k1(e) => write!(f, "Secp256k1: {e}"),
+            Self::Signature(e) => write!(f, "Signature: {e}"),
+            Self::Miniscript(e) => write!(f, "Miniscript: {e}"),
+            Self::Conversion(e) => write!(f, "Conversion: {e}"),
+            Self::Descriptors(e) => write!(f, "Descriptors: {e}"),
+            Self::Multisig(e) => write!(f, "Multisig: {e}"),
+            Self::InvalidToken => {
+                write!(f, "Invalid token: expected `00` or 16 or 32 hex characters")
+            }
+            Self::TokenMismatch => write!(f, "The record was created for another token"),
+            Self::InvalidMac => write!(f, "Invalid MAC: wrong token or corrupted record"),
+            Self::InvalidRecord => write!(f, "Invalid record format"),
+            Self::UnsupportedVersion(version) => write!(f, "Unsupported version: {version}"),
+            Self::InvalidDescription => write!(
+                f,
+                "Invalid description: expected a single line of max {MAX_DESCRIPTION_LEN} chars"
+            ),
+            Self::UnsupportedKey(key) => write!(
+                f,
+                "Unsupported key {key}: expected an extended public key with origin"
+            ),
+            Self::InvalidSignature(key) => write!(f, "Invalid signature for key {key}"),
+            Self::PathRestrictions(path) => write!(f, "Unsupported path restrictions: {path}"),
+            Self::AddressMismatch { expected, found } => write!(
+                f,
+                "First address mismatch: expected {expected}, found {found}"
+            ),
+            Self::NotACosigner(fingerprint) => {
+                write!(f, "Keychain {fingerprint} is not a cosigner of the wallet")
+            }
+        }
+    }
+}
+
+impl From<hex::Error> for Error {
+    fn from(e: hex::Error) -> Self {
+        Self::Hex(e)
+    }
+}
+
+impl From<base64::DecodeError> for Error {
+    fn from(e: base64::DecodeError) -> Self {
+        Self::Base64(e)
+    }
+}
+
+impl From<bip32::Error> for Error {
+    fn from(e: bip32::Error) -> Self {
+        Self::BIP32(e)
+    }
+}
+
+impl From<secp256k1::Error> for Error {
+    fn from(e: secp256k1::Error) -> Self {
+        Self::Secp256k1(e)
+    }
+}
+
+impl From<MessageSignatureError> for Error {
+    fn from(e: MessageSignatureError) -> Self {
+        Self::Signature(e)
+    }
+}
+
+impl From<bdk::miniscript::Error> for Error {
+    fn from(e: bdk::miniscript::Error) -> Self {
+        Self::Miniscript(e)
+    }
+}
+
+impl From<ConversionError> for Error {
+    fn from(e: ConversionError) -> Self {
+        Self::Conversion(e)
+    }
+}
+
+impl From<descriptors::Error> for Error {
+    fn from(e: descriptors::Error) -> Self {
+        Self::Descriptors(e)
+    }
+}
+
+impl From<multisig::Error> for Error {
+    fn from(e: multisig::Error) -> Self {
+        Self::Multisig(e)
+    }
+}
+
+/// Setup token shared by the coordinator with the signers (empty: no encryption)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Token(Vec<u8>);
+
+impl FromStr for Token {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s: &str = s.trim();
+        if s == NO_TOKEN || s == NO_TOKEN_SHORT {
+            return Ok(Self::default());
+        }
+        if s.len() != 16 && s.len() != 32 {
+            return Err(Error::InvalidToken);
+        }
+        Ok(Self(hex::decode(s).map_err(|_| Error::InvalidToken)?))
+    }
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_none() {
+            write!(f, "{NO_TOKEN}")
+        } else {
+            write!(f, "{}", hex::encode(&self.0))
+        }
+    }
+}
+
+impl Token {
+    /// Random 64-bit token
+    pub fn random_64() -> Self {
+        let token: [u8; 8] = rand::random();
+        Self(token.to_vec())
+    }
+
+    /// Random 128-bit token
+    pub fn random_128() -> Self {
+        let token: [u8; 16] = rand::random();
+        Self(token.to_vec())
+    }
+
+    /// No encryption
+    pub fn is_none(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    fn encryption_key(&self) -> [u8; 32] {
+        let key: [u8; 64] = hash::pbkdf2_hmac_sha512(b"No SPOF", &self.0, 2048);
+        let mut encryption_key: [u8; 32] = [0u8; 32];
+        encryption_key.copy_from_slice(&key[..32]);
+        encryption_key
+    }
+
+    /// `HMAC-SHA256(SHA256(ENCRYPTION_KEY), TOKEN || DATA)`, with the raw token bytes as `TOKEN`
+    fn mac(&self, encryption_key: &[u8; 32], data: &[u8]) -> [u8; 32] {
+        let mac_key: sha256::Hash = hash::sha256(encryption_key);
+        let mut engine: HmacEngine<sha256::Hash> = HmacEngine::new(mac_key.as_ref());
+        engine.input(&self.0);
+        engine.input(data);
+        Hmac::from_engine(engine).to_byte_array()
+    }
+
+    /// Encrypt a record: hex of the MAC followed by the AES-256-CTR ciphertext
+    ///
+    /// Without a token the record is returned as is.
+    pub fn encrypt<S>(&self, record: S) -> String
+    where
+        S: AsRef<str>,
+    {
+        let record: &str = record.as_ref();
+        if self.is_none() {
+            return record.to_string();
+        }
+        let key: [u8; 32] = self.encryption_key();
+        let mac: [u8; 32] = self.mac(&key, record.as_bytes());
+        let mut data: Vec<u8> = record.as_bytes().to_vec();
+        aes_256_ctr(&key, &mac, &mut data);
+        let mut encrypted: Vec<u8> = mac.to_vec();
+        encrypted.extend(data);
+        hex::encode(encrypted)
+    }
+
+    /// Decrypt a record and check its MAC
+    ///
+    /// Plaintext records (starting with the BSMS version) are returned as is.
+    pub fn decrypt<S>(&self, record: S) -> Result<String, Error>
+    where
+        S: AsRef<str>,
+    {
+        let record: &str = record.as_ref().trim();
+        if record.starts_with("BSMS") {
+            return Ok(record.to_string());
+        }
+        if self.is_none() {
+            return Err(Error::InvalidRecord);
+        }
+        let encrypted: Vec<u8> = hex::decode(record)?;
+        if encrypted.len() <= 32 {
+            return Err(Error::InvalidRecord);
+        }
+        let mut mac: [u8; 32] = [0u8; 32];
+        mac.copy_from_slice(&encrypted[..32]);
+        let mut data: Vec<u8> = encrypted[32..].to_vec();
+        let key: [u8; 32] = self.encryption_key();
+        aes_256_ctr(&key, &mac, &mut data);
+        if !hash::constant_time_eq(self.mac(&key, &data), mac) {
+            return Err(Error::InvalidMac);
+        }
+        String::from_utf8(data).map_err(|_| Error::InvalidRecord)
+    }
+}
+
+/// AES-256 in CTR mode, with the first 16 bytes of the `mac` as IV (128-bit big-endian counter)
+fn aes_256_ctr(key: &[u8; 32], mac: &[u8; 32], data: &mut [u8]) {
+    let cipher = Aes256::new(key.as_slice().into());
+    let mut iv: [u8; 16] = [0u8; 16];
+    iv.copy_from_slice(&mac[..16]);
+    let mut counter: u128 = u128::from_be_bytes(iv);
+    for chunk in data.chunks_mut(16) {
+        let mut block: Block = Block::from(counter.to_be_bytes());
+        cipher.encrypt_block(&mut block);
+        chunk
+            .iter_mut()
+            .zip(block.iter())
+            .for_each(|(b, k)| *b ^= k);
+        counter = counter.wrapping_add(1);
+    }
+}
+
+/// Split a record in lines, checking the version
+fn record_lines(record: &str, expected: usize) -> Result<Vec<&str>, Error> {
+    let lines: Vec<&str> = record
+        .trim()
+        .lines()
+        .map(|line| line.trim_end_matches('\r'))
+        .collect();
+    match lines.first() {
+        Some(&VERSION) => (),
+        Some(version) => return Err(Error::UnsupportedVersion(version.to_string())),
+        None => return Err(Error::InvalidRecord),
+    }
+    if lines.len() != expected {
+        return Err(Error::InvalidRecord);
+    }
+    Ok(lines)
+}
+
+/// Round 1: key record of a signer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyRecord {
+    token: Token,
+    key: DescriptorPublicKey,
+    description: String,
+    signature: MessageSignature,
+}
+
+impl fmt::Display for KeyRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", self.message())?;
+        write!(f, "{}", base64::encode(self.signature.serialize()))
+    }
+}
+
+impl KeyRecord {
+    /// Sign the key record of the extended key at `path`
+    pub fn new<S, C>(
+        token: Token,
+        description: S,
+        root: &ExtendedPrivKey,
+        path: &DerivationPath,
+        secp: &Secp256k1<C>,
+    ) -> Result<Self, Error>
+    where
+        S: Into<String>,
+        C: Signing,
+    {
+        let description: String = description.into();
+        if description.chars().count() > MAX_DESCRIPTION_LEN || description.contains('\n') {
+            return Err(Error::InvalidDescription);
+        }
+
+        let derived: ExtendedPrivKey = root.derive_priv(secp, path)?;
+        let xpub: ExtendedPubKey = ExtendedPubKey::from_priv(secp, &derived);
+        let key: DescriptorPublicKey =
+            descriptors::key_expression(root.fingerprint(secp), xpub, path)?;
+
+        let hash = sign_message::signed_msg_hash(&key_record_message(&token, &key, &description));
+        let msg: Message = Message::from_slice(hash.as_ref())?;
+        let signature: MessageSignature = MessageSignature::new(
+            secp.sign_ecdsa_recoverable(&msg, &derived.private_key),
+            true,
+        );
+
+        Ok(Self {
+            token,
+            key,
+            description,
+            signature,
+        })
+    }
+
+    /// Parse a (possibly encrypted) key record and verify its signature
+    pub fn parse<S, C>(record: S, token: &Token, secp: &Secp256k1<C>) -> Result<Self, Error>
+    where
+        S: AsRef<str>,
+        C: Verification,
+    {
+        let record: String = token.decrypt(record)?;
+        let lines: Vec<&str> = record_lines(&record, 5)?;
+
+        if Token::from_str(lines[1])? != *token {
+            return Err(Error::TokenMismatch);
+        }
+
+        let record = Self {
+            token: token.clone(),
+            key: DescriptorPublicKey::from_str(lines[2]).map_err(descriptors::Error::from)?,
+            description: lines[3].to_string(),
+            signature: MessageSignature::from_slice(&base64::decode(lines[4])?)?,
+        };
+        record.verify(secp)?;
+        Ok(record)
+    }
+
+    /// Account key (with origin)
+    pub fn key(&self) -> DescriptorPublicKey {
+        self.key.clone()
+    }
+
+    pub fn description(&self) -> String {
+        self.description.clone()
+    }
+
+    /// Check that the signature was made by the private key of the record key
+    pub fn verify<C>(&self, secp: &Secp256k1<C>) -> Result<(), Error>
+    where
+        C: Verification,
+    {
+        let xkey = match &self.key {
+            DescriptorPublicKey::XPub(xkey)
+                if xkey.origin.is_some()
+                    && xkey.derivation_path.is_master()
+                    && xkey.wildcard == Wildcard::None =>
+            {
+                xkey
+            }
+            _ => return Err(Error::UnsupportedKey(self.key.to_string())),
+        };
+        let hash = sign_message::signed_msg_hash(&self.message());
+        let pubkey = self.signature.recover_pubkey(secp, hash)?;
+        if pubkey.inner == xkey.xkey.public_key {
+            Ok(())
+        } else {
+            Err(Error::InvalidSignature(self.key.to_string()))
+        }
+    }
+
+    /// Record to send to the coordinator (encrypted if there is a token)
+    pub fn export(&self) -> String {
+        self.token.encrypt(self.to_string())
+    }
+
+    /// Signed lines
+    fn message(&self) -> String {
+        key_record_message(&self.token, &self.key, &self.description)
+    }
+}
+
+/// First 4 lines of a key record (signed by the key)
+fn key_record_message(token: &Token, key: &DescriptorPublicKey, description: &str) -> String {
+    format!("{VERSION}\n{token}\n{key}\n{description}")
+}
+
+/// Round 2: descriptor record of the wallet
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DescriptorRecord {
+    token: Token,
+    /// Multipath descriptor (`/<0;1>/*`)
+    descriptor: Descriptor<DescriptorPublicKey>,
+    first_address: String,
+}
+
+impl fmt::Display for DescriptorRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let desc: String = self.descriptor.to_string();
+        let template: &str = desc.split('#').next().unwrap_or_default();
+        writeln!(f, "{VERSION}")?;
+        writeln!(f, "{}", template.replace("/<0;1>/*", "/**"))?;
+        writeln!(f, "{PATH_RESTRICTIONS}")?;
+        write!(f, "{}", self.first_address)
+    }
+}
+
+impl DescriptorRecord {
+    /// Coordinator: assemble the `sortedmulti` wallet from the verified key records
+    pub fn from_key_records(
+        token: Token,
+        records: &[KeyRecord],
+        script: ScriptType,
+        threshold: usize,
+        network: Network,
+    ) -> Result<Self, Error> {
+        let keys: Vec<DescriptorPublicKey> = records.iter().map(|r| r.key()).collect();
+        let wallet: MultisigWallet =
+            MultisigWallet::new(COORDINATOR_WALLET_NAME, script, threshold, keys, network)?;
+        Self::from_wallet(token, &wallet, network)
+    }
+
+    pub fn from_wallet(
+        token: Token,
+        wallet: &MultisigWallet,
+        network: Network,
+    ) -> Result<Self, Error> {
+        let external: String = wallet.descriptor(false)?.to_string();
+        let external: &str = external.split('#').next().unwrap_or_default();
+        let descriptor: Descriptor<DescriptorPublicKey> =
+            Descriptor::from_str(&external.replace("/0/*", "/<0;1>/*"))?;
+        Ok(Self {
+            token,
+            descriptor,
+            first_address: wallet.address(false, 0, network)?.to_string(),
+        })
+    }
+
+    /// Parse a (possibly encrypted) descriptor record and verify its first address
+    pub fn parse<S>(record: S, token: &Token, network: Network) -> Result<Self, Error>
+    where
+        S: AsRef<str>,
+    {
+        let record: String = token.decrypt(record)?;
+        let lines: Vec<&str> = record_lines(&record, 4)?;
+
+        let template: &str = lines[1].split('#').next().unwrap_or_default();
+        let descriptor: Descriptor<DescriptorPublicKey> =
+            Descriptor::from_str(&template.replace("/**", "/<0;1>/*"))?;
+
+        if lines[2] != PATH_RESTRICTIONS && lines[2] != NO_PATH_RESTRICTIONS {
+            return Err(Error::PathRestrictions(lines[2].to_string()));
+        }
+
+        let expected: String = descriptor
+            .clone()
+            .into_single_descriptors()?
+            .first()
+            .ok_or(Error::InvalidRecord)?
+            .at_derivation_index(0)?
+            .address(network)?
+            .to_string();
+        if lines[3] != expected {
+            return Err(Error::AddressMismatch {
+                expected,
+                found: lines[3].to_string(),
+            });
+        }
+
+        Ok(Self {
+            token: token.clone(),
+            descriptor,
+            first_address: expected,
+        })
+    }
+
+    /// Multipath descriptor (`/<0;1>/*`)
+    pub fn descriptor(&self) -> Descriptor<DescriptorPublicKey> {
+        self.descriptor.clone()
+    }
+
+    pub fn first_address(&self) -> String {
+        self.first_address.clone()
+    }
+
+    /// Wallet to register in the keychain with root `fingerprint`
+    pub fn to_wallet<S>(
+        &self,
+        name: S,
+        fingerprint: Fingerprint,
+        network: Network,
+    ) -> Result<MultisigWallet, Error>
+    where
+        S: Into<String>,
+    {
+        let wallet: MultisigWallet =
+            MultisigWallet::from_descriptor(name, &self.descriptor, network)?;
+        if !wallet.fingerprints().contains(&fingerprint) {
+            return Err(Error::NotACosigner(fingerprint));
+        }
+        Ok(wallet)
+    }
+
+    /// Record to send to the signers (encrypted if there is a token)
+    pub fn export(&self) -> String {
+        self.token.encrypt(self.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Result;
+
+    fn root(seed: u8) -> ExtendedPrivKey {
+        ExtendedPrivKey::new_master(Network::Testnet, &[seed; 32]).unwrap()
+    }
+
+    #[test]
+    fn test_token_encryption() -> Result<()> {
+        assert!(Token::from_str("00")?.is_none());
+        assert!(Token::from_str("0")?.is_none());
+        assert_eq!(Token::default().to_string(), "00");
+        assert_eq!(
+            Token::from_str("a54044308ceac9b7")?.to_string(),
+            "a54044308ceac9b7"
+        );
+        assert!(Token::from_str("a54044308ceac9").is_err());
+        assert!(Token::from_str("a54044308ceac9zz").is_err());
+        assert_eq!(Token::random_128().to_string().len(), 32);
+
+        let token = Token::from_str("a54044308ceac9b7")?;
+        let record: &str = "BSMS 1.0\na54044308ceac9b7\nKEY\nSigner 1\nSIG";
+        let encrypted: String = token.encrypt(record);
+        // Regression value, computed by this implementation: not yet checked against the
+        // official BIP129 ENCRYPTION_KEY, MAC and ciphertext vectors
+        assert_eq!(encrypted, "73704752c856ea851f37e6fd5952827fa3a3852ea551bd2eb50b1a0837222432e73e46cb5691fca5430e953ff16e5e417941d7cbbd7ff6f29954c139346ccd7059cbd2f82fc162e05926");
+        assert_eq!(token.decrypt(&encrypted)?, record);
+
+        let other = Token::from_str("a54044308ceac9b8")?;
+        assert!(matches!(other.decrypt(&encrypted), Err(Error::InvalidMac)));
+
+        // No token: plaintext
+        assert_eq!(Token::default().encrypt(record), record);
+        Ok(())
+    }
+
+    #[test]
+    fn test_key_record() -> Result<()> {
+        let secp = Secp256k1::new();
+        let path = DerivationPath::from_str("m/48'/1'/0'/2'")?;
+        let token = Token::from_str("a54044308ceac9b7")?;
+
+        let record = KeyRecord::new(token.clone(), "Signer 1", &root(1), &path, &secp)?;
+        let exported: String = record.export();
+        assert!(!exported.starts_with("BSMS"));
+        assert_eq!(KeyRecord::parse(&exported, &token, &secp)?, record);
+        assert_eq!(KeyRecord::parse(record.to_string(), &token, &secp)?, record);
+
+        // Token of another setup
+        assert!(matches!(
+            KeyRecord::parse(record.to_string(), &Token::default(), &secp),
+            Err(Error::TokenMismatch)
+        ));
+
+        // Unencrypted
+        let plain = KeyRecord::new(Token::default(), "Signer 1", &root(1), &path, &secp)?;
+        assert_eq!(plain.export().lines().nth(1), Some(NO_TOKEN));
+        assert_eq!(
+            KeyRecord::parse(plain.export(), &Token::default(), &secp)?,
+            plain
+        );
+
+        // Tampered description
+        let tampered: String = record.to_string().replace("Signer 1", "Signer 2");
+        assert!(matches!(
+            KeyRecord::parse(tampered, &token, &secp),
+            Err(Error::InvalidSignature(..))
+        ));
+
+        assert!(matches!(
+            KeyRecord::new(token, "a".repeat(81), &root(1), &path, &secp),
+            Err(Error::InvalidDescription)
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_descriptor_record() -> Result<()> {
+        let secp = Secp256k1::new();
+        let path = DerivationPath::from_str("m/48'/1'/0'/2'")?;
+        let token = Token::default();
+
+        let records: Vec<KeyRecord> = vec![
+            KeyRecord::new(token.clone(), "Signer 1", &root(1), &path, &secp)?,
+            KeyRecord::new(token.clone(), "Signer 2", &root(2), &path, &secp)?,
+        ];
+        let record = DescriptorRecord::from_key_records(
+            token.clone(),
+            &records,
+            ScriptType::P2WSH,
+            2,
+            Network::Testnet,
+        )?;
+        let exported: String = record.export();
+        let lines: Vec<&str> = exported.lines().collect();
+        assert_eq!(lines[0], VERSION);
+        assert!(lines[1].starts_with("wsh(sortedmulti(2,["));
+        assert!(lines[1].ends_with("/**))"));
+        assert_eq!(lines[2], PATH_RESTRICTIONS);
+
+        let parsed = DescriptorRecord::parse(&exported, &token, Network::Testnet)?;
+        assert_eq!(parsed, record);
+
+        let fingerprint: Fingerprint = root(2).fingerprint(&secp);
+        let wallet: MultisigWallet = parsed.to_wallet("Vault", fingerprint, Network::Testnet)?;
+        assert_eq!(wallet.threshold(), 2);
+        assert_eq!(wallet.keys().len(), 2);
+        assert_eq!(
+            wallet.address(false, 0, Network::Testnet)?.to_string(),
+            parsed.first_address()
+        );
+
+        let stranger: Fingerprint = root(3).fingerprint(&secp);
+        assert!(matches!(
+            parsed.to_wallet("Vault", stranger, Network::Testnet),
+            Err(Error::NotACosigner(..))
+        ));
+
+        // Wrong first address
+        let address: String = wallet.address(false, 1, Network::Testnet)?.to_string();
+        let tampered: String = exported.replace(&parsed.first_address(), &address);
+        assert!(matches!(
+            DescriptorRecord::parse(tampered, &token, Network::Testnet),
+            Err(Error::AddressMismatch { .. })
+        ));
+        Ok(())
+    }
+}
@@ -0,0 +1,575 @@
+// Copyright (c) 2022-2023 Yuki Kishimoto
+// Distributed under the MIT software license
+
+//! BIP388
+//!
+//! Wallet policies: a descriptor template (ex. `wsh(sortedmulti(2,@0/**,@1/**))`) plus the list of keys.
+//!
+//! <https://github.com/bitcoin/bips/blob/master/bip-0388.mediawiki>
+
+use core::fmt;
+use core::str::FromStr;
+
+use bdk::bitcoin::consensus::encode;
+use bdk::bitcoin::hashes::hmac::{Hmac, HmacEngine};
+use bdk::bitcoin::hashes::{sha256, Hash, HashEngine};
+use bdk::bitcoin::VarInt;
+use bdk::miniscript::descriptor::{DescriptorPublicKey, DescriptorXKey, Wildcard};
+use bdk::miniscript::{Descriptor, ForEachKey};
+use serde::{Deserialize, Serialize};
+
+use super::bip32::{ChildNumber, DerivationPath};
+use crate::crypto::hash;
+
+/// Max number of keys in a wallet policy
+const MAX_KEYS: usize = 1000;
+
+#[derive(Debug)]
+pub enum Error {
+    Miniscript(bdk::miniscript::Error),
+    Json(serde_json::Error),
+    EmptyTemplate,
+    NoKeys,
+    TooManyKeys(usize),
+    UnsupportedKey(String),
+    DuplicateKey(String),
+    InvalidPlaceholder(String),
+    KeyNotFound(usize),
+    UnorderedPlaceholder(usize),
+    UnusedKey(usize),
+    OverlappingDerivations(usize),
+    UnexpectedKey,
+}
+
+impl std::error::Error for Error {}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Miniscript(e) => write!(f, "Miniscript: {e}"),
+            Self::Json(e) => write!(f, "Json: {e}"),
+            Self::EmptyTemplate => write!(f, "Empty descriptor template"),
+            Self::NoKeys => write!(f, "A wallet policy needs at least 1 key"),
+            Self::TooManyKeys(max) => write!(f, "Too many keys: max {max}"),
+            Self::UnsupportedKey(key) => write!(
+                f,
+                "Unsupported key {key}: expected an extended public key without derivation suffix"
+            ),
+            Self::DuplicateKey(key) => write!(f, "Duplicate key {key}"),
+            Self::InvalidPlaceholder(placeholder) => write!(
+                f,
+                "Invalid key placeholder {placeholder}: expected `@i/**` or `@i/<M;N>/*`"
+            ),
+            Self::KeyNotFound(index) => write!(f, "Key @{index} not found"),
+            Self::UnorderedPlaceholder(index) => {
+                write!(f, "Key @{index} is used before the previous keys")
+            }
+            Self::UnusedKey(index) => write!(f, "Key @{index} is not used in the template"),
+            Self::OverlappingDerivations(index) => {
+                write!(
+                    f,
+                    "Key @{index} is used more than once with the same derivations"
+                )
+            }
+            Self::UnexpectedKey => write!(
+                f,
+                "Keys in the descriptor template must be placeholders (ex. `@0/**`)"
+            ),
+        }
+    }
+}
+
+impl From<bdk::miniscript::Error> for Error {
+    fn from(e: bdk::miniscript::Error) -> Self {
+        Self::Miniscript(e)
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(e: serde_json::Error) -> Self {
+        Self::Json(e)
+    }
+}
+
+/// Key placeholder (`@i/**` or `@i/<M;N>/*`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Placeholder {
+    index: usize,
+    receive: u32,
+    change: u32,
+}
+
+impl FromStr for Placeholder {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::InvalidPlaceholder(s.to_string());
+        let body: &str = s.strip_prefix('@').ok_or_else(invalid)?;
+        let (index, suffix) = body.split_at(body.find('/').ok_or_else(invalid)?);
+
+        // Decimal, without leading zeros
+        if index.is_empty()
+            || (index.len() > 1 && index.starts_with('0'))
+            || !index.chars().all(|c| c.is_ascii_digit())
+        {
+            return Err(invalid());
+        }
+        let index: usize = index.parse().map_err(|_| invalid())?;
+
+        let (receive, change) = if suffix == "/**" {
+            (0, 1)
+        } else {
+            let derivations: &str = suffix
+                .strip_prefix("/<")
+                .and_then(|s| s.strip_suffix(">/*"))
+                .ok_or_else(invalid)?;
+            let (receive, change) = derivations.split_once(';').ok_or_else(invalid)?;
+            let receive: u32 = parse_normal_index(receive).ok_or_else(invalid)?;
+            let change: u32 = parse_normal_index(change).ok_or_else(invalid)?;
+            (receive, change)
+        };
+
+        if receive == change {
+            return Err(invalid());
+        }
+
+        Ok(Self {
+            index,
+            receive,
+            change,
+        })
+    }
+}
+
+impl fmt::Display for Placeholder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.receive == 0 && self.change == 1 {
+            write!(f, "@{}/**", self.index)
+        } else {
+            write!(f, "@{}/<{};{}>/*", self.index, self.receive, self.change)
+        }
+    }
+}
+
+impl Placeholder {
+    fn overlaps(&self, other: &Self) -> bool {
+        self.index == other.index
+            && (self.receive == other.receive
+                || self.receive == other.change
+                || self.change == other.receive
+                || self.change == other.change)
+    }
+}
+
+fn parse_normal_index(s: &str) -> Option<u32> {
+    if s.is_empty() || !s.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    match ChildNumber::from_normal_idx(s.parse().ok()?) {
+        Ok(ChildNumber::Normal { index }) => Some(index),
+        _ => None,
+    }
+}
+
+/// Replace the key expressions of a descriptor (or template) string
+fn map_keys<F>(s: &str, mut f: F) -> Result<String, Error>
+where
+    F: FnMut(&str) -> Result<Option<String>, Error>,
+{
+    let mut output: String = String::with_capacity(s.len());
+    let mut token: String = String::new();
+    for c in s.chars() {
+        if matches!(c, '(' | ')' | ',' | '{' | '}') {
+            match f(&token)? {
+                Some(key) => output.push_str(&key),
+                None => output.push_str(&token),
+            }
+            token.clear();
+            output.push(c);
+        } else {
+            token.push(c);
+        }
+    }
+    match f(&token)? {
+        Some(key) => output.push_str(&key),
+        None => output.push_str(&token),
+    }
+    Ok(output)
+}
+
+/// Wallet policy
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "WalletPolicyRaw", into = "WalletPolicyRaw")]
+pub struct WalletPolicy {
+    template: String,
+    keys: Vec<DescriptorPublicKey>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct WalletPolicyRaw {
+    descriptor_template: String,
+    keys_info: Vec<String>,
+}
+
+impl From<WalletPolicy> for WalletPolicyRaw {
+    fn from(policy: WalletPolicy) -> Self {
+        Self {
+            descriptor_template: policy.template,
+            keys_info: policy.keys.iter().map(|k| k.to_string()).collect(),
+        }
+    }
+}
+
+impl TryFrom<WalletPolicyRaw> for WalletPolicy {
+    type Error = Error;
+
+    fn try_from(raw: WalletPolicyRaw) -> Result<Self, Self::Error> {
+        let mut keys: Vec<DescriptorPublicKey> = Vec::with_capacity(raw.keys_info.len());
+        for key in raw.keys_info.iter() {
+            keys.push(
+                DescriptorPublicKey::from_str(key)
+                    .map_err(|_| Error::UnsupportedKey(key.clone()))?,
+            );
+        }
+        Self::new(raw.descriptor_template, keys)
+    }
+}
+
+impl FromStr for WalletPolicy {
+    type Err = Error;
+
+    /// Parse a wallet policy JSON (`{"descriptor_template": "...", "keys_info": [...]}`)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_json(s.trim())
+    }
+}
+
+impl WalletPolicy {
+    /// New wallet policy
+    ///
+    /// Keys are extended public keys (with or without origin) without derivation suffix.
+    pub fn new<S>(template: S, keys: Vec<DescriptorPublicKey>) -> Result<Self, Error>
+    where
+        S: Into<String>,
+    {
+        let template: String = template.into().trim().to_string();
+        if template.is_empty() {
+            return Err(Error::EmptyTemplate);
+        }
+
+        if keys.is_empty() {
+            return Err(Error::NoKeys);
+        }
+
+        if keys.len() > MAX_KEYS {
+            return Err(Error::TooManyKeys(MAX_KEYS));
+        }
+
+        for (i, key) in keys.iter().enumerate() {
+            match key {
+                DescriptorPublicKey::XPub(xkey)
+                    if xkey.derivation_path.is_master() && xkey.wildcard == Wildcard::None => {}
+                _ => return Err(Error::UnsupportedKey(key.to_string())),
+            }
+            if keys[..i].contains(key) {
+                return Err(Error::DuplicateKey(key.to_string()));
+            }
+        }
+
+        let policy = Self { template, keys };
+        policy.to_descriptor()?;
+        Ok(policy)
+    }
+
+    /// Wallet policy of a descriptor with multipath keys (ex. `[fingerprint/48'/0'/0'/2']xpub.../<0;1>/*`)
+    pub fn from_descriptor(desc: &Descriptor<DescriptorPublicKey>) -> Result<Self, Error> {
+        let mut placeholders: Vec<(String, Placeholder)> = Vec::new();
+        let mut keys: Vec<DescriptorPublicKey> = Vec::new();
+        let mut unsupported: Option<String> = None;
+        desc.for_each_key(|key| match placeholder_of(key, &mut keys) {
+            Ok(placeholder) => {
+                placeholders.push((key.to_string(), placeholder));
+                true
+            }
+            Err(_) => {
+                unsupported = Some(key.to_string());
+                false
+            }
+        });
+
+        if let Some(key) = unsupported {
+            return Err(Error::UnsupportedKey(key));
+        }
+
+        let desc: String = desc.to_string();
+        let desc: &str = desc.split('#').next().unwrap_or_default();
+        let template: String = map_keys(desc, |token| {
+            Ok(placeholders
+                .iter()
+                .find(|(key, _)| key == token)
+                .map(|(_, placeholder)| placeholder.to_string()))
+        })?;
+
+        Self::new(template, keys)
+    }
+
+    pub fn from_json<T>(json: T) -> Result<Self, Error>
+    where
+        T: AsRef<[u8]>,
+    {
+        Ok(serde_json::from_slice(json.as_ref())?)
+    }
+
+    pub fn as_json(&self) -> String {
+        serde_json::json!(self).to_string()
+    }
+
+    pub fn template(&self) -> &str {
+        &self.template
+    }
+
+    pub fn keys(&self) -> &[DescriptorPublicKey] {
+        &self.keys
+    }
+
+    /// Descriptor with multipath keys
+    pub fn to_descriptor(&self) -> Result<Descriptor<DescriptorPublicKey>, Error> {
+        let mut placeholders: Vec<Placeholder> = Vec::new();
+        let desc: String = map_keys(&self.template, |token| {
+            if !token.starts_with('@') {
+                return Ok(None);
+            }
+            let placeholder = Placeholder::from_str(token)?;
+            let key: &DescriptorPublicKey = self
+                .keys
+                .get(placeholder.index)
+                .ok_or(Error::KeyNotFound(placeholder.index))?;
+            placeholders.push(placeholder);
+            Ok(Some(format!(
+                "{key}/<{};{}>/*",
+                placeholder.receive, placeholder.change
+            )))
+        })?;
+
+        // The first occurrence of `@i` comes after the one of `@(i-1)`
+        let mut next: usize = 0;
+        for (i, placeholder) in placeholders.iter().enumerate() {
+            if placeholder.index > next {
+                return Err(Error::UnorderedPlaceholder(placeholder.index));
+            }
+            if placeholder.index == next {
+                next += 1;
+            }
+            if placeholders[..i].iter().any(|p| p.overlaps(placeholder)) {
+                return Err(Error::OverlappingDerivations(placeholder.index));
+            }
+        }
+
+        if next < self.keys.len() {
+            return Err(Error::UnusedKey(next));
+        }
+
+        let desc: Descriptor<DescriptorPublicKey> = Descriptor::from_str(&desc)?;
+
+        // Keys can't be written in the template, only referenced
+        let mut count: usize = 0;
+        desc.for_each_key(|_| {
+            count += 1;
+            true
+        });
+        if count != placeholders.len() {
+            return Err(Error::UnexpectedKey);
+        }
+
+        Ok(desc)
+    }
+
+    /// Descriptor to sign with (external)
+    pub fn signing_descriptor(&self) -> Result<Descriptor<String>, Error> {
+        let desc: Descriptor<DescriptorPublicKey> = self
+            .to_descriptor()?
+            .into_single_descriptors()?
+            .into_iter()
+            .next()
+            .ok_or(Error::EmptyTemplate)?;
+        Ok(Descriptor::from_str(&desc.to_string())?)
+    }
+
+    /// Policy id: `SHA256(template || keys)`, each item prefixed with its length (`CompactSize`)
+    ///
+    /// BIP388 doesn't define an id: this one is keechain-local (it doesn't match the wallet id
+    /// or HMAC of hardware signers) and is only used to register policies in keechain.
+    pub fn id(&self) -> sha256::Hash {
+        let mut engine = sha256::Hash::engine();
+        engine.input(&encode::serialize(&self.template));
+        engine.input(&encode::serialize(&VarInt(self.keys.len() as u64)));
+        for key in self.keys.iter() {
+            engine.input(&encode::serialize(&key.to_string()));
+        }
+        sha256::Hash::from_engine(engine)
+    }
+
+    /// Registration HMAC of the policy `name` and id, keyed with a signer secret
+    pub fn hmac<S>(&self, name: S, registration_key: &[u8]) -> Hmac<sha256::Hash>
+    where
+        S: AsRef<str>,
+    {
+        let mut engine: HmacEngine<sha256::Hash> = HmacEngine::new(registration_key);
+        engine.input(&encode::serialize(&name.as_ref().to_string()));
+        engine.input(self.id().as_byte_array());
+        Hmac::from_engine(engine)
+    }
+
+    /// Check a registration HMAC
+    pub fn verify_hmac<S>(
+        &self,
+        name: S,
+        registration_key: &[u8],
+        hmac: &Hmac<sha256::Hash>,
+    ) -> bool
+    where
+        S: AsRef<str>,
+    {
+        let expected: Hmac<sha256::Hash> = self.hmac(name, registration_key);
+        hash::constant_time_eq(expected.as_byte_array(), hmac.as_byte_array())
+    }
+}
+
+/// Placeholder of a multipath key, adding its extended key to `keys`
+fn placeholder_of(
+    key: &DescriptorPublicKey,
+    keys: &mut Vec<DescriptorPublicKey>,
+) -> Result<Placeholder, Error> {
+    let unsupported = || Error::UnsupportedKey(key.to_string());
+    let xkey = match key {
+        DescriptorPublicKey::MultiXPub(xkey) if xkey.wildcard == Wildcard::Unhardened => xkey,
+        _ => return Err(unsupported()),
+    };
+    let (receive, change) = match xkey.derivation_paths.paths().as_slice() {
+        [receive, change] => match (receive.as_ref(), change.as_ref()) {
+            ([ChildNumber::Normal { index: receive }], [ChildNumber::Normal { index: change }]) => {
+                (*receive, *change)
+            }
+            _ => return Err(unsupported()),
+        },
+        _ => return Err(unsupported()),
+    };
+    let base = DescriptorPublicKey::XPub(DescriptorXKey {
+        origin: xkey.origin.clone(),
+        xkey: xkey.xkey,
+        derivation_path: DerivationPath::master(),
+        wildcard: Wildcard::None,
+    });
+    let index: usize = match keys.iter().position(|k| k == &base) {
+        Some(index) => index,
+        None => {
+            keys.push(base);
+            keys.len() - 1
+        }
+    };
+    Ok(Placeholder {
+        index,
+        receive,
+        change,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Result;
+
+    const KEY_A: &str = "[0f056943/48'/1'/0'/2']tpubDF2rnouQaaYrXF4noGTv6rQYmx87cQ4GrUdhpvXkhtChwQPbdGTi8GA88NUaSrwZBwNsTkC9bFkkC8vDyGBVVAQTZ2AS6gs68RQXtXcCvkP";
+    const KEY_B: &str = "[91ef223d/48'/1'/0'/2']tpubDE2zNGQxK1h4rn7kM66E61RzvxvSZE1soaGwbwMmdD1LANkgupjT4qpgNZ2UjwwoNLyq4Xjs3XMBHpLjynChgBfgp6hKSfoT6i1BXg1cEtL";
+
+    fn keys() -> Vec<DescriptorPublicKey> {
+        vec![
+            DescriptorPublicKey::from_str(KEY_A).unwrap(),
+            DescriptorPublicKey::from_str(KEY_B).unwrap(),
+        ]
+    }
+
+    #[test]
+    fn test_wallet_policy() -> Result<()> {
+        let policy = WalletPolicy::new("wsh(sortedmulti(2,@0/**,@1/**))", keys())?;
+        let desc: Descriptor<DescriptorPublicKey> = Descriptor::from_str(&format!(
+            "wsh(sortedmulti(2,{KEY_A}/<0;1>/*,{KEY_B}/<0;1>/*))"
+        ))?;
+        assert_eq!(policy.to_descriptor()?, desc);
+        assert_eq!(WalletPolicy::from_descriptor(&desc)?, policy);
+
+        let signing: Descriptor<String> =
+            Descriptor::from_str(&format!("wsh(sortedmulti(2,{KEY_A}/0/*,{KEY_B}/0/*))"))?;
+        assert_eq!(policy.signing_descriptor()?, signing);
+
+        let json: String = policy.as_json();
+        assert!(json.contains("\"descriptor_template\""));
+        assert_eq!(WalletPolicy::from_str(&json)?, policy);
+
+        // Same key with different derivations
+        let policy = WalletPolicy::new(
+            "wsh(or_d(pk(@0/**),and_v(v:pk(@0/<2;3>/*),older(144))))",
+            vec![DescriptorPublicKey::from_str(KEY_A)?],
+        )?;
+        let desc: Descriptor<DescriptorPublicKey> = policy.to_descriptor()?;
+        assert_eq!(WalletPolicy::from_descriptor(&desc)?, policy);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_wallet_policy() {
+        let invalid = |template: &str| WalletPolicy::new(template, keys()).unwrap_err();
+
+        assert!(matches!(
+            invalid("wsh(sortedmulti(2,@1/**,@0/**))"),
+            Error::UnorderedPlaceholder(1)
+        ));
+        assert!(matches!(
+            invalid("wsh(sortedmulti(2,@0/**,@2/**))"),
+            Error::KeyNotFound(2)
+        ));
+        assert!(matches!(invalid("wsh(pk(@0/**))"), Error::UnusedKey(1)));
+        assert!(matches!(
+            invalid("wsh(sortedmulti(2,@0/**,@1/<1;1>/*))"),
+            Error::InvalidPlaceholder(_)
+        ));
+        assert!(matches!(
+            invalid("wsh(sortedmulti(2,@0/**,@01/**))"),
+            Error::InvalidPlaceholder(_)
+        ));
+        assert!(matches!(
+            invalid("wsh(multi(3,@0/**,@1/**,@0/<1;2>/*))"),
+            Error::OverlappingDerivations(0)
+        ));
+        assert!(matches!(invalid(""), Error::EmptyTemplate));
+
+        let key = DescriptorPublicKey::from_str(&format!("{KEY_A}/0/*")).unwrap();
+        assert!(matches!(
+            WalletPolicy::new("wsh(pk(@0/**))", vec![key]),
+            Err(Error::UnsupportedKey(_))
+        ));
+
+        let key = DescriptorPublicKey::from_str(KEY_A).unwrap();
+        assert!(matches!(
+            WalletPolicy::new("wsh(sortedmulti(1,@0/**,@1/**))", vec![key.clone(), key]),
+            Err(Error::DuplicateKey(_))
+        ));
+    }
+
+    #[test]
+    fn test_policy_id() -> Result<()> {
+        let policy = WalletPolicy::new("wsh(sortedmulti(2,@0/**,@1/**))", keys())?;
+        let other = WalletPolicy::new("wsh(multi(2,@0/**,@1/**))", keys())?;
+        assert_eq!(policy.id(), WalletPolicy::from_json(policy.as_json())?.id());
+        assert_ne!(policy.id(), other.id());
+
+        let hmac = policy.hmac("Vault", b"registration key");
+        assert!(policy.verify_hmac("Vault", b"registration key", &hmac));
+        assert!(!policy.verify_hmac("Vault", b"another key", &hmac));
+        assert!(!policy.verify_hmac("Savings", b"registration key", &hmac));
+        assert!(!other.verify_hmac("Vault", b"registration key", &hmac));
+
+        Ok(())
+    }
+}
@@ -5,11 +5,41 @@
 //!
 //! <https://github.com/bitcoin/bips/blob/master/bip-0043.mediawiki>
 
+use core::fmt;
+
 use bdk::bitcoin::Network;
 
-use super::bip32::{self, DerivationPath, Error};
+use super::bip32::{self, ChildNumber, DerivationPath};
 use super::bip48::{self, ScriptType};
 
+#[derive(Debug, PartialEq, Eq)]
+pub enum Error {
+    /// Purpose of a standard variant used as custom purpose
+    StandardPurpose(u32),
+}
+
+impl std::error::Error for Error {}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::StandardPurpose(purpose) => {
+                write!(f, "Purpose {purpose}' is standard, it can't be custom")
+            }
+        }
+    }
+}
+
+/// Non-standard purpose number, built by [`Purpose::custom`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CustomPurpose(u32);
+
+impl CustomPurpose {
+    pub fn as_u32(&self) -> u32 {
+        self.0
+    }
+}
+
 /// Derivation path purpose
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum Purpose {
@@ -17,6 +47,10 @@ pub enum Purpose {
     ///
     /// <https://github.com/bitcoin/bips/blob/master/bip-0044.mediawiki>
     BIP44,
+    /// BIP45 - Legacy P2SH multisig (`m/45'/<cosigner>/<change>/<index>`)
+    ///
+    /// <https://github.com/bitcoin/bips/blob/master/bip-0045.mediawiki>
+    BIP45,
     /// BIP48 - Multi-Script Hierarchy for Multi-Sig Wallets
     ///
     /// <https://github.com/bitcoin/bips/blob/master/bip-0048.mediawiki>
@@ -33,18 +67,35 @@ pub enum Purpose {
     ///
     /// <https://github.com/bitcoin/bips/blob/master/bip-0086.mediawiki>
     BIP86,
+    /// Non-standard purpose (`m/<purpose>'/<coin>'/<account>'`)
+    ///
+    /// Only the purpose number is kept, so it covers paths with the BIP44 levels: keys at other
+    /// paths are signed by their key origin alone.
+    Custom(CustomPurpose),
 }
 
 impl Purpose {
+    /// Non-standard purpose (the purposes of the variants above are rejected)
+    pub fn custom(purpose: u32) -> Result<Self, Error> {
+        match purpose {
+            44 | 45 | 48 | 49 | 84 | 86 => Err(Error::StandardPurpose(purpose)),
+            purpose => Ok(Self::Custom(CustomPurpose(purpose))),
+        }
+    }
+
     pub fn to_account_extended_path(
         &self,
         network: Network,
         account: Option<u32>,
-    ) -> Result<DerivationPath, Error> {
+    ) -> Result<DerivationPath, bip32::Error> {
         match self {
-            Self::BIP44 | Self::BIP49 | Self::BIP84 | Self::BIP86 => Ok(
+            Self::BIP44 | Self::BIP49 | Self::BIP84 | Self::BIP86 | Self::Custom(..) => Ok(
                 bip32::account_extended_path(self.as_u32(), network, account)?,
             ),
+            // BIP45 has no coin type and account levels
+            Self::BIP45 => Ok(DerivationPath::from(vec![ChildNumber::from_hardened_idx(
+                45,
+            )?])),
             Self::BIP48 { script } => Ok(bip48::account_extended_path(network, account, *script)?),
         }
     }
@@ -52,10 +103,12 @@ impl Purpose {
     pub fn as_u32(&self) -> u32 {
         match self {
             Self::BIP44 => 44,
+            Self::BIP45 => 45,
             Self::BIP48 { .. } => 48,
             Self::BIP49 => 49,
             Self::BIP84 => 84,
             Self::BIP86 => 86,
+            Self::Custom(purpose) => purpose.as_u32(),
         }
     }
 }
@@ -8,12 +8,13 @@
 use core::fmt;
 
 use super::bip32::{self, ChildNumber, DerivationPath};
-use super::bip43::Purpose;
+use super::bip43::{self, Purpose};
 use super::bip48::ScriptType;
 
 #[derive(Debug, PartialEq, Eq)]
 pub enum Error {
     BIP32(bip32::Error),
+    BIP43(bip43::Error),
     UnsupportedDerivationPath,
     BIP48ScriptNotFound,
 }
@@ -24,6 +25,7 @@ impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::BIP32(e) => write!(f, "BIP32: {e}"),
+            Self::BIP43(e) => write!(f, "BIP43: {e}"),
             Self::UnsupportedDerivationPath => write!(f, "Unsupported derivation path"),
             Self::BIP48ScriptNotFound => write!(f, "BIP48 script type not found"),
         }
@@ -36,77 +38,149 @@ impl From<bip32::Error> for Error {
     }
 }
 
+impl From<bip43::Error> for Error {
+    fn from(e: bip43::Error) -> Self {
+        Self::BIP43(e)
+    }
+}
+
 pub struct ExtendedPath {
     pub purpose: Purpose,
-    pub coin: u32,
+    /// `None` for BIP45 paths
+    pub coin: Option<u32>,
+    /// Cosigner index for BIP45 paths
     pub account: u32,
     pub change: bool,
 }
 
 impl ExtendedPath {
+    /// Parse `m/<purpose>'/<coin>'/<account>'[/<script>'][/<change>]` or, for BIP45,
+    /// `m/45'/<cosigner>/<change>`
+    ///
+    /// Unknown purposes with the same shape are returned as [`Purpose::Custom`].
     pub fn from_derivation_path(path: &DerivationPath) -> Result<Self, Error> {
         let mut path = path.into_iter();
-        let purpose = path.next();
-        let coin = path.next();
-        let account = path.next();
-        let change = path.next();
+        let purpose: u32 = match path.next() {
+            Some(ChildNumber::Hardened { index }) => *index,
+            _ => return Err(Error::UnsupportedDerivationPath),
+        };
 
-        let coin: u32 = match coin {
+        if purpose == 45 {
+            let cosigner: u32 = match path.next() {
+                Some(ChildNumber::Normal { index }) => *index,
+                _ => return Err(Error::UnsupportedDerivationPath),
+            };
+            let change: bool = match path.next() {
+                Some(ChildNumber::Normal { index }) => *index != 0,
+                _ => return Err(Error::UnsupportedDerivationPath),
+            };
+            return Ok(Self {
+                purpose: Purpose::BIP45,
+                coin: None,
+                account: cosigner,
+                change,
+            });
+        }
+
+        let coin: u32 = match path.next() {
             Some(ChildNumber::Hardened { index: 0 }) => 0,
             Some(ChildNumber::Hardened { index: 1 }) => 1,
             _ => return Err(Error::UnsupportedDerivationPath),
         };
 
-        let account: u32 = match account {
+        let account: u32 = match path.next() {
             Some(ChildNumber::Hardened { index }) => *index,
             _ => return Err(Error::UnsupportedDerivationPath),
         };
 
-        let change: bool = match change {
-            Some(ChildNumber::Normal { index }) => *index != 0,
-            _ => return Err(Error::UnsupportedDerivationPath),
-        };
-
-        match purpose {
-            Some(ChildNumber::Hardened { index: 44 }) => Ok(Self {
-                purpose: Purpose::BIP44,
-                coin,
-                account,
-                change,
-            }),
-            Some(ChildNumber::Hardened { index: 48 }) => {
+        let purpose: Purpose = match purpose {
+            44 => Purpose::BIP44,
+            48 => {
                 let script: ScriptType = match path.next() {
                     Some(ChildNumber::Hardened { index: 1 }) => ScriptType::P2SHWSH,
                     Some(ChildNumber::Hardened { index: 2 }) => ScriptType::P2WSH,
                     Some(ChildNumber::Hardened { index: 3 }) => ScriptType::P2TR,
                     _ => return Err(Error::BIP48ScriptNotFound),
                 };
-                Ok(Self {
-                    purpose: Purpose::BIP48 { script },
-                    coin,
-                    account,
-                    change,
-                })
+                Purpose::BIP48 { script }
             }
-            Some(ChildNumber::Hardened { index: 49 }) => Ok(Self {
-                purpose: Purpose::BIP49,
-                coin,
-                account,
-                change,
-            }),
-            Some(ChildNumber::Hardened { index: 84 }) => Ok(Self {
-                purpose: Purpose::BIP84,
-                coin,
-                account,
-                change,
-            }),
-            Some(ChildNumber::Hardened { index: 86 }) => Ok(Self {
-                purpose: Purpose::BIP86,
-                coin,
-                account,
-                change,
-            }),
-            _ => Err(Error::UnsupportedDerivationPath),
-        }
+            49 => Purpose::BIP49,
+            84 => Purpose::BIP84,
+            86 => Purpose::BIP86,
+            purpose => Purpose::custom(purpose)?,
+        };
+
+        // Account paths stop before the change level
+        let change: bool = match path.next() {
+            Some(ChildNumber::Normal { index }) => *index != 0,
+            None => false,
+            _ => return Err(Error::UnsupportedDerivationPath),
+        };
+
+        Ok(Self {
+            purpose,
+            coin: Some(coin),
+            account,
+            change,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::str::FromStr;
+
+    use super::*;
+
+    fn parse(path: &str) -> Result<ExtendedPath, Error> {
+        ExtendedPath::from_derivation_path(&DerivationPath::from_str(path).unwrap())
+    }
+
+    #[test]
+    fn test_extended_path() {
+        let path = parse("m/84'/1'/2'/1/5").unwrap();
+        assert_eq!(path.purpose, Purpose::BIP84);
+        assert_eq!(path.coin, Some(1));
+        assert_eq!(path.account, 2);
+        assert!(path.change);
+
+        let path = parse("m/48'/0'/0'/2'/0/3").unwrap();
+        assert_eq!(
+            path.purpose,
+            Purpose::BIP48 {
+                script: ScriptType::P2WSH
+            }
+        );
+        assert!(!path.change);
+
+        let path = parse("m/45'/3/1/7").unwrap();
+        assert_eq!(path.purpose, Purpose::BIP45);
+        assert_eq!(path.coin, None);
+        assert_eq!(path.account, 3);
+        assert!(path.change);
+
+        let path = parse("m/1017'/0'/4'/0/0").unwrap();
+        assert_eq!(path.purpose, Purpose::custom(1017).unwrap());
+        assert_eq!(path.account, 4);
+
+        // Account path
+        let path = parse("m/0'/0'/5'").unwrap();
+        assert_eq!(path.purpose, Purpose::custom(0).unwrap());
+        assert_eq!(path.account, 5);
+        assert!(!path.change);
+
+        assert_eq!(
+            parse("m/0'/0'/5'/0'").err(),
+            Some(Error::UnsupportedDerivationPath)
+        );
+        assert_eq!(
+            Purpose::custom(84).err(),
+            Some(bip43::Error::StandardPurpose(84))
+        );
+        assert_eq!(Purpose::custom(1017).map(|p| p.as_u32()), Ok(1017));
+        assert_eq!(
+            parse("m/48'/0'/0'/0/0").err(),
+            Some(Error::BIP48ScriptNotFound)
+        );
     }
 }
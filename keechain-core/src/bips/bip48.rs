@@ -5,12 +5,15 @@
 //!
 //! <https://github.com/bitcoin/bips/blob/master/bip-0048.mediawiki>
 
+use core::fmt;
+
 use bdk::bitcoin::bip32::{ChildNumber, DerivationPath, Error};
 use bdk::bitcoin::Network;
+use serde::{Deserialize, Serialize};
 
 use super::bip32;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 #[repr(u8)]
 pub enum ScriptType {
     P2SHWSH = 1,
@@ -18,6 +21,16 @@ pub enum ScriptType {
     P2TR = 3,
 }
 
+impl fmt::Display for ScriptType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::P2SHWSH => write!(f, "P2SH-P2WSH"),
+            Self::P2WSH => write!(f, "P2WSH"),
+            Self::P2TR => write!(f, "P2TR"),
+        }
+    }
+}
+
 impl ScriptType {
     pub fn as_u32(&self) -> u32 {
         *self as u32
@@ -7,20 +7,46 @@
 
 use core::fmt;
 
-use bdk::bitcoin::bip32;
+use bdk::bitcoin::bip32::{self, ChainCode};
 use bdk::bitcoin::hashes::hmac::{Hmac, HmacEngine};
 use bdk::bitcoin::hashes::{sha512, Hash, HashEngine};
-use bdk::bitcoin::secp256k1::{Secp256k1, Signing};
-use bdk::bitcoin::Network;
-use bip39::Mnemonic;
+use bdk::bitcoin::secp256k1::{self, Secp256k1, SecretKey, Signing};
+use bdk::bitcoin::{Network, PrivateKey};
+use bip39::{Language, Mnemonic};
+use sha3::digest::{ExtendableOutput, Update, XofReader};
+use sha3::Shake256;
 
 use super::bip32::{Bip32, ChildNumber, DerivationPath, ExtendedPrivKey};
 use crate::types::{Index, WordCount};
+use crate::util::{base64, hex};
+
+const BIP85_PURPOSE: u32 = 83696968;
+
+const APP_BIP39: u32 = 39;
+const APP_HEX: u32 = 128169;
+const APP_WIF: u32 = 2;
+const APP_XPRV: u32 = 32;
+const APP_PWD_BASE64: u32 = 707764;
+const APP_PWD_BASE85: u32 = 707785;
+const APP_DICE: u32 = 89101;
+
+/// RFC 1924 alphabet
+const BASE85_ALPHABET: &[u8; 85] =
+    b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz!#$%&()*+-;<=>?@^_`{|}~";
 
 #[derive(Debug, PartialEq, Eq)]
 pub enum Error {
     BIP32(bip32::Error),
     BIP39(bip39::Error),
+    Secp256k1(secp256k1::Error),
+    /// HEX length must be between 16 and 64 bytes
+    InvalidNumBytes(u32),
+    /// Password length out of the application range
+    InvalidPasswordLength(u32),
+    /// Dice must have at least 2 sides and 1 roll
+    InvalidDice,
+    /// Language without a BIP85 code
+    UnsupportedLanguage(Language),
 }
 
 impl std::error::Error for Error {}
@@ -30,6 +56,18 @@ impl fmt::Display for Error {
         match self {
             Self::BIP32(e) => write!(f, "BIP32: {e}"),
             Self::BIP39(e) => write!(f, "BIP39: {e}"),
+            Self::Secp256k1(e) => write!(f, "Secp256k1: {e}"),
+            Self::InvalidNumBytes(len) => {
+                write!(
+                    f,
+                    "Invalid number of bytes: {len} (must be between 16 and 64)"
+                )
+            }
+            Self::InvalidPasswordLength(len) => write!(f, "Invalid password length: {len}"),
+            Self::InvalidDice => write!(f, "Invalid dice (at least 2 sides and 1 roll)"),
+            Self::UnsupportedLanguage(language) => {
+                write!(f, "Unsupported language: {language}")
+            }
         }
     }
 }
@@ -46,9 +84,89 @@ impl From<bip39::Error> for Error {
     }
 }
 
+impl From<secp256k1::Error> for Error {
+    fn from(e: secp256k1::Error) -> Self {
+        Self::Secp256k1(e)
+    }
+}
+
+/// BIP85 application
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Application {
+    /// BIP39 mnemonic
+    Mnemonic {
+        language: Language,
+        word_count: WordCount,
+    },
+    /// Raw entropy (between 16 and 64 bytes)
+    Hex { num_bytes: u32 },
+    /// HD-Seed WIF (compressed)
+    Wif,
+    /// Extended private key
+    Xprv,
+    /// Base64 password (between 20 and 86 chars)
+    PwdBase64 { length: u32 },
+    /// Base85 password (between 10 and 80 chars)
+    PwdBase85 { length: u32 },
+    /// Dice rolls (from 0 to `sides - 1`)
+    Dice { sides: u32, rolls: u32 },
+}
+
+impl fmt::Display for Application {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Mnemonic { .. } => write!(f, "BIP39"),
+            Self::Hex { .. } => write!(f, "HEX"),
+            Self::Wif => write!(f, "WIF"),
+            Self::Xprv => write!(f, "XPRV"),
+            Self::PwdBase64 { .. } => write!(f, "PWD BASE64"),
+            Self::PwdBase85 { .. } => write!(f, "PWD BASE85"),
+            Self::Dice { .. } => write!(f, "DICE"),
+        }
+    }
+}
+
+/// Derive the 64 bytes of entropy at `m/83696968'/<path>`
+fn derive_entropy<C>(
+    root: &ExtendedPrivKey,
+    path: &[u32],
+    secp: &Secp256k1<C>,
+) -> Result<[u8; 64], Error>
+where
+    C: Signing,
+{
+    let mut full_path: Vec<ChildNumber> = vec![ChildNumber::from_hardened_idx(BIP85_PURPOSE)?];
+    for index in path.iter() {
+        full_path.push(ChildNumber::from_hardened_idx(*index)?);
+    }
+    let path: DerivationPath = DerivationPath::from(full_path);
+    let derived: ExtendedPrivKey = root.derive_priv(secp, &path)?;
+
+    let mut h = HmacEngine::<sha512::Hash>::new(b"bip-entropy-from-k");
+    h.input(&derived.private_key.secret_bytes());
+    Ok(Hmac::from_engine(h).to_byte_array())
+}
+
+/// BIP85 language code
+fn language_code(language: Language) -> Result<u32, Error> {
+    match language {
+        Language::English => Ok(0),
+        Language::Japanese => Ok(1),
+        Language::Korean => Ok(2),
+        Language::Spanish => Ok(3),
+        Language::SimplifiedChinese => Ok(4),
+        Language::TraditionalChinese => Ok(5),
+        Language::French => Ok(6),
+        Language::Italian => Ok(7),
+        Language::Czech => Ok(8),
+        language => Err(Error::UnsupportedLanguage(language)),
+    }
+}
+
 pub trait FromBip85: Sized {
     fn from_bip85<C>(
         root: &ExtendedPrivKey,
+        language: Language,
         word_count: WordCount,
         index: Index,
         secp: &Secp256k1<C>,
@@ -60,6 +178,7 @@ pub trait FromBip85: Sized {
 impl FromBip85 for Mnemonic {
     fn from_bip85<C>(
         root: &ExtendedPrivKey,
+        language: Language,
         word_count: WordCount,
         index: Index,
         secp: &Secp256k1<C>,
@@ -68,22 +187,189 @@ impl FromBip85 for Mnemonic {
         C: Signing,
     {
         let word_count: u32 = word_count.as_u32();
-        let path: Vec<ChildNumber> = vec![
-            ChildNumber::from_hardened_idx(83696968)?,
-            ChildNumber::from_hardened_idx(39)?,
-            ChildNumber::from_hardened_idx(0)?,
-            ChildNumber::from_hardened_idx(word_count)?,
-            ChildNumber::from_hardened_idx(index.as_u32())?,
+        let path: [u32; 4] = [
+            APP_BIP39,
+            language_code(language)?,
+            word_count,
+            index.as_u32(),
         ];
-        let path: DerivationPath = DerivationPath::from(path);
-        let derived: ExtendedPrivKey = root.derive_priv(secp, &path)?;
-
-        let mut h = HmacEngine::<sha512::Hash>::new(b"bip-entropy-from-k");
-        h.input(&derived.private_key.secret_bytes());
-        let data: [u8; 64] = Hmac::from_engine(h).to_byte_array();
+        let data: [u8; 64] = derive_entropy(root, &path, secp)?;
         let len: u32 = word_count * 4 / 3;
-        Ok(Mnemonic::from_entropy(&data[0..len as usize])?)
+        Ok(Mnemonic::from_entropy_in(language, &data[0..len as usize])?)
+    }
+}
+
+/// HEX application: `num_bytes` of raw entropy
+pub fn derive_hex<C>(
+    root: &ExtendedPrivKey,
+    num_bytes: u32,
+    index: Index,
+    secp: &Secp256k1<C>,
+) -> Result<Vec<u8>, Error>
+where
+    C: Signing,
+{
+    if !(16..=64).contains(&num_bytes) {
+        return Err(Error::InvalidNumBytes(num_bytes));
+    }
+    let data: [u8; 64] = derive_entropy(root, &[APP_HEX, num_bytes, index.as_u32()], secp)?;
+    Ok(data[..num_bytes as usize].to_vec())
+}
+
+/// WIF application
+pub fn derive_wif<C>(
+    root: &ExtendedPrivKey,
+    index: Index,
+    secp: &Secp256k1<C>,
+) -> Result<PrivateKey, Error>
+where
+    C: Signing,
+{
+    let data: [u8; 64] = derive_entropy(root, &[APP_WIF, index.as_u32()], secp)?;
+    let key = SecretKey::from_slice(&data[..32])?;
+    Ok(PrivateKey::new(key, Network::Bitcoin))
+}
+
+/// XPRV application: first 32 bytes are the chain code, the others the private key
+pub fn derive_xprv<C>(
+    root: &ExtendedPrivKey,
+    index: Index,
+    secp: &Secp256k1<C>,
+) -> Result<ExtendedPrivKey, Error>
+where
+    C: Signing,
+{
+    let data: [u8; 64] = derive_entropy(root, &[APP_XPRV, index.as_u32()], secp)?;
+    let mut chain_code: [u8; 32] = [0u8; 32];
+    chain_code.copy_from_slice(&data[..32]);
+    Ok(ExtendedPrivKey {
+        network: Network::Bitcoin,
+        depth: 0,
+        parent_fingerprint: Default::default(),
+        child_number: ChildNumber::Normal { index: 0 },
+        private_key: SecretKey::from_slice(&data[32..])?,
+        chain_code: ChainCode::from(chain_code),
+    })
+}
+
+/// PWD BASE64 application
+pub fn derive_pwd_base64<C>(
+    root: &ExtendedPrivKey,
+    length: u32,
+    index: Index,
+    secp: &Secp256k1<C>,
+) -> Result<String, Error>
+where
+    C: Signing,
+{
+    if !(20..=86).contains(&length) {
+        return Err(Error::InvalidPasswordLength(length));
+    }
+    let data: [u8; 64] = derive_entropy(root, &[APP_PWD_BASE64, length, index.as_u32()], secp)?;
+    let mut password: String = base64::encode(data);
+    password.truncate(length as usize);
+    Ok(password)
+}
+
+/// PWD BASE85 application
+pub fn derive_pwd_base85<C>(
+    root: &ExtendedPrivKey,
+    length: u32,
+    index: Index,
+    secp: &Secp256k1<C>,
+) -> Result<String, Error>
+where
+    C: Signing,
+{
+    if !(10..=80).contains(&length) {
+        return Err(Error::InvalidPasswordLength(length));
+    }
+    let data: [u8; 64] = derive_entropy(root, &[APP_PWD_BASE85, length, index.as_u32()], secp)?;
+    let mut password: String = base85_encode(&data);
+    password.truncate(length as usize);
+    Ok(password)
+}
+
+/// DICE application
+pub fn derive_dice<C>(
+    root: &ExtendedPrivKey,
+    sides: u32,
+    rolls: u32,
+    index: Index,
+    secp: &Secp256k1<C>,
+) -> Result<Vec<u32>, Error>
+where
+    C: Signing,
+{
+    if sides < 2 || rolls < 1 {
+        return Err(Error::InvalidDice);
     }
+    let data: [u8; 64] = derive_entropy(root, &[APP_DICE, sides, rolls, index.as_u32()], secp)?;
+
+    // BIP85-DRNG
+    let mut shake = Shake256::default();
+    shake.update(&data);
+    let mut drng = shake.finalize_xof();
+
+    let bits_per_roll: u32 = 32 - (sides - 1).leading_zeros();
+    let bytes_per_roll: usize = ((bits_per_roll + 7) / 8) as usize;
+    let mut result: Vec<u32> = Vec::with_capacity(rolls as usize);
+    while result.len() < rolls as usize {
+        let mut buf: [u8; 4] = [0u8; 4];
+        drng.read(&mut buf[4 - bytes_per_roll..]);
+        let trial: u32 = u32::from_be_bytes(buf) >> (bytes_per_roll as u32 * 8 - bits_per_roll);
+        if trial < sides {
+            result.push(trial);
+        }
+    }
+    Ok(result)
+}
+
+/// Derive a BIP85 application and format it for display
+pub fn derive<C>(
+    root: &ExtendedPrivKey,
+    application: Application,
+    index: Index,
+    secp: &Secp256k1<C>,
+) -> Result<String, Error>
+where
+    C: Signing,
+{
+    Ok(match application {
+        Application::Mnemonic {
+            language,
+            word_count,
+        } => Mnemonic::from_bip85(root, language, word_count, index, secp)?.to_string(),
+        Application::Hex { num_bytes } => hex::encode(derive_hex(root, num_bytes, index, secp)?),
+        Application::Wif => derive_wif(root, index, secp)?.to_wif(),
+        Application::Xprv => derive_xprv(root, index, secp)?.to_string(),
+        Application::PwdBase64 { length } => derive_pwd_base64(root, length, index, secp)?,
+        Application::PwdBase85 { length } => derive_pwd_base85(root, length, index, secp)?,
+        Application::Dice { sides, rolls } => derive_dice(root, sides, rolls, index, secp)?
+            .into_iter()
+            .map(|roll| roll.to_string())
+            .collect::<Vec<String>>()
+            .join(","),
+    })
+}
+
+fn base85_encode(data: &[u8]) -> String {
+    let mut output: String = String::with_capacity(data.len() * 5 / 4 + 5);
+    for chunk in data.chunks(4) {
+        let mut buf: [u8; 4] = [0u8; 4];
+        buf[..chunk.len()].copy_from_slice(chunk);
+        let mut value: u32 = u32::from_be_bytes(buf);
+        let mut digits: [u8; 5] = [0u8; 5];
+        for digit in digits.iter_mut().rev() {
+            *digit = BASE85_ALPHABET[(value % 85) as usize];
+            value /= 85;
+        }
+        // Partial chunk: like Python `base64.b85encode`, without padding
+        for digit in digits.iter().take(chunk.len() + 1) {
+            output.push(char::from(*digit));
+        }
+    }
+    output
 }
 
 pub trait Bip85: Sized + Bip32
@@ -95,6 +381,7 @@ where
     /// <https://github.com/bitcoin/bips/blob/master/bip-0085.mediawiki>
     fn derive_bip85_mnemonic<C>(
         &self,
+        language: Language,
         word_count: WordCount,
         index: Index,
         secp: &Secp256k1<C>,
@@ -103,7 +390,23 @@ where
         C: Signing,
     {
         let root: ExtendedPrivKey = self.to_bip32_root_key(Network::Bitcoin)?;
-        Mnemonic::from_bip85(&root, word_count, index, secp)
+        Mnemonic::from_bip85(&root, language, word_count, index, secp)
+    }
+
+    /// Derive BIP85 application, formatted for display
+    ///
+    /// <https://github.com/bitcoin/bips/blob/master/bip-0085.mediawiki>
+    fn derive_bip85<C>(
+        &self,
+        application: Application,
+        index: Index,
+        secp: &Secp256k1<C>,
+    ) -> Result<String, Error>
+    where
+        C: Signing,
+    {
+        let root: ExtendedPrivKey = self.to_bip32_root_key(Network::Bitcoin)?;
+        derive(&root, application, index, secp)
     }
 }
 
@@ -125,30 +428,42 @@ mod tests {
         let passphrase: Option<&str> = Some("mypassphrase");
         let seed = Seed::new(mnemonic, passphrase);
 
-        let root = ExtendedPrivKey::new_master(NETWORK, &seed.to_bytes()).unwrap();
+        let root = ExtendedPrivKey::new_master(NETWORK, &seed.to_bytes().unwrap()).unwrap();
 
         // Words: 12
         // Index: 0
         assert_eq!(
-            Mnemonic::from_bip85(&root, WordCount::W12, Index::new(0).unwrap(), &secp)
-                .unwrap()
-                .to_string(),
+            Mnemonic::from_bip85(
+                &root,
+                Language::English,
+                WordCount::W12,
+                Index::new(0).unwrap(),
+                &secp
+            )
+            .unwrap()
+            .to_string(),
             "gap gun smooth leader muscle renew impulse hundred twin enact fetch zoo".to_string()
         );
 
         // Words: 12
         // Index: 1
         assert_eq!(
-            Mnemonic::from_bip85(&root, WordCount::W12, Index::new(1).unwrap(), &secp)
-                .unwrap()
-                .to_string(),
+            Mnemonic::from_bip85(
+                &root,
+                Language::English,
+                WordCount::W12,
+                Index::new(1).unwrap(),
+                &secp
+            )
+            .unwrap()
+            .to_string(),
             "join siren history age snack dial initial raise kick enter vintage rabbit".to_string()
         );
 
         // Words: 24
         // Index: 57
         assert_eq!(
-            Mnemonic::from_bip85(&root, WordCount::W24, Index::new(57).unwrap(), &secp)
+            Mnemonic::from_bip85(&root, Language::English, WordCount::W24, Index::new(57).unwrap(), &secp)
                 .unwrap()
                 .to_string(),
             "this supply project flush south sport acid focus damp pulp hundred convince ramp mandate picnic area bracket group pact piano coconut cigar decline actress".to_string()
@@ -156,9 +471,15 @@ mod tests {
 
         // Test wrong seed
         assert_ne!(
-            Mnemonic::from_bip85(&root, WordCount::W12, Index::new(12).unwrap(), &secp)
-                .unwrap()
-                .to_string(),
+            Mnemonic::from_bip85(
+                &root,
+                Language::English,
+                WordCount::W12,
+                Index::new(12).unwrap(),
+                &secp
+            )
+            .unwrap()
+            .to_string(),
             "pride drama job inform cross recall vapor lake weasel basket curve pencil".to_string()
         )
     }
@@ -173,25 +494,35 @@ mod tests {
         // Words: 12
         // Index: 0
         assert_eq!(
-            seed.derive_bip85_mnemonic(WordCount::W12, Index::new(0).unwrap(), &secp)
-                .unwrap()
-                .to_string(),
+            seed.derive_bip85_mnemonic(
+                Language::English,
+                WordCount::W12,
+                Index::new(0).unwrap(),
+                &secp
+            )
+            .unwrap()
+            .to_string(),
             "gap gun smooth leader muscle renew impulse hundred twin enact fetch zoo".to_string()
         );
 
         // Words: 12
         // Index: 1
         assert_eq!(
-            seed.derive_bip85_mnemonic(WordCount::W12, Index::new(1).unwrap(), &secp)
-                .unwrap()
-                .to_string(),
+            seed.derive_bip85_mnemonic(
+                Language::English,
+                WordCount::W12,
+                Index::new(1).unwrap(),
+                &secp
+            )
+            .unwrap()
+            .to_string(),
             "join siren history age snack dial initial raise kick enter vintage rabbit".to_string()
         );
 
         // Words: 24
         // Index: 57
         assert_eq!(
-            seed.derive_bip85_mnemonic(WordCount::W24, Index::new(57).unwrap(), &secp)
+            seed.derive_bip85_mnemonic(Language::English, WordCount::W24, Index::new(57).unwrap(), &secp)
                 .unwrap()
                 .to_string(),
             "this supply project flush south sport acid focus damp pulp hundred convince ramp mandate picnic area bracket group pact piano coconut cigar decline actress".to_string()
@@ -199,13 +530,131 @@ mod tests {
 
         // Test wrong seed
         assert_ne!(
-            seed.derive_bip85_mnemonic(WordCount::W12, Index::new(12).unwrap(), &secp)
-                .unwrap()
-                .to_string(),
+            seed.derive_bip85_mnemonic(
+                Language::English,
+                WordCount::W12,
+                Index::new(12).unwrap(),
+                &secp
+            )
+            .unwrap()
+            .to_string(),
             "pride drama job inform cross recall vapor lake weasel basket curve pencil".to_string()
         )
     }
 
+    fn bip85_root() -> ExtendedPrivKey {
+        ExtendedPrivKey::from_str("xprv9s21ZrQH143K2LBWUUQRFXhucrQqBpKdRRxNVq2zBqsx8HVqFk2uYo8kmbaLLHRdqtQpUm98uKfu3vca1LqdGhUtyoFnCNkfmXRyPXLjbKb").unwrap()
+    }
+
+    #[test]
+    fn test_derive_entropy() {
+        let secp = Secp256k1::new();
+        let root = bip85_root();
+
+        assert_eq!(
+            hex::encode(derive_entropy(&root, &[0, 0], &secp).unwrap()),
+            "efecfbccffea313214232d29e71563d941229afb4338c21f9517c41aaa0d16f00b83d2a09ef747e7a64e8e2bd5a14869e693da66ce94ac2da570ab7ee48618f7"
+        );
+        assert_eq!(
+            hex::encode(derive_entropy(&root, &[0, 1], &secp).unwrap()),
+            "70c6e3e8ebee8dc4c0dbba66076819bb8c09672527c4277ca8729532ad711872218f826919f6b67218adde99018a6df9095ab2b58d803b5b93ec9802085a690e"
+        );
+    }
+
+    #[test]
+    fn test_applications() {
+        let secp = Secp256k1::new();
+        let root = bip85_root();
+        let index = Index::new(0).unwrap();
+
+        assert_eq!(
+            derive(&root, Application::Hex { num_bytes: 64 }, index, &secp).unwrap(),
+            "492db4698cf3b73a5a24998aa3e9d7fa96275d85724a91e71aa2d645442f878555d078fd1f1f67e368976f04137b1f7a0d19232136ca50c44614af72b5582a5c"
+        );
+        assert_eq!(
+            derive(&root, Application::Wif, index, &secp).unwrap(),
+            "Kzyv4uF39d4Jrw2W7UryTHwZr1zQVNk4dAFyqE6BuMrMh1Za7uhp"
+        );
+        assert_eq!(
+            derive(&root, Application::Xprv, index, &secp).unwrap(),
+            "xprv9s21ZrQH143K2srSbCSg4m4kLvPMzcWydgmKEnMmoZUurYuBuYG46c6P71UGXMzmriLzCCBvKQWBUv3vPB3m1SATMhp3uEjXHJ42jFg7myX"
+        );
+        assert_eq!(
+            derive(&root, Application::PwdBase64 { length: 21 }, index, &secp).unwrap(),
+            "dKLoepugzdVJvdL56ogNV"
+        );
+        assert_eq!(
+            derive(&root, Application::PwdBase85 { length: 12 }, index, &secp).unwrap(),
+            "_s`{TW89)i4`"
+        );
+        assert_eq!(
+            derive(
+                &root,
+                Application::Dice {
+                    sides: 6,
+                    rolls: 10
+                },
+                index,
+                &secp
+            )
+            .unwrap(),
+            "1,0,0,2,0,1,5,5,2,4"
+        );
+
+        assert_eq!(
+            derive_hex(&root, 15, index, &secp),
+            Err(Error::InvalidNumBytes(15))
+        );
+        assert_eq!(
+            derive_pwd_base85(&root, 81, index, &secp),
+            Err(Error::InvalidPasswordLength(81))
+        );
+        assert_eq!(
+            derive_dice(&root, 1, 10, index, &secp),
+            Err(Error::InvalidDice)
+        );
+    }
+
+    #[test]
+    fn test_languages() {
+        let secp = Secp256k1::new();
+        let root = bip85_root();
+        let index = Index::new(0).unwrap();
+
+        for (language, code) in [
+            (Language::English, 0),
+            (Language::Japanese, 1),
+            (Language::Spanish, 3),
+            (Language::Czech, 8),
+        ] {
+            let mnemonic =
+                Mnemonic::from_bip85(&root, language, WordCount::W12, index, &secp).unwrap();
+            let entropy = derive_entropy(&root, &[APP_BIP39, code, 12, 0], &secp).unwrap();
+            assert_eq!(mnemonic.language(), language);
+            assert_eq!(mnemonic.to_entropy(), entropy[..16].to_vec());
+        }
+
+        // BIP85 vector (English, 12 words)
+        assert_eq!(
+            derive(
+                &root,
+                Application::Mnemonic {
+                    language: Language::English,
+                    word_count: WordCount::W12
+                },
+                index,
+                &secp
+            )
+            .unwrap(),
+            "girl mad pet galaxy egg matter matrix prison refuse sense ordinary nose"
+        );
+
+        assert_eq!(
+            Mnemonic::from_bip85(&root, Language::Portuguese, WordCount::W12, index, &secp),
+            Err(Error::UnsupportedLanguage(Language::Portuguese))
+        );
+    }
+
     #[test]
     fn test_eq_bip85_result() {
         let secp = Secp256k1::new();
@@ -213,18 +662,44 @@ mod tests {
         let passphrase: Option<&str> = Some("mypassphrase");
         let seed = Seed::new(mnemonic, passphrase);
 
-        let root = ExtendedPrivKey::new_master(Network::Testnet, &seed.to_bytes()).unwrap();
+        let root =
+            ExtendedPrivKey::new_master(Network::Testnet, &seed.to_bytes().unwrap()).unwrap();
         assert_eq!(
-            seed.derive_bip85_mnemonic(WordCount::W12, Index::new(0).unwrap(), &secp)
-                .unwrap(),
-            Mnemonic::from_bip85(&root, WordCount::W12, Index::new(0).unwrap(), &secp).unwrap()
+            seed.derive_bip85_mnemonic(
+                Language::English,
+                WordCount::W12,
+                Index::new(0).unwrap(),
+                &secp
+            )
+            .unwrap(),
+            Mnemonic::from_bip85(
+                &root,
+                Language::English,
+                WordCount::W12,
+                Index::new(0).unwrap(),
+                &secp
+            )
+            .unwrap()
         );
 
-        let root = ExtendedPrivKey::new_master(Network::Regtest, &seed.to_bytes()).unwrap();
+        let root =
+            ExtendedPrivKey::new_master(Network::Regtest, &seed.to_bytes().unwrap()).unwrap();
         assert_eq!(
-            seed.derive_bip85_mnemonic(WordCount::W24, Index::new(4).unwrap(), &secp)
-                .unwrap(),
-            Mnemonic::from_bip85(&root, WordCount::W24, Index::new(4).unwrap(), &secp).unwrap()
+            seed.derive_bip85_mnemonic(
+                Language::English,
+                WordCount::W24,
+                Index::new(4).unwrap(),
+                &secp
+            )
+            .unwrap(),
+            Mnemonic::from_bip85(
+                &root,
+                Language::English,
+                WordCount::W24,
+                Index::new(4).unwrap(),
+                &secp
+            )
+            .unwrap()
         );
     }
 }
@@ -5,7 +5,9 @@
 //!
 //! <https://github.com/bitcoin/bips>
 
+pub mod bip129;
 pub mod bip32;
+pub mod bip388;
 pub mod bip39;
 pub mod bip43;
 pub mod bip44;
@@ -0,0 +1,253 @@
+// Copyright (c) 2022-2023 Yuki Kishimoto
+// Distributed under the MIT software license
+
+//! Blinded xpubs
+//!
+//! Account keys extended with random unhardened indices, to share with a collaborative custody
+//! provider without linking them to the other wallets of the keychain.
+
+use core::fmt;
+use core::str::FromStr;
+
+use bdk::bitcoin::secp256k1::{rand, Secp256k1, Signing};
+use bdk::miniscript::descriptor::{DescriptorPublicKey, DescriptorXKey, Wildcard};
+use serde::{Deserialize, Serialize};
+
+use crate::bips::bip32::{
+    self, ChildNumber, DerivationPath, ExtendedPrivKey, ExtendedPubKey, Fingerprint,
+};
+use crate::descriptors;
+
+/// Number of random indices appended to the account path
+pub const BLINDING_DEPTH: usize = 4;
+
+#[derive(Debug)]
+pub enum Error {
+    BIP32(bip32::Error),
+    Descriptors(descriptors::Error),
+    EmptyLabel,
+    InvalidBlindingPath(String),
+    UnsupportedKey(String),
+}
+
+impl std::error::Error for Error {}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::BIP32(e) => write!(f, "BIP32: {e}"),
+            Self::Descriptors(e) => write!(f, "Descriptors: {e}"),
+            Self::EmptyLabel => write!(f, "Empty blinded key label"),
+            Self::InvalidBlindingPath(path) => write!(
+                f,
+                "Invalid blinding path {path}: expected at least 1 unhardened index"
+            ),
+            Self::UnsupportedKey(key) => write!(
+                f,
+                "Unsupported key {key}: expected an extended public key with the blinded origin"
+            ),
+        }
+    }
+}
+
+impl From<bip32::Error> for Error {
+    fn from(e: bip32::Error) -> Self {
+        Self::BIP32(e)
+    }
+}
+
+impl From<descriptors::Error> for Error {
+    fn from(e: descriptors::Error) -> Self {
+        Self::Descriptors(e)
+    }
+}
+
+/// Random blinding path of [`BLINDING_DEPTH`] unhardened 31-bit indices
+pub fn random_blinding_path() -> DerivationPath {
+    let path: Vec<ChildNumber> = (0..BLINDING_DEPTH)
+        .map(|_| ChildNumber::Normal {
+            index: rand::random::<u32>() & 0x7FFF_FFFF,
+        })
+        .collect();
+    DerivationPath::from(path)
+}
+
+/// Blinded account key, saved in the keychain to find it again when signing
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "BlindedKeyRaw", into = "BlindedKeyRaw")]
+pub struct BlindedKey {
+    label: String,
+    root_fingerprint: Fingerprint,
+    account: DerivationPath,
+    blinding: DerivationPath,
+    xpub: ExtendedPubKey,
+}
+
+#[derive(Serialize, Deserialize)]
+struct BlindedKeyRaw {
+    label: String,
+    /// Key with the full blinded origin
+    key: String,
+    blinding: String,
+}
+
+impl From<BlindedKey> for BlindedKeyRaw {
+    fn from(blinded: BlindedKey) -> Self {
+        Self {
+            label: blinded.label.clone(),
+            key: blinded.key().to_string(),
+            blinding: blinded.blinding.to_string(),
+        }
+    }
+}
+
+impl TryFrom<BlindedKeyRaw> for BlindedKey {
+    type Error = Error;
+
+    fn try_from(raw: BlindedKeyRaw) -> Result<Self, Self::Error> {
+        let key: DescriptorPublicKey =
+            DescriptorPublicKey::from_str(&raw.key).map_err(descriptors::Error::from)?;
+        let blinding: DerivationPath = DerivationPath::from_str(&raw.blinding)?;
+        if let DescriptorPublicKey::XPub(xkey) = &key {
+            if let Some((root_fingerprint, path)) = &xkey.origin {
+                let path: &[ChildNumber] = path.as_ref();
+                let suffix: &[ChildNumber] = blinding.as_ref();
+                if xkey.derivation_path.is_master()
+                    && !suffix.is_empty()
+                    && path.len() > suffix.len()
+                    && path.ends_with(suffix)
+                {
+                    return Ok(Self {
+                        label: raw.label,
+                        root_fingerprint: *root_fingerprint,
+                        account: DerivationPath::from(&path[..path.len() - suffix.len()]),
+                        blinding,
+                        xpub: xkey.xkey,
+                    });
+                }
+            }
+        }
+        Err(Error::UnsupportedKey(raw.key))
+    }
+}
+
+impl BlindedKey {
+    /// Blind the key at `account` with `blinding` (see [`random_blinding_path`])
+    pub fn new<S, C>(
+        label: S,
+        root: &ExtendedPrivKey,
+        account: &DerivationPath,
+        blinding: &DerivationPath,
+        secp: &Secp256k1<C>,
+    ) -> Result<Self, Error>
+    where
+        S: Into<String>,
+        C: Signing,
+    {
+        let label: String = label.into();
+        if label.trim().is_empty() {
+            return Err(Error::EmptyLabel);
+        }
+        if blinding.is_master() || blinding.into_iter().any(|child| child.is_hardened()) {
+            return Err(Error::InvalidBlindingPath(blinding.to_string()));
+        }
+
+        let derived: ExtendedPrivKey = root.derive_priv(secp, &account.extend(blinding))?;
+        Ok(Self {
+            label,
+            root_fingerprint: root.fingerprint(secp),
+            account: account.clone(),
+            blinding: blinding.clone(),
+            xpub: ExtendedPubKey::from_priv(secp, &derived),
+        })
+    }
+
+    pub fn label(&self) -> String {
+        self.label.clone()
+    }
+
+    /// Key with the full blinded origin (ex. `[fingerprint/48'/0'/0'/2'/a/b/c/d]xpub...`), to export
+    pub fn key(&self) -> DescriptorPublicKey {
+        DescriptorPublicKey::XPub(DescriptorXKey {
+            origin: Some((self.root_fingerprint, self.path())),
+            xkey: self.xpub,
+            derivation_path: DerivationPath::master(),
+            wildcard: Wildcard::None,
+        })
+    }
+
+    pub fn account_path(&self) -> DerivationPath {
+        self.account.clone()
+    }
+
+    pub fn blinding_path(&self) -> DerivationPath {
+        self.blinding.clone()
+    }
+
+    /// Full derivation path from the root (account and blinding paths)
+    pub fn path(&self) -> DerivationPath {
+        self.account.extend(&self.blinding)
+    }
+
+    pub fn root_fingerprint(&self) -> Fingerprint {
+        self.root_fingerprint
+    }
+
+    /// Fingerprint of the blinded xpub itself
+    ///
+    /// Coordinators that drop the blinded origin use it as root of the key origins.
+    pub fn fingerprint(&self) -> Fingerprint {
+        self.xpub.fingerprint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bdk::bitcoin::Network;
+
+    use super::*;
+
+    #[test]
+    fn test_blinded_key() {
+        let secp = Secp256k1::new();
+        let root = ExtendedPrivKey::new_master(Network::Testnet, &[1u8; 32]).unwrap();
+        let account = DerivationPath::from_str("m/48'/1'/0'/2'").unwrap();
+        let blinding = DerivationPath::from_str("m/1534566890/7/2147483647/0").unwrap();
+
+        let blinded = BlindedKey::new("Provider", &root, &account, &blinding, &secp).unwrap();
+        let full = DerivationPath::from_str("m/48'/1'/0'/2'/1534566890/7/2147483647/0").unwrap();
+        assert_eq!(blinded.path(), full);
+        assert_eq!(blinded.account_path(), account);
+        assert_eq!(blinded.root_fingerprint(), root.fingerprint(&secp));
+
+        let xpub = ExtendedPubKey::from_priv(&secp, &root.derive_priv(&secp, &full).unwrap());
+        assert_eq!(blinded.fingerprint(), xpub.fingerprint());
+        assert_eq!(
+            blinded.key().to_string(),
+            format!(
+                "[{}/48'/1'/0'/2'/1534566890/7/2147483647/0]{xpub}",
+                root.fingerprint(&secp)
+            )
+        );
+
+        // Serialization
+        let json = serde_json::to_string(&blinded).unwrap();
+        let deserialized: BlindedKey = serde_json::from_str(&json).unwrap();
+        assert_eq!(blinded, deserialized);
+
+        // Hardened blinding
+        let hardened = DerivationPath::from_str("m/1/2'").unwrap();
+        assert!(matches!(
+            BlindedKey::new("Provider", &root, &account, &hardened, &secp),
+            Err(Error::InvalidBlindingPath(..))
+        ));
+    }
+
+    #[test]
+    fn test_random_blinding_path() {
+        let path: DerivationPath = random_blinding_path();
+        assert_eq!(path.len(), BLINDING_DEPTH);
+        assert!(path.into_iter().all(|child| child.is_normal()));
+        assert_ne!(path, random_blinding_path());
+    }
+}
@@ -0,0 +1,306 @@
+// Copyright (c) 2022-2023 Yuki Kishimoto
+// Distributed under the MIT software license
+
+//! AEZ v5
+//!
+//! <https://web.cs.ucdavis.edu/~rogaway/aez/>
+//!
+//! Only AEZ-tiny is implemented (ciphertexts shorter than 32 bytes): it's what aezeed uses.
+//!
+//! Not yet checked against the reference test vectors: the output doesn't match LND's aezeed
+//! vectors, so treat it as keechain-local until it does.
+
+use core::fmt;
+
+use aes::hazmat::cipher_round;
+use aes::Block;
+use blake2::digest::{Update, VariableOutput};
+use blake2::Blake2bVar;
+
+const BLOCK_SIZE: usize = 16;
+const EXTRACTED_KEY_SIZE: usize = 3 * BLOCK_SIZE;
+const MAX_TINY_SIZE: usize = 2 * BLOCK_SIZE;
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum Error {
+    /// Message empty or too long for AEZ-tiny
+    UnsupportedLength,
+    /// Ciphertext shorter than the authenticator
+    InvalidCiphertext,
+    /// Authentication failed (wrong key or corrupted ciphertext)
+    Authentication,
+}
+
+impl std::error::Error for Error {}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnsupportedLength => write!(f, "unsupported message length"),
+            Self::InvalidCiphertext => write!(f, "invalid ciphertext"),
+            Self::Authentication => write!(f, "invalid key or corrupted ciphertext"),
+        }
+    }
+}
+
+fn double(x: u128) -> u128 {
+    (x << 1) ^ ((x >> 127) * 0x87)
+}
+
+fn mult(x: u128, mut n: u32) -> u128 {
+    let mut x: u128 = x;
+    let mut result: u128 = 0;
+    while n > 0 {
+        if n & 1 == 1 {
+            result ^= x;
+        }
+        x = double(x);
+        n >>= 1;
+    }
+    result
+}
+
+struct Aez {
+    i: u128,
+    j: u128,
+    l: u128,
+}
+
+impl Aez {
+    fn new(key: &[u8]) -> Self {
+        let mut extracted = [0u8; EXTRACTED_KEY_SIZE];
+        if key.len() == EXTRACTED_KEY_SIZE {
+            extracted.copy_from_slice(key);
+        } else {
+            let mut hasher = Blake2bVar::new(EXTRACTED_KEY_SIZE).expect("valid output size");
+            hasher.update(key);
+            hasher
+                .finalize_variable(&mut extracted)
+                .expect("valid output size");
+        }
+
+        let block = |index: usize| -> u128 {
+            let mut b = [0u8; BLOCK_SIZE];
+            b.copy_from_slice(&extracted[index * BLOCK_SIZE..(index + 1) * BLOCK_SIZE]);
+            u128::from_be_bytes(b)
+        };
+
+        Self {
+            i: block(0),
+            j: block(1),
+            l: block(2),
+        }
+    }
+
+    fn aes4(&self, x: u128) -> u128 {
+        let mut block = Block::from(x.to_be_bytes());
+        for key in [self.j, self.i, self.l, 0] {
+            cipher_round(&mut block, &Block::from(key.to_be_bytes()));
+        }
+        u128::from_be_bytes(block.into())
+    }
+
+    /// Tweakable block cipher `E(j,i)` (only `j >= 0` is needed)
+    fn e(&self, j: u32, i: u32, x: u128) -> u128 {
+        let mut i_multiple: u128 = self.i;
+        for _ in 0..(i + 7) / 8 {
+            i_multiple = double(i_multiple);
+        }
+        self.aes4(x ^ mult(self.j, j) ^ i_multiple ^ mult(self.l, i % 8))
+    }
+
+    fn hash_vector(&self, j: u32, data: &[u8]) -> u128 {
+        let mut sum: u128 = 0;
+        let mut chunks = data.chunks_exact(BLOCK_SIZE);
+        for (i, chunk) in chunks.by_ref().enumerate() {
+            let mut block = [0u8; BLOCK_SIZE];
+            block.copy_from_slice(chunk);
+            sum ^= self.e(j, i as u32 + 1, u128::from_be_bytes(block));
+        }
+
+        let remainder: &[u8] = chunks.remainder();
+        if !remainder.is_empty() || data.is_empty() {
+            let mut block = [0u8; BLOCK_SIZE];
+            block[..remainder.len()].copy_from_slice(remainder);
+            block[remainder.len()] = 0x80;
+            sum ^= self.e(j, 0, u128::from_be_bytes(block));
+        }
+
+        sum
+    }
+
+    fn hash(&self, nonce: &[u8], ad: &[&[u8]], tau: usize) -> u128 {
+        let mut delta: u128 = self.e(3, 1, (tau as u128) * 8);
+        delta ^= self.hash_vector(4, nonce);
+        for (k, data) in ad.iter().enumerate() {
+            delta ^= self.hash_vector(5 + k as u32, data);
+        }
+        delta
+    }
+
+    fn e_bytes(&self, j: u32, i: u32, x: [u8; BLOCK_SIZE]) -> [u8; BLOCK_SIZE] {
+        self.e(j, i, u128::from_be_bytes(x)).to_be_bytes()
+    }
+
+    fn tiny(&self, delta: u128, input: &[u8], decrypt: bool) -> Vec<u8> {
+        let len: usize = input.len();
+        let half: usize = (len + 1) / 2;
+        let (i, rounds): (u32, u32) = match len {
+            1 => (7, 24),
+            2 => (7, 16),
+            l if l < BLOCK_SIZE => (7, 10),
+            _ => (6, 8),
+        };
+
+        let mut left = [0u8; BLOCK_SIZE];
+        let mut right = [0u8; BLOCK_SIZE];
+        left[..half].copy_from_slice(&input[..half]);
+        right[..half].copy_from_slice(&input[len / 2..len / 2 + half]);
+
+        let (mut mask, mut pad): (u8, u8) = (0x00, 0x80);
+        if len & 1 != 0 {
+            for k in 0..len / 2 {
+                right[k] = (right[k] << 4) | (right[k + 1] >> 4);
+            }
+            right[len / 2] <<= 4;
+            pad = 0x08;
+            mask = 0xf0;
+        }
+
+        let delta: [u8; BLOCK_SIZE] = delta.to_be_bytes();
+        let round = |src: &[u8; BLOCK_SIZE], dst: &mut [u8; BLOCK_SIZE], j: i64| {
+            let mut buf = [0u8; BLOCK_SIZE];
+            buf[..half].copy_from_slice(&src[..half]);
+            buf[len / 2] = (buf[len / 2] & mask) | pad;
+            for (b, d) in buf.iter_mut().zip(delta.iter()) {
+                *b ^= d;
+            }
+            buf[15] ^= j as u8;
+            let out: [u8; BLOCK_SIZE] = self.e_bytes(0, i, buf);
+            for (d, o) in dst.iter_mut().zip(out.iter()) {
+                *d ^= o;
+            }
+        };
+
+        let (mut j, step): (i64, i64) = if decrypt {
+            if len < BLOCK_SIZE {
+                let mut buf = [0u8; BLOCK_SIZE];
+                buf[..len].copy_from_slice(input);
+                buf[0] |= 0x80;
+                for (b, d) in buf.iter_mut().zip(delta.iter()) {
+                    *b ^= d;
+                }
+                left[0] ^= self.e_bytes(0, 3, buf)[0] & 0x80;
+            }
+            (rounds as i64 - 1, -1)
+        } else {
+            (0, 1)
+        };
+
+        for _ in 0..rounds / 2 {
+            round(&right, &mut left, j);
+            round(&left, &mut right, j + step);
+            j += 2 * step;
+        }
+
+        let mut buf = [0u8; MAX_TINY_SIZE];
+        buf[..len / 2].copy_from_slice(&right[..len / 2]);
+        buf[len / 2..len / 2 + half].copy_from_slice(&left[..half]);
+        if len & 1 != 0 {
+            for k in (len / 2 + 1..len).rev() {
+                buf[k] = (buf[k] >> 4) | (buf[k - 1] << 4);
+            }
+            buf[len / 2] = (left[0] >> 4) | (right[len / 2] & 0xf0);
+        }
+
+        let mut output: Vec<u8> = buf[..len].to_vec();
+        if len < BLOCK_SIZE && !decrypt {
+            let mut b = [0u8; BLOCK_SIZE];
+            b[..len].copy_from_slice(&buf[..len]);
+            b[0] |= 0x80;
+            for (b, d) in b.iter_mut().zip(delta.iter()) {
+                *b ^= d;
+            }
+            output[0] ^= self.e_bytes(0, 3, b)[0] & 0x80;
+        }
+
+        output
+    }
+}
+
+/// Encrypt with AEZ, appending `tau` bytes of authenticator
+pub fn encrypt<K, P>(
+    key: K,
+    nonce: &[u8],
+    ad: &[&[u8]],
+    tau: usize,
+    plaintext: P,
+) -> Result<Vec<u8>, Error>
+where
+    K: AsRef<[u8]>,
+    P: AsRef<[u8]>,
+{
+    let plaintext: &[u8] = plaintext.as_ref();
+    let len: usize = plaintext.len() + tau;
+    if len == 0 || len >= MAX_TINY_SIZE {
+        return Err(Error::UnsupportedLength);
+    }
+
+    let aez = Aez::new(key.as_ref());
+    let delta: u128 = aez.hash(nonce, ad, tau);
+    let mut input: Vec<u8> = plaintext.to_vec();
+    input.resize(len, 0);
+    Ok(aez.tiny(delta, &input, false))
+}
+
+/// Decrypt with AEZ, checking the `tau` bytes of authenticator
+pub fn decrypt<K, C>(
+    key: K,
+    nonce: &[u8],
+    ad: &[&[u8]],
+    tau: usize,
+    ciphertext: C,
+) -> Result<Vec<u8>, Error>
+where
+    K: AsRef<[u8]>,
+    C: AsRef<[u8]>,
+{
+    let ciphertext: &[u8] = ciphertext.as_ref();
+    if ciphertext.len() < tau {
+        return Err(Error::InvalidCiphertext);
+    }
+    if ciphertext.is_empty() || ciphertext.len() >= MAX_TINY_SIZE {
+        return Err(Error::UnsupportedLength);
+    }
+
+    let aez = Aez::new(key.as_ref());
+    let delta: u128 = aez.hash(nonce, ad, tau);
+    let mut output: Vec<u8> = aez.tiny(delta, ciphertext, true);
+    let plaintext_len: usize = ciphertext.len() - tau;
+    if output[plaintext_len..].iter().any(|b| *b != 0) {
+        return Err(Error::Authentication);
+    }
+    output.truncate(plaintext_len);
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encrypt_decrypt() {
+        let key = b"aez key";
+        let ad: &[&[u8]] = &[b"additional data"];
+        for len in 1..MAX_TINY_SIZE - 4 {
+            let plaintext: Vec<u8> = (0..len as u8).collect();
+            let ciphertext = encrypt(key, &[], ad, 4, &plaintext).unwrap();
+            assert_eq!(ciphertext.len(), len + 4);
+            assert_eq!(decrypt(key, &[], ad, 4, &ciphertext).unwrap(), plaintext);
+            assert_eq!(
+                decrypt(b"wrong key", &[], ad, 4, &ciphertext).unwrap_err(),
+                Error::Authentication
+            );
+        }
+    }
+}
@@ -1,8 +1,9 @@
 // Copyright (c) 2022-2023 Yuki Kishimoto
 // Distributed under the MIT software license
 
+use bdk::bitcoin::hashes::hmac::{Hmac, HmacEngine};
 use bdk::bitcoin::hashes::sha256::Hash as Sha256Hash;
-use bdk::bitcoin::hashes::Hash;
+use bdk::bitcoin::hashes::{sha512, Hash, HashEngine};
 
 pub fn sha256<T>(value: T) -> Sha256Hash
 where
@@ -10,3 +11,57 @@ where
 {
     Sha256Hash::hash(value.as_ref())
 }
+
+/// PBKDF2 with HMAC-SHA512
+pub fn pbkdf2_hmac_sha512<P, S>(password: P, salt: S, rounds: u32) -> [u8; 64]
+where
+    P: AsRef<[u8]>,
+    S: AsRef<[u8]>,
+{
+    let password: &[u8] = password.as_ref();
+
+    let mut engine: HmacEngine<sha512::Hash> = HmacEngine::new(password);
+    engine.input(salt.as_ref());
+    engine.input(&1u32.to_be_bytes());
+    let mut u: [u8; 64] = Hmac::from_engine(engine).to_byte_array();
+    let mut output: [u8; 64] = u;
+
+    for _ in 1..rounds {
+        let mut engine: HmacEngine<sha512::Hash> = HmacEngine::new(password);
+        engine.input(&u);
+        u = Hmac::from_engine(engine).to_byte_array();
+        output.iter_mut().zip(u.iter()).for_each(|(o, u)| *o ^= u);
+    }
+
+    output
+}
+
+/// CRC-32C (Castagnoli)
+pub fn crc32c<T>(data: T) -> u32
+where
+    T: AsRef<[u8]>,
+{
+    let mut crc: u32 = !0;
+    for byte in data.as_ref().iter() {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = (crc >> 1) ^ (0x82F6_3B78 & (!(crc & 1)).wrapping_add(1));
+        }
+    }
+    !crc
+}
+
+/// Compare two byte strings in constant time (for equal lengths)
+pub fn constant_time_eq<A, B>(a: A, b: B) -> bool
+where
+    A: AsRef<[u8]>,
+    B: AsRef<[u8]>,
+{
+    let a: &[u8] = a.as_ref();
+    let b: &[u8] = b.as_ref();
+    if a.len() != b.len() {
+        return false;
+    }
+    let diff: u8 = a.iter().zip(b.iter()).fold(0, |acc, (x, y)| acc | (x ^ y));
+    diff == 0
+}
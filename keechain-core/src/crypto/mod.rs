@@ -8,6 +8,7 @@ use serde::de::DeserializeOwned;
 use serde::Serialize;
 
 pub mod aes;
+pub mod aez;
 pub mod chacha20;
 pub mod hash;
 
@@ -6,24 +6,40 @@ use std::collections::HashMap;
 use std::str::FromStr;
 
 use bdk::bitcoin::secp256k1::{Secp256k1, Signing};
-use bdk::bitcoin::Network;
-use bdk::miniscript::descriptor::{Descriptor, DescriptorKeyParseError, DescriptorPublicKey};
+use bdk::bitcoin::{Address, Network, Script};
+use bdk::miniscript::descriptor::{
+    ConversionError, Descriptor, DescriptorKeyParseError, DescriptorPublicKey, ShInner, Wildcard,
+};
+use bdk::miniscript::ForEachKey;
 
 use crate::bips::bip32::{
     self, Bip32, ChildNumber, DerivationPath, ExtendedPrivKey, ExtendedPubKey, Fingerprint,
 };
 use crate::bips::bip43::Purpose;
-use crate::types::Seed;
+use crate::bips::bip48::ScriptType;
+use crate::seeds::electrum::ElectrumSeedVersion;
+use crate::slips::slip132;
+use crate::types::{Seed, SeedKind};
 
 #[derive(Debug)]
 pub enum Error {
     BIP32(bip32::Error),
     Miniscript(bdk::miniscript::Error),
     DescriptorKeyParse(DescriptorKeyParseError),
+    Conversion(ConversionError),
     UnsupportedDerivationPath,
+    UnsupportedDescriptor,
+    UnsupportedKey,
     PurposePathNotFound,
-    CoinPathNotFound,
     DescriptorNotFound,
+    MultisigKeyNotFound,
+    DerivationPathNotFound,
+    /// Electrum seeds have a single account
+    ElectrumAccount(u32),
+    Slip132Mismatch {
+        key: String,
+        script: slip132::ScriptType,
+    },
 }
 
 impl std::error::Error for Error {}
@@ -34,12 +50,27 @@ impl fmt::Display for Error {
             Self::BIP32(e) => write!(f, "BIP32: {e}"),
             Self::Miniscript(e) => write!(f, "Miniscript: {e}"),
             Self::DescriptorKeyParse(e) => write!(f, "Descriptor Key parse: {e}"),
+            Self::Conversion(e) => write!(f, "Conversion: {e}"),
             Self::UnsupportedDerivationPath => write!(f, "Unsupported derivation path"),
+            Self::UnsupportedDescriptor => write!(
+                f,
+                "Unsupported descriptor: expected pkh, sh(wpkh), wpkh or tr (key path only)"
+            ),
+            Self::UnsupportedKey => write!(
+                f,
+                "Unsupported key: expected an extended public key with origin and `/0/*`, `/1/*` or `/<0;1>/*`"
+            ),
             Self::PurposePathNotFound => write!(f, "Invalid derivation path: purpose not provided"),
-            Self::CoinPathNotFound => {
-                write!(f, "Invalid derivation path: invalid coin or not provided")
-            }
             Self::DescriptorNotFound => write!(f, "Descriptor not found"),
+            Self::MultisigKeyNotFound => write!(f, "Multisig key not found"),
+            Self::DerivationPathNotFound => write!(f, "Key derivation path not found"),
+            Self::ElectrumAccount(account) => {
+                write!(f, "Electrum seeds have no account {account}: only account 0")
+            }
+            Self::Slip132Mismatch { key, script } => write!(
+                f,
+                "Key {key} was encoded as {script} (SLIP132) but derived for another script type"
+            ),
         }
     }
 }
@@ -62,10 +93,18 @@ impl From<DescriptorKeyParseError> for Error {
     }
 }
 
+impl From<ConversionError> for Error {
+    fn from(e: ConversionError) -> Self {
+        Self::Conversion(e)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Descriptors {
     external: HashMap<Purpose, Descriptor<DescriptorPublicKey>>,
     internal: HashMap<Purpose, Descriptor<DescriptorPublicKey>>,
+    /// BIP48 account keys (with origin), one per script type
+    multisig: HashMap<ScriptType, DescriptorPublicKey>,
 }
 
 impl Descriptors {
@@ -81,6 +120,42 @@ impl Descriptors {
         let root: ExtendedPrivKey = seed.to_bip32_root_key(network)?;
         let root_fingerprint = root.fingerprint(secp);
 
+        // Electrum seeds have a single wallet, at the root defined by the seed version
+        if let SeedKind::Electrum(mnemonic) = &seed.kind() {
+            match account {
+                Some(account) if account != 0 => return Err(Error::ElectrumAccount(account)),
+                _ => (),
+            }
+
+            let version: ElectrumSeedVersion = mnemonic.version();
+            let path: DerivationPath = version.account_extended_path();
+            let derived_private_key: ExtendedPrivKey = root.derive_priv(secp, &path)?;
+            let derived_public_key: ExtendedPubKey =
+                ExtendedPubKey::from_priv(secp, &derived_private_key);
+
+            let mut descriptors = Descriptors {
+                external: HashMap::with_capacity(1),
+                internal: HashMap::with_capacity(1),
+                multisig: HashMap::new(),
+            };
+
+            for change in [false, true] {
+                let desc: DescriptorPublicKey =
+                    origin_descriptor(root_fingerprint, derived_public_key, &path, change)?;
+                let desc: Descriptor<DescriptorPublicKey> = match version {
+                    ElectrumSeedVersion::Standard => Descriptor::new_pkh(desc)?,
+                    ElectrumSeedVersion::Segwit => Descriptor::new_wpkh(desc)?,
+                };
+                if change {
+                    descriptors.internal.insert(version.purpose(), desc);
+                } else {
+                    descriptors.external.insert(version.purpose(), desc);
+                }
+            }
+
+            return Ok(descriptors);
+        }
+
         let purposes: Vec<Purpose> = vec![
             Purpose::BIP44,
             Purpose::BIP49,
@@ -88,10 +163,14 @@ impl Descriptors {
             Purpose::BIP86,
         ];
 
+        let scripts: Vec<ScriptType> =
+            vec![ScriptType::P2SHWSH, ScriptType::P2WSH, ScriptType::P2TR];
+
         let capacity: usize = purposes.len();
         let mut descriptors = Descriptors {
             external: HashMap::with_capacity(capacity),
             internal: HashMap::with_capacity(capacity),
+            multisig: HashMap::with_capacity(scripts.len()),
         };
 
         for purpose in purposes.into_iter() {
@@ -113,9 +192,148 @@ impl Descriptors {
             );
         }
 
+        for script in scripts.into_iter() {
+            let purpose = Purpose::BIP48 { script };
+            let path: DerivationPath = purpose.to_account_extended_path(network, account)?;
+            let derived_private_key: ExtendedPrivKey = root.derive_priv(secp, &path)?;
+            let derived_public_key: ExtendedPubKey =
+                ExtendedPubKey::from_priv(secp, &derived_private_key);
+            descriptors.multisig.insert(
+                script,
+                key_expression(root_fingerprint, derived_public_key, &path)?,
+            );
+        }
+
         Ok(descriptors)
     }
 
+    /// Collect single-key descriptors (`pkh`, `sh(wpkh)`, `wpkh` and key path only `tr`)
+    ///
+    /// The purpose is inferred from the script type and the chain from the `/0/*` or `/1/*` suffix.
+    /// Multipath descriptors (`/<0;1>/*`, BIP389) are split into the external and internal ones.
+    pub fn from_descriptors<I>(descriptors: I) -> Result<Self, Error>
+    where
+        I: IntoIterator<Item = Descriptor<DescriptorPublicKey>>,
+    {
+        let mut external = HashMap::new();
+        let mut internal = HashMap::new();
+
+        let mut list: Vec<Descriptor<DescriptorPublicKey>> = Vec::new();
+        for desc in descriptors.into_iter() {
+            if desc.is_multipath() {
+                list.extend(desc.into_single_descriptors()?);
+            } else {
+                list.push(desc);
+            }
+        }
+
+        for desc in list.into_iter() {
+            let (purpose, key) = single_key(&desc)?;
+
+            let change: bool = match key {
+                DescriptorPublicKey::XPub(xkey)
+                    if xkey.origin.is_some() && xkey.wildcard == Wildcard::Unhardened =>
+                {
+                    match xkey.derivation_path.as_ref() {
+                        [ChildNumber::Normal { index: 0 }] => false,
+                        [ChildNumber::Normal { index: 1 }] => true,
+                        _ => return Err(Error::UnsupportedKey),
+                    }
+                }
+                _ => return Err(Error::UnsupportedKey),
+            };
+
+            if change {
+                internal.insert(purpose, desc);
+            } else {
+                external.insert(purpose, desc);
+            }
+        }
+
+        Ok(Self {
+            external,
+            internal,
+            multisig: HashMap::new(),
+        })
+    }
+
+    /// Root fingerprint, taken from the key origin
+    pub fn root_fingerprint(&self) -> Option<Fingerprint> {
+        self.external
+            .values()
+            .chain(self.internal.values())
+            .find_map(|desc| match desc {
+                Descriptor::Pkh(pkh) => Some(pkh.as_inner().master_fingerprint()),
+                Descriptor::Sh(sh) => match sh.as_inner() {
+                    ShInner::Wpkh(wpkh) => Some(wpkh.as_inner().master_fingerprint()),
+                    _ => None,
+                },
+                Descriptor::Wpkh(wpkh) => Some(wpkh.as_inner().master_fingerprint()),
+                Descriptor::Tr(tr) => Some(tr.internal_key().master_fingerprint()),
+                _ => None,
+            })
+    }
+
+    /// Available purposes
+    pub fn purposes(&self) -> Vec<Purpose> {
+        let mut purposes: Vec<Purpose> = self.external.keys().copied().collect();
+        purposes.sort();
+        purposes
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.external.is_empty() && self.internal.is_empty()
+    }
+
+    pub fn address(
+        &self,
+        purpose: Purpose,
+        internal: bool,
+        index: u32,
+        network: Network,
+    ) -> Result<Address, Error> {
+        let desc: Descriptor<DescriptorPublicKey> = self.get_by_purpose(purpose, internal)?;
+        Ok(desc.at_derivation_index(index)?.address(network)?)
+    }
+
+    pub fn addresses(
+        &self,
+        purpose: Purpose,
+        internal: bool,
+        from: u32,
+        count: u32,
+        network: Network,
+    ) -> Result<Vec<DerivedAddress>, Error> {
+        let desc: Descriptor<DescriptorPublicKey> = self.get_by_purpose(purpose, internal)?;
+        derive_addresses(&desc, None, from, count, network)
+    }
+
+    /// Find the descriptor that produces `script` at the index of `path` (last child)
+    ///
+    /// Return the purpose, if it's a change (internal) and the index.
+    pub fn find_script(
+        &self,
+        script: &Script,
+        path: &DerivationPath,
+    ) -> Option<(Purpose, bool, u32)> {
+        let index: u32 = match path.as_ref().last() {
+            Some(ChildNumber::Normal { index }) => *index,
+            _ => return None,
+        };
+
+        for (internal, descriptors) in [(false, &self.external), (true, &self.internal)] {
+            for (purpose, desc) in descriptors.iter() {
+                if let Ok(derived) = desc.at_derivation_index(index) {
+                    if derived.script_pubkey().as_script() == script {
+                        return Some((*purpose, internal, index));
+                    }
+                }
+            }
+        }
+
+        None
+    }
+
     pub fn external(&self) -> Vec<Descriptor<DescriptorPublicKey>> {
         self.external.clone().into_values().collect()
     }
@@ -124,6 +342,32 @@ impl Descriptors {
         self.internal.clone().into_values().collect()
     }
 
+    /// Multipath descriptors (`/<0;1>/*`, BIP389), sorted by purpose
+    pub fn multipath(&self) -> Result<Vec<Descriptor<DescriptorPublicKey>>, Error> {
+        self.purposes()
+            .into_iter()
+            .map(|purpose| self.get_multipath_by_purpose(purpose))
+            .collect()
+    }
+
+    /// BIP48 multisig account keys, sorted by script type
+    pub fn multisig_keys(&self) -> Vec<(ScriptType, DescriptorPublicKey)> {
+        let mut keys: Vec<(ScriptType, DescriptorPublicKey)> = self
+            .multisig
+            .iter()
+            .map(|(script, key)| (*script, key.clone()))
+            .collect();
+        keys.sort_by_key(|(script, _)| *script);
+        keys
+    }
+
+    pub fn multisig_key(&self, script: ScriptType) -> Result<DescriptorPublicKey, Error> {
+        self.multisig
+            .get(&script)
+            .cloned()
+            .ok_or(Error::MultisigKeyNotFound)
+    }
+
     pub fn get_by_purpose(
         &self,
         purpose: Purpose,
@@ -141,6 +385,104 @@ impl Descriptors {
                 .ok_or(Error::DescriptorNotFound)
         }
     }
+
+    pub fn get_multipath_by_purpose(
+        &self,
+        purpose: Purpose,
+    ) -> Result<Descriptor<DescriptorPublicKey>, Error> {
+        let desc: &Descriptor<DescriptorPublicKey> = self
+            .external
+            .get(&purpose)
+            .ok_or(Error::DescriptorNotFound)?;
+        to_multipath(desc)
+    }
+}
+
+/// Purpose and key of a single-key descriptor
+fn single_key(
+    desc: &Descriptor<DescriptorPublicKey>,
+) -> Result<(Purpose, &DescriptorPublicKey), Error> {
+    match desc {
+        Descriptor::Pkh(pkh) => Ok((Purpose::BIP44, pkh.as_inner())),
+        Descriptor::Sh(sh) => match sh.as_inner() {
+            ShInner::Wpkh(wpkh) => Ok((Purpose::BIP49, wpkh.as_inner())),
+            _ => Err(Error::UnsupportedDescriptor),
+        },
+        Descriptor::Wpkh(wpkh) => Ok((Purpose::BIP84, wpkh.as_inner())),
+        Descriptor::Tr(tr) if tr.tap_tree().is_none() => Ok((Purpose::BIP86, tr.internal_key())),
+        _ => Err(Error::UnsupportedDescriptor),
+    }
+}
+
+/// Merge the `/0/*` or `/1/*` chain of a single-key descriptor into a multipath one (`/<0;1>/*`, BIP389)
+pub fn to_multipath(
+    desc: &Descriptor<DescriptorPublicKey>,
+) -> Result<Descriptor<DescriptorPublicKey>, Error> {
+    let (_, key) = single_key(desc)?;
+    let key: DescriptorPublicKey = match key {
+        DescriptorPublicKey::XPub(xkey) if xkey.wildcard == Wildcard::Unhardened => {
+            match (&xkey.origin, xkey.derivation_path.as_ref()) {
+                (
+                    Some((root_fingerprint, path)),
+                    [ChildNumber::Normal { index: 0 }] | [ChildNumber::Normal { index: 1 }],
+                ) => compose_key(*root_fingerprint, xkey.xkey, path, "/<0;1>/*")?,
+                _ => return Err(Error::UnsupportedKey),
+            }
+        }
+        _ => return Err(Error::UnsupportedKey),
+    };
+    match desc {
+        Descriptor::Pkh(_) => Ok(Descriptor::new_pkh(key)?),
+        Descriptor::Sh(_) => Ok(Descriptor::new_sh_wpkh(key)?),
+        Descriptor::Wpkh(_) => Ok(Descriptor::new_wpkh(key)?),
+        Descriptor::Tr(_) => Ok(Descriptor::new_tr(key, None)?),
+        _ => Err(Error::UnsupportedDescriptor),
+    }
+}
+
+/// Address with the full derivation path of the key that generates it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DerivedAddress {
+    pub index: u32,
+    pub path: DerivationPath,
+    pub address: Address,
+}
+
+/// Derive `count` addresses of `desc`, starting at `from`
+///
+/// The derivation path is taken from the key with root `fingerprint` (or the first extended key).
+pub fn derive_addresses(
+    desc: &Descriptor<DescriptorPublicKey>,
+    fingerprint: Option<Fingerprint>,
+    from: u32,
+    count: u32,
+    network: Network,
+) -> Result<Vec<DerivedAddress>, Error> {
+    let mut base_path: Option<DerivationPath> = None;
+    desc.for_each_key(|key| {
+        if let DescriptorPublicKey::XPub(xkey) = key {
+            if fingerprint.map_or(true, |f| f == key.master_fingerprint()) {
+                base_path = Some(match &xkey.origin {
+                    Some((_, origin)) => origin.extend(&xkey.derivation_path),
+                    None => xkey.derivation_path.clone(),
+                });
+                return false;
+            }
+        }
+        true
+    });
+    let base_path: DerivationPath = base_path.ok_or(Error::DerivationPathNotFound)?;
+
+    let mut addresses: Vec<DerivedAddress> = Vec::with_capacity(count as usize);
+    for index in from..from.saturating_add(count) {
+        let child: ChildNumber = ChildNumber::from_normal_idx(index)?;
+        addresses.push(DerivedAddress {
+            index,
+            path: base_path.child(child),
+            address: desc.at_derivation_index(index)?.address(network)?,
+        });
+    }
+    Ok(addresses)
 }
 
 pub trait ToDescriptor: Bip32
@@ -187,47 +529,93 @@ where
             ExtendedPubKey::from_priv(secp, &derived_private_key);
         typed_descriptor(root_fingerprint, derived_public_key, &path, change)
     }
+
+    /// Multipath descriptor (`/<0;1>/*`, BIP389)
+    fn to_multipath_descriptor<C>(
+        &self,
+        purpose: Purpose,
+        account: Option<u32>,
+        network: Network,
+        secp: &Secp256k1<C>,
+    ) -> Result<Descriptor<DescriptorPublicKey>, Error>
+    where
+        C: Signing,
+    {
+        let root: ExtendedPrivKey = self.to_bip32_root_key(network)?;
+        let root_fingerprint: Fingerprint = root.fingerprint(secp);
+        let path: DerivationPath = purpose.to_account_extended_path(network, account)?;
+        let derived_private_key: ExtendedPrivKey = root.derive_priv(secp, &path)?;
+        let derived_public_key: ExtendedPubKey =
+            ExtendedPubKey::from_priv(secp, &derived_private_key);
+        typed_multipath_descriptor(root_fingerprint, derived_public_key, &path)
+    }
 }
 
+/// Compose descriptor key with origin (ex. `[fingerprint/84'/0'/0']xpub.../0/*`)
+///
+/// The path can have any depth: the first child is returned as purpose.
 pub fn descriptor(
     root_fingerprint: Fingerprint,
     pubkey: ExtendedPubKey,
     path: &DerivationPath,
     change: bool,
 ) -> Result<(ChildNumber, DescriptorPublicKey), Error> {
-    let mut iter_path = path.into_iter();
-
-    let purpose: &ChildNumber = match iter_path.next() {
-        Some(child) => child,
+    let purpose: ChildNumber = match path.into_iter().next() {
+        Some(child) => *child,
         None => return Err(Error::PurposePathNotFound),
     };
+    let desc: DescriptorPublicKey = origin_descriptor(root_fingerprint, pubkey, path, change)?;
+    Ok((purpose, desc))
+}
 
-    let coin: &ChildNumber = match iter_path.next() {
-        Some(ChildNumber::Hardened { index: 0 }) => &ChildNumber::Hardened { index: 0 },
-        Some(ChildNumber::Hardened { index: 1 }) => &ChildNumber::Hardened { index: 1 },
-        _ => return Err(Error::CoinPathNotFound),
+/// Compose multipath descriptor key with origin (ex. `[fingerprint/84'/0'/0']xpub.../<0;1>/*`, BIP389)
+///
+/// The path can have any depth: the first child is returned as purpose.
+pub fn multipath_descriptor(
+    root_fingerprint: Fingerprint,
+    pubkey: ExtendedPubKey,
+    path: &DerivationPath,
+) -> Result<(ChildNumber, DescriptorPublicKey), Error> {
+    let purpose: ChildNumber = match path.into_iter().next() {
+        Some(child) => *child,
+        None => return Err(Error::PurposePathNotFound),
     };
+    let desc: DescriptorPublicKey = compose_key(root_fingerprint, pubkey, path, "/<0;1>/*")?;
+    Ok((purpose, desc))
+}
 
-    let account: &ChildNumber = match iter_path.next() {
-        Some(child) => child,
-        None => &ChildNumber::Hardened { index: 0 },
-    };
+/// Compose descriptor key with origin at any depth
+fn origin_descriptor(
+    root_fingerprint: Fingerprint,
+    pubkey: ExtendedPubKey,
+    path: &DerivationPath,
+    change: bool,
+) -> Result<DescriptorPublicKey, Error> {
+    let suffix: String = format!("/{}/*", i32::from(change));
+    compose_key(root_fingerprint, pubkey, path, &suffix)
+}
 
-    let desc: String = format!(
-        "[{}/{:#}/{:#}/{:#}{}]{}/{}/*",
-        root_fingerprint,
-        purpose,
-        coin,
-        account,
-        match iter_path.next() {
-            Some(script) => format!("/{script:#}"),
-            None => String::new(),
-        },
-        pubkey,
-        i32::from(change)
-    );
+/// Compose key expression with origin and without derivation suffix (ex. `[fingerprint/48'/0'/0'/2']xpub...`)
+pub fn key_expression(
+    root_fingerprint: Fingerprint,
+    pubkey: ExtendedPubKey,
+    path: &DerivationPath,
+) -> Result<DescriptorPublicKey, Error> {
+    compose_key(root_fingerprint, pubkey, path, "")
+}
 
-    Ok((*purpose, DescriptorPublicKey::from_str(&desc)?))
+fn compose_key(
+    root_fingerprint: Fingerprint,
+    pubkey: ExtendedPubKey,
+    path: &DerivationPath,
+    suffix: &str,
+) -> Result<DescriptorPublicKey, Error> {
+    let origin: String = path
+        .into_iter()
+        .map(|child| format!("/{child:#}"))
+        .collect();
+    let desc: String = format!("[{root_fingerprint}{origin}]{pubkey}{suffix}");
+    Ok(DescriptorPublicKey::from_str(&desc)?)
 }
 
 pub fn typed_descriptor(
@@ -237,6 +625,22 @@ pub fn typed_descriptor(
     change: bool,
 ) -> Result<Descriptor<DescriptorPublicKey>, Error> {
     let (purpose, desc) = descriptor(root_fingerprint, pubkey, path, change)?;
+    new_typed(purpose, desc)
+}
+
+pub fn typed_multipath_descriptor(
+    root_fingerprint: Fingerprint,
+    pubkey: ExtendedPubKey,
+    path: &DerivationPath,
+) -> Result<Descriptor<DescriptorPublicKey>, Error> {
+    let (purpose, desc) = multipath_descriptor(root_fingerprint, pubkey, path)?;
+    new_typed(purpose, desc)
+}
+
+fn new_typed(
+    purpose: ChildNumber,
+    desc: DescriptorPublicKey,
+) -> Result<Descriptor<DescriptorPublicKey>, Error> {
     match purpose {
         ChildNumber::Hardened { index: 44 } => Ok(Descriptor::new_pkh(desc)?),
         ChildNumber::Hardened { index: 49 } => Ok(Descriptor::new_sh_wpkh(desc)?),
@@ -246,11 +650,62 @@ pub fn typed_descriptor(
     }
 }
 
+/// Parse a key expression, also with a SLIP132 extended key (ex. `[fingerprint/84'/0'/0']zpub...`)
+pub fn parse_key(key: &str) -> Result<DescriptorPublicKey, Error> {
+    let (replaced, decoded) = slip132::replace_keys(key.trim());
+    let key = DescriptorPublicKey::from_str(&replaced)?;
+    check_slip132(&key, &decoded)?;
+    Ok(key)
+}
+
+/// Parse a descriptor, also with SLIP132 extended keys (ex. `[fingerprint/48'/0'/0'/2']Zpub.../0/*`)
+///
+/// The checksum is dropped when a SLIP132 key is replaced, since it was computed on the original encoding.
+pub fn parse_descriptor(desc: &str) -> Result<Descriptor<DescriptorPublicKey>, Error> {
+    let (replaced, decoded) = slip132::replace_keys(desc.trim());
+    let replaced: &str = if decoded.is_empty() {
+        &replaced
+    } else {
+        replaced.split('#').next().unwrap_or_default()
+    };
+    let desc: Descriptor<DescriptorPublicKey> = Descriptor::from_str(replaced)?;
+    let mut result: Result<(), Error> = Ok(());
+    desc.for_each_key(|key| {
+        result = check_slip132(key, &decoded);
+        result.is_ok()
+    });
+    result?;
+    Ok(desc)
+}
+
+/// Check the origin of a key against the script type of its SLIP132 version
+fn check_slip132(
+    key: &DescriptorPublicKey,
+    decoded: &[(ExtendedPubKey, slip132::ScriptType)],
+) -> Result<(), Error> {
+    let (origin, xpub) = match key {
+        DescriptorPublicKey::XPub(xkey) => (&xkey.origin, xkey.xkey),
+        DescriptorPublicKey::MultiXPub(xkey) => (&xkey.origin, xkey.xkey),
+        DescriptorPublicKey::Single(..) => return Ok(()),
+    };
+    if let Some((_, path)) = origin {
+        for (_, script) in decoded.iter().filter(|(decoded, _)| *decoded == xpub) {
+            if !script.matches_path(path) {
+                return Err(Error::Slip132Mismatch {
+                    key: key.to_string(),
+                    script: *script,
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod test {
     use bip39::Mnemonic;
 
-    use crate::bips::bip48::ScriptType;
+    use crate::seeds::electrum::ElectrumMnemonic;
 
     use super::*;
 
@@ -305,4 +760,158 @@ mod test {
             .unwrap();
         assert_eq!(desc.to_string(), String::from("wpkh([91ef223d/84'/1'/2345']tpubDCgYuiX1p1eecECkhNc2bLSktmSDoMTj5J3v184ErUXqHTywQ7X5afv51UGfDVSaYzDWvdHhVyJ6UK8fM27EwGByWdczEERfAA9j2nzHUAj/1/*)#tj43jnd8"));
     }
+
+    #[test]
+    fn test_multipath_descriptor() {
+        let secp = Secp256k1::new();
+        let mnemonic = Mnemonic::from_str("range special tuna oblige own drama trend render harsh army outdoor bulb brisk sing analyst own fork senior stove flash fire bulk umbrella vast").unwrap();
+        let seed = Seed::from_mnemonic(mnemonic);
+
+        let multipath = Descriptor::<DescriptorPublicKey>::from_str("tr([91ef223d/86'/0'/0']xpub6CjhhJyrYK83TKQq797CMiNzc4bpoJiYRBeb7iQ99T6dXrEgvg24hDw3ZKDJLNMyiy9Sbwqaw8TtCdaE4xXhnYwy7ptpNVfEAKUCcz8PMtP/<0;1>/*)").unwrap();
+        let desc: Descriptor<DescriptorPublicKey> = seed
+            .to_multipath_descriptor(Purpose::BIP86, None, Network::Bitcoin, &secp)
+            .unwrap();
+        assert_eq!(desc, multipath);
+
+        // Merge external and internal
+        let descriptors = Descriptors::new(&seed, Network::Bitcoin, None, &secp).unwrap();
+        assert_eq!(
+            descriptors
+                .get_multipath_by_purpose(Purpose::BIP86)
+                .unwrap(),
+            multipath
+        );
+        assert_eq!(descriptors.multipath().unwrap().len(), 4);
+
+        // Split into external and internal
+        let parsed = Descriptors::from_descriptors([multipath]).unwrap();
+        for internal in [false, true] {
+            assert_eq!(
+                parsed.get_by_purpose(Purpose::BIP86, internal).unwrap(),
+                descriptors
+                    .get_by_purpose(Purpose::BIP86, internal)
+                    .unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_custom_path_descriptor() {
+        let fingerprint = Fingerprint::from_str("73c5da0a").unwrap();
+        let pubkey = ExtendedPubKey::from_str("xpub6Cih91nLw1tZRMGDCtyH5ryUmQnovGbXTeis3R6Dus3g8PvgkK9NdTbuGYJNj2opxmCqh51GgtzBWwqAbDD9ExPbafkH5oQWg78Yufz5gM8").unwrap();
+
+        let path = DerivationPath::from_str("m/45'/0'/7'").unwrap();
+        let (purpose, desc) = descriptor(fingerprint, pubkey, &path, true).unwrap();
+        assert_eq!(purpose, ChildNumber::Hardened { index: 45 });
+        assert_eq!(desc.to_string(), String::from("[73c5da0a/45'/0'/7']xpub6Cih91nLw1tZRMGDCtyH5ryUmQnovGbXTeis3R6Dus3g8PvgkK9NdTbuGYJNj2opxmCqh51GgtzBWwqAbDD9ExPbafkH5oQWg78Yufz5gM8/1/*"));
+
+        assert!(matches!(
+            descriptor(fingerprint, pubkey, &DerivationPath::master(), false),
+            Err(Error::PurposePathNotFound)
+        ));
+    }
+
+    #[test]
+    fn test_multisig_keys() {
+        let secp = Secp256k1::new();
+        let mnemonic = Mnemonic::from_str("range special tuna oblige own drama trend render harsh army outdoor bulb brisk sing analyst own fork senior stove flash fire bulk umbrella vast").unwrap();
+        let seed = Seed::from_mnemonic(mnemonic);
+        let descriptors = Descriptors::new(&seed, Network::Bitcoin, None, &secp).unwrap();
+
+        let keys = descriptors.multisig_keys();
+        assert_eq!(keys.len(), 3);
+        assert_eq!(keys[0].0, ScriptType::P2SHWSH);
+        assert!(keys[1]
+            .1
+            .to_string()
+            .starts_with("[91ef223d/48'/0'/0'/2']xpub"));
+        assert_eq!(descriptors.multisig_key(ScriptType::P2TR).unwrap().to_string(), String::from("[91ef223d/48'/0'/0'/3']xpub6DaRkmkUCnzQNUYFxbZKDZTxmBaU2mwjHxxhaVd9f5twgMoiPz232PDqEfkKfqTnQeqnGZciVcmWnhTKUxUgp48R8FvCNYiwH4P8oCEk6B8"));
+    }
+
+    #[test]
+    fn test_addresses() {
+        let secp = Secp256k1::new();
+        let mnemonic = Mnemonic::from_str("range special tuna oblige own drama trend render harsh army outdoor bulb brisk sing analyst own fork senior stove flash fire bulk umbrella vast").unwrap();
+        let seed = Seed::from_mnemonic(mnemonic);
+        let descriptors = Descriptors::new(&seed, Network::Bitcoin, None, &secp).unwrap();
+
+        let addresses = descriptors
+            .addresses(Purpose::BIP84, false, 5, 2, Network::Bitcoin)
+            .unwrap();
+        assert_eq!(addresses.len(), 2);
+        assert_eq!(addresses[0].index, 5);
+        assert_eq!(
+            addresses[0].path,
+            DerivationPath::from_str("m/84'/0'/0'/0/5").unwrap()
+        );
+        assert_eq!(
+            addresses[0].address.to_string(),
+            String::from("bc1q0ama9f3wq6h50367wrulf08eh5772dh5urme3q")
+        );
+
+        let addresses = descriptors
+            .addresses(Purpose::BIP84, true, 6, 1, Network::Bitcoin)
+            .unwrap();
+        assert_eq!(
+            addresses[0].path,
+            DerivationPath::from_str("m/84'/0'/0'/1/6").unwrap()
+        );
+        assert_eq!(
+            addresses[0].address.to_string(),
+            String::from("bc1qh8ajvm8lzn2pddquq5gzhdn8v6q0033zr9ude3")
+        );
+
+        assert!(descriptors
+            .addresses(Purpose::BIP84, false, 1 << 31, 1, Network::Bitcoin)
+            .is_err());
+    }
+
+    #[test]
+    fn test_electrum_seed_descriptors() {
+        let secp = Secp256k1::new();
+
+        // Standard
+        let mnemonic = ElectrumMnemonic::from_str(
+            "cycle rocket west magnet parrot shuffle foot correct salt library feed song",
+        )
+        .unwrap();
+        let seed = Seed::with_kind::<&str>(SeedKind::Electrum(mnemonic), None);
+        let descriptors = Descriptors::new(&seed, Network::Bitcoin, None, &secp).unwrap();
+        assert_eq!(descriptors.external().len(), 1);
+        let desc = descriptors.get_by_purpose(Purpose::BIP44, false).unwrap();
+        assert!(desc.to_string().starts_with("pkh(["));
+        let address = desc
+            .at_derivation_index(0)
+            .unwrap()
+            .address(Network::Bitcoin)
+            .unwrap();
+        assert_eq!(
+            address.to_string(),
+            String::from("1NNkttn1YvVGdqBW4PR6zvc3Zx3H5owKRf")
+        );
+
+        // Segwit
+        let mnemonic = ElectrumMnemonic::from_str(
+            "bitter grass shiver impose acquire brush forget axis eager alone wine silver",
+        )
+        .unwrap();
+        let seed = Seed::with_kind::<&str>(SeedKind::Electrum(mnemonic), None);
+        assert!(matches!(
+            Descriptors::new(&seed, Network::Bitcoin, Some(3), &secp),
+            Err(Error::ElectrumAccount(3))
+        ));
+        let descriptors = Descriptors::new(&seed, Network::Bitcoin, Some(0), &secp).unwrap();
+        let desc = descriptors.get_by_purpose(Purpose::BIP84, false).unwrap();
+        assert!(desc.to_string().contains("/0']xpub69Cm26ZhqfAuejFN7RcRK11Fkaqw6TDim9LfLS942vGrsesF9JbABEFmLEDadVG7vgTLHsQARRuKJ2SfFu94zRvBaHpcEE7k3LXci9vCfSU/0/*"));
+        let address = desc
+            .at_derivation_index(0)
+            .unwrap()
+            .address(Network::Bitcoin)
+            .unwrap();
+        assert_eq!(
+            address.to_string(),
+            String::from("bc1q3g5tmkmlvxryhh843v4dz026avatc0zzr6h3af")
+        );
+        assert!(descriptors.get_by_purpose(Purpose::BIP86, false).is_err());
+    }
 }
@@ -37,11 +37,13 @@ pub struct BitcoinCoreDescriptor {
     timestamp: String,
     active: bool,
     desc: Descriptor<DescriptorPublicKey>,
-    internal: bool,
+    /// Not set for multipath descriptors, that include both the chains
+    #[serde(skip_serializing_if = "Option::is_none")]
+    internal: Option<bool>,
 }
 
 impl BitcoinCoreDescriptor {
-    pub fn new(desc: Descriptor<DescriptorPublicKey>, internal: bool) -> Self {
+    pub fn new(desc: Descriptor<DescriptorPublicKey>, internal: Option<bool>) -> Self {
         Self {
             timestamp: String::from("now"),
             active: true,
@@ -55,10 +57,12 @@ impl BitcoinCoreDescriptor {
 pub struct BitcoinCore(Vec<BitcoinCoreDescriptor>);
 
 impl BitcoinCore {
+    /// With `multipath`, export a single `/<0;1>/*` descriptor (BIP389) per script type
     pub fn new<C>(
         seed: &Seed,
         network: Network,
         account: Option<u32>,
+        multipath: bool,
         secp: &Secp256k1<C>,
     ) -> Result<Self, Error>
     where
@@ -67,12 +71,18 @@ impl BitcoinCore {
         let descriptors: Descriptors = Descriptors::new(seed, network, account, secp)?;
         let mut bitcoin_core_descriptors: Vec<BitcoinCoreDescriptor> = Vec::new();
 
-        for desc in descriptors.external().into_iter() {
-            bitcoin_core_descriptors.push(BitcoinCoreDescriptor::new(desc, false));
-        }
+        if multipath {
+            for desc in descriptors.multipath()?.into_iter() {
+                bitcoin_core_descriptors.push(BitcoinCoreDescriptor::new(desc, None));
+            }
+        } else {
+            for desc in descriptors.external().into_iter() {
+                bitcoin_core_descriptors.push(BitcoinCoreDescriptor::new(desc, Some(false)));
+            }
 
-        for desc in descriptors.internal().into_iter() {
-            bitcoin_core_descriptors.push(BitcoinCoreDescriptor::new(desc, true));
+            for desc in descriptors.internal().into_iter() {
+                bitcoin_core_descriptors.push(BitcoinCoreDescriptor::new(desc, Some(true)));
+            }
         }
 
         Ok(Self(bitcoin_core_descriptors))
@@ -18,7 +18,9 @@ use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use crate::bips::bip32::{DerivationPath, ExtendedPubKey, Fingerprint};
 use crate::bips::bip43::Purpose;
 use crate::bips::bip48::ScriptType;
-use crate::descriptors::{self, descriptor};
+use crate::descriptors::{
+    self, key_expression, multipath_descriptor, typed_descriptor, Descriptors,
+};
 
 #[derive(Debug)]
 pub enum Error {
@@ -126,12 +128,41 @@ impl ColdcardGenericJson {
         self.xpub
     }
 
+    /// Multipath key with origin (`/<0;1>/*`, BIP389)
     pub fn descriptor(&self, purpose: Purpose) -> Result<DescriptorPublicKey, Error> {
         let child = self.bips.get(&purpose).ok_or(Error::PurposeNotFound)?;
-        let (_, desc) = descriptor(self.xfp, child.xpub, &child.deriv, false)?;
+        let (_, desc) = multipath_descriptor(self.xfp, child.xpub, &child.deriv)?;
         Ok(desc)
     }
 
+    /// BIP48 key expression (with origin) for multisig
+    pub fn multisig_key(&self, script: ScriptType) -> Result<DescriptorPublicKey, Error> {
+        let child = self
+            .bips
+            .get(&Purpose::BIP48 { script })
+            .ok_or(Error::PurposeNotFound)?;
+        Ok(key_expression(self.xfp, child.xpub, &child.deriv)?)
+    }
+
+    /// Single-sig descriptors (BIP44, BIP49, BIP84 and BIP86), both external and internal
+    pub fn descriptors(&self) -> Result<Descriptors, Error> {
+        let mut list = Vec::new();
+        for (purpose, child) in self.bips.iter() {
+            if let Purpose::BIP48 { .. } = purpose {
+                continue;
+            }
+            for change in [false, true] {
+                list.push(typed_descriptor(
+                    self.xfp,
+                    child.xpub,
+                    &child.deriv,
+                    change,
+                )?);
+            }
+        }
+        Ok(Descriptors::from_descriptors(list)?)
+    }
+
     /* pub fn as_json(&self) -> String {
         serde_json::json!(self).to_string()
     } */
@@ -217,6 +248,7 @@ where
                 ScriptType::P2WSH => "bip48_2",
                 ScriptType::P2TR => "bip48_3",
             },
+            Purpose::BIP45 | Purpose::Custom(..) => continue,
         };
         map.serialize_entry(purpose, child)?;
     }
@@ -292,12 +324,58 @@ mod tests {
         assert_eq!(generic_json.network(), Network::Testnet);
 
         // Check descriptors
-        assert_eq!(generic_json.descriptor(Purpose::BIP44).unwrap(), DescriptorPublicKey::from_str("[0f056943/44'/1'/0']tpubDCiHGUNYdRRBPNYm7CqeeLwPWfeb2ZT2rPsk4aEW3eUoJM93jbBa7hPpB1T9YKtigmjpxHrB1522kSsTxGm9V6cqKqrp1EDaYaeJZqcirYB/0/*").unwrap());
-        assert_eq!(generic_json.descriptor(Purpose::BIP49).unwrap(), DescriptorPublicKey::from_str("[0f056943/49'/1'/0']tpubDCDqt7XXvhAYY9HSwrCXB7BXqYM4RXB8WFtKgtTXGa6u3U6EV1NJJRFTcuTRyhSY5Vreg1LP8aPdyiAPQGrDJLikkHoc7VQg6DA9NtUxHtj/0/*").unwrap());
-        assert_eq!(generic_json.descriptor(Purpose::BIP84).unwrap(), DescriptorPublicKey::from_str("[0f056943/84'/1'/0']tpubDC7jGaaSE66Pn4dgtbAAstde4bCyhSUs4r3P8WhMVvPByvcRrzrwqSvpF9Ghx83Z1LfVugGRrSBko5UEKELCz9HoMv5qKmGq3fqnnbS5E9r/0/*").unwrap());
-        assert_eq!(generic_json.descriptor(Purpose::BIP86).unwrap(), DescriptorPublicKey::from_str("[0f056943/86'/1'/0']tpubDCeEX49avtiXrBTv3JWTtco99Ka499jXdZHBRtm7va2gkMAui11ctZjqNAT9dLVNaEozt2C1kfTM88cnvZCXsWLJN2p4viGvsyGjtKVV7A1/0/*").unwrap());
-        assert_eq!(generic_json.descriptor(Purpose::BIP48 { script: ScriptType::P2SHWSH }).unwrap(), DescriptorPublicKey::from_str("[0f056943/48'/1'/0'/1']tpubDF2rnouQaaYrUEy2JM1YD3RFzew4onawGM4X2Re67gguTf5CbHonBRiFGe3Xjz7DK88dxBFGf2i7K1hef3PM4cFKyUjcbJXddaY9F5tJBoP/0/*").unwrap());
-        assert_eq!(generic_json.descriptor(Purpose::BIP48 { script: ScriptType::P2WSH }).unwrap(), DescriptorPublicKey::from_str("[0f056943/48'/1'/0'/2']tpubDF2rnouQaaYrXF4noGTv6rQYmx87cQ4GrUdhpvXkhtChwQPbdGTi8GA88NUaSrwZBwNsTkC9bFkkC8vDyGBVVAQTZ2AS6gs68RQXtXcCvkP/0/*").unwrap());
-        assert_eq!(generic_json.descriptor(Purpose::BIP48 { script: ScriptType::P2TR }).unwrap(), DescriptorPublicKey::from_str("[0f056943/48'/1'/0'/3']tpubDF2rnouQaaYrY6CUWTapYkeFEs3h3qrzL4M52ZGoPeU9dkarJMtrw6VF1zJRGuGuAFxYS3kXtavfAwQPTQkU5dyNYpbgxcpftrR8H3U85Ez/0/*").unwrap());
+        assert_eq!(generic_json.descriptor(Purpose::BIP44).unwrap(), DescriptorPublicKey::from_str("[0f056943/44'/1'/0']tpubDCiHGUNYdRRBPNYm7CqeeLwPWfeb2ZT2rPsk4aEW3eUoJM93jbBa7hPpB1T9YKtigmjpxHrB1522kSsTxGm9V6cqKqrp1EDaYaeJZqcirYB/<0;1>/*").unwrap());
+        assert_eq!(generic_json.descriptor(Purpose::BIP49).unwrap(), DescriptorPublicKey::from_str("[0f056943/49'/1'/0']tpubDCDqt7XXvhAYY9HSwrCXB7BXqYM4RXB8WFtKgtTXGa6u3U6EV1NJJRFTcuTRyhSY5Vreg1LP8aPdyiAPQGrDJLikkHoc7VQg6DA9NtUxHtj/<0;1>/*").unwrap());
+        assert_eq!(generic_json.descriptor(Purpose::BIP84).unwrap(), DescriptorPublicKey::from_str("[0f056943/84'/1'/0']tpubDC7jGaaSE66Pn4dgtbAAstde4bCyhSUs4r3P8WhMVvPByvcRrzrwqSvpF9Ghx83Z1LfVugGRrSBko5UEKELCz9HoMv5qKmGq3fqnnbS5E9r/<0;1>/*").unwrap());
+        assert_eq!(generic_json.descriptor(Purpose::BIP86).unwrap(), DescriptorPublicKey::from_str("[0f056943/86'/1'/0']tpubDCeEX49avtiXrBTv3JWTtco99Ka499jXdZHBRtm7va2gkMAui11ctZjqNAT9dLVNaEozt2C1kfTM88cnvZCXsWLJN2p4viGvsyGjtKVV7A1/<0;1>/*").unwrap());
+        assert_eq!(generic_json.descriptor(Purpose::BIP48 { script: ScriptType::P2SHWSH }).unwrap(), DescriptorPublicKey::from_str("[0f056943/48'/1'/0'/1']tpubDF2rnouQaaYrUEy2JM1YD3RFzew4onawGM4X2Re67gguTf5CbHonBRiFGe3Xjz7DK88dxBFGf2i7K1hef3PM4cFKyUjcbJXddaY9F5tJBoP/<0;1>/*").unwrap());
+        assert_eq!(generic_json.descriptor(Purpose::BIP48 { script: ScriptType::P2WSH }).unwrap(), DescriptorPublicKey::from_str("[0f056943/48'/1'/0'/2']tpubDF2rnouQaaYrXF4noGTv6rQYmx87cQ4GrUdhpvXkhtChwQPbdGTi8GA88NUaSrwZBwNsTkC9bFkkC8vDyGBVVAQTZ2AS6gs68RQXtXcCvkP/<0;1>/*").unwrap());
+        assert_eq!(generic_json.descriptor(Purpose::BIP48 { script: ScriptType::P2TR }).unwrap(), DescriptorPublicKey::from_str("[0f056943/48'/1'/0'/3']tpubDF2rnouQaaYrY6CUWTapYkeFEs3h3qrzL4M52ZGoPeU9dkarJMtrw6VF1zJRGuGuAFxYS3kXtavfAwQPTQkU5dyNYpbgxcpftrR8H3U85Ez/<0;1>/*").unwrap());
+
+        assert_eq!(generic_json.multisig_key(ScriptType::P2WSH).unwrap(), DescriptorPublicKey::from_str("[0f056943/48'/1'/0'/2']tpubDF2rnouQaaYrXF4noGTv6rQYmx87cQ4GrUdhpvXkhtChwQPbdGTi8GA88NUaSrwZBwNsTkC9bFkkC8vDyGBVVAQTZ2AS6gs68RQXtXcCvkP").unwrap());
+
+        // Check single-sig descriptors against the first addresses
+        let descriptors = generic_json.descriptors().unwrap();
+        assert_eq!(
+            descriptors.root_fingerprint(),
+            Some(generic_json.fingerprint())
+        );
+        assert_eq!(
+            descriptors.purposes(),
+            vec![
+                Purpose::BIP44,
+                Purpose::BIP49,
+                Purpose::BIP84,
+                Purpose::BIP86
+            ]
+        );
+        assert_eq!(
+            descriptors
+                .address(Purpose::BIP44, false, 0, Network::Testnet)
+                .unwrap()
+                .to_string(),
+            "mtHSVByP9EYZmB26jASDdPVm19gvpecb5R"
+        );
+        assert_eq!(
+            descriptors
+                .address(Purpose::BIP49, false, 0, Network::Testnet)
+                .unwrap()
+                .to_string(),
+            "2NCAJ5wD4GvmW32GFLVybKPNphNU8UYoEJv"
+        );
+        assert_eq!(
+            descriptors
+                .address(Purpose::BIP84, false, 0, Network::Testnet)
+                .unwrap()
+                .to_string(),
+            "tb1qupyd58ndsh7lut0et0vtrq432jvu9jtdyws9n9"
+        );
+        assert_eq!(
+            descriptors
+                .address(Purpose::BIP86, false, 0, Network::Testnet)
+                .unwrap()
+                .to_string(),
+            "tb1prlna6c6us6jss2qyemcm8jpzjpuuyx46tz6pe80r6jmpf5dm3z7qnxwucf"
+        );
     }
 }
@@ -13,8 +13,8 @@ use serde::{Deserialize, Serialize};
 use crate::bips::bip32::{
     self, Bip32, DerivationPath, ExtendedPrivKey, ExtendedPubKey, Fingerprint,
 };
-use crate::slips::slip132::{self, ToSlip132};
-use crate::types::Seed;
+use crate::slips::slip132;
+use crate::types::{Seed, SeedKind};
 
 #[derive(Debug)]
 pub enum Error {
@@ -103,6 +103,8 @@ pub struct ElectrumKeystore {
     #[serde(rename = "type")]
     keystore_type: String,
     derivation: DerivationPath,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    seed_type: Option<String>,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
@@ -125,17 +127,35 @@ impl Electrum {
         C: Signing,
     {
         let root: ExtendedPrivKey = seed.to_bip32_root_key(network)?;
-        let path: DerivationPath = bip32::account_extended_path(script.as_u32(), network, account)?;
+
+        // Electrum seeds define their own script and root (`script` and `account` are ignored)
+        let (path, purpose, seed_type): (DerivationPath, u32, Option<String>) = match &seed.kind() {
+            SeedKind::Electrum(mnemonic) => {
+                let version = mnemonic.version();
+                (
+                    version.account_extended_path(),
+                    version.purpose().as_u32(),
+                    Some(version.to_string()),
+                )
+            }
+            _ => (
+                bip32::account_extended_path(script.as_u32(), network, account)?,
+                script.as_u32(),
+                None,
+            ),
+        };
+
         let xpriv: ExtendedPrivKey = root.derive_priv(secp, &path)?;
         let pubkey: ExtendedPubKey = ExtendedPubKey::from_priv(secp, &xpriv);
 
         Ok(Self {
             keystore: ElectrumKeystore {
-                xpub: pubkey.to_slip132(&path)?,
+                xpub: slip132::encode(&pubkey, purpose, network == Network::Bitcoin)?,
                 fingerprint: pubkey.fingerprint(),
                 root_fingerprint: root.fingerprint(secp),
                 keystore_type: String::from("bip32"),
                 derivation: path,
+                seed_type,
             },
             wallet_type: String::from("standard"),
             use_encryption: false,
@@ -158,3 +178,62 @@ impl Electrum {
         Ok(path)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::seeds::electrum::ElectrumMnemonic;
+
+    #[test]
+    fn test_electrum_seed_export() {
+        let secp = Secp256k1::new();
+
+        // Segwit
+        let mnemonic = ElectrumMnemonic::from_str(
+            "bitter grass shiver impose acquire brush forget axis eager alone wine silver",
+        )
+        .unwrap();
+        let seed = Seed::with_kind::<&str>(SeedKind::Electrum(mnemonic), None);
+        let electrum = Electrum::new(
+            &seed,
+            Network::Bitcoin,
+            ElectrumSupportedScripts::Legacy,
+            Some(2),
+            &secp,
+        )
+        .unwrap();
+        assert_eq!(electrum.keystore.xpub, "zpub6nsHdRuY92FsMKdbn9BfjBCG6X8pyhCibNP6uDvpnw2cyrVhecvHRMa3Ne8kdJZxjxgwnpbHLkcR4bfnhHy6auHPJyDTQ3kianeuVLdkCYQ".to_string());
+        assert_eq!(electrum.keystore.seed_type, Some(String::from("segwit")));
+
+        let json: serde_json::Value = serde_json::from_str(&electrum.as_json()).unwrap();
+        assert_eq!(json["keystore"]["derivation"], "m/0'");
+        assert_eq!(json["keystore"]["seed_type"], "segwit");
+
+        // Standard
+        let mnemonic = ElectrumMnemonic::from_str(
+            "cycle rocket west magnet parrot shuffle foot correct salt library feed song",
+        )
+        .unwrap();
+        let seed = Seed::with_kind::<&str>(SeedKind::Electrum(mnemonic), None);
+        let electrum = Electrum::new(
+            &seed,
+            Network::Bitcoin,
+            ElectrumSupportedScripts::NativeSegwit,
+            None,
+            &secp,
+        )
+        .unwrap();
+        assert_eq!(electrum.keystore.xpub, "xpub661MyMwAqRbcFWohJWt7PHsFEJfZAvw9ZxwQoDa4SoMgsDDM1T7WK3u9E4edkC4ugRnZ8E4xDZRpk8Rnts3Nbt97dPwT52CwBdDWroaZf8U".to_string());
+        assert_eq!(electrum.keystore.derivation, DerivationPath::master());
+
+        // Round-trip
+        let electrum_from_json: Electrum = serde_json::from_str(&electrum.as_json()).unwrap();
+        assert_eq!(electrum_from_json.keystore.xpub, electrum.keystore.xpub);
+        assert_eq!(
+            electrum_from_json.keystore.root_fingerprint,
+            electrum.keystore.root_fingerprint
+        );
+    }
+}
@@ -11,20 +11,30 @@ pub use bdk::bitcoin::secp256k1;
 pub use bdk::miniscript;
 
 pub mod bips;
+pub mod blinded;
 pub mod crypto;
 pub mod descriptors;
 pub mod export;
+pub mod multisig;
+pub mod policy;
 pub mod psbt;
+pub mod recovery;
+pub mod seeds;
 pub mod slips;
 pub mod types;
 pub mod util;
+pub mod vanity;
 
 pub use self::bips::bip43::Purpose;
 pub use self::descriptors::Descriptors;
 pub use self::export::{
     BitcoinCore, ColdcardGenericJson, Electrum, ElectrumSupportedScripts, Wasabi,
 };
-pub use self::psbt::PsbtUtility;
-pub use self::types::{EncryptedKeychain, Index, KeeChain, Keychain, Secrets, Seed, WordCount};
+pub use self::multisig::MultisigWallet;
+pub use self::psbt::{PsbtUtility, SigningDescriptor};
+pub use self::types::{
+    Bip85Origin, EncryptedKeychain, Index, KeeChain, Keychain, Passphrase, Secrets, Seed, SeedKind,
+    WatchOnly, WordCount,
+};
 
 pub type Result<T, E = Box<dyn std::error::Error>> = std::result::Result<T, E>;
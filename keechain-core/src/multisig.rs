@@ -0,0 +1,818 @@
+// Copyright (c) 2022-2023 Yuki Kishimoto
+// Distributed under the MIT software license
+
+//! Multisig coordinator
+//!
+//! Assemble `sortedmulti` wallets from this keychain's BIP48 key and the cosigner keys,
+//! or register wallets compiled from a miniscript policy.
+
+use core::fmt;
+use core::str::FromStr;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use bdk::bitcoin::{Address, Network};
+use bdk::miniscript::descriptor::{
+    ConversionError, DefiniteDescriptorKey, Descriptor, DescriptorPublicKey, ShInner, Wildcard,
+    WshInner,
+};
+use bdk::miniscript::{ForEachKey, Terminal};
+use serde::{Deserialize, Serialize};
+
+use crate::bips::bip32::{ChildNumber, DerivationPath, Fingerprint};
+use crate::bips::bip48::ScriptType;
+use crate::descriptors::{self, key_expression, DerivedAddress};
+use crate::export::coldcard::{self, ColdcardGenericJson};
+use crate::policy::CompiledPolicy;
+
+/// Unspendable internal key for taproot multisig (BIP341 NUMS point `H`)
+pub(crate) const UNSPENDABLE_KEY: &str =
+    "50929b74c1a04954b78b4b6035e97a5e078a5a0f28ec96d547bfee9ace803ac0";
+
+/// Max number of keys in a `sortedmulti` (P2WSH and P2SH-P2WSH)
+const MAX_MULTI_KEYS: usize = 20;
+
+#[derive(Debug)]
+pub enum Error {
+    IO(std::io::Error),
+    Descriptors(descriptors::Error),
+    Coldcard(coldcard::Error),
+    Miniscript(bdk::miniscript::Error),
+    Conversion(ConversionError),
+    InvalidName,
+    InvalidCosigner,
+    UnsupportedDescriptor,
+    AmbiguousCosigner(usize),
+    UnsupportedKey(String),
+    ScriptTypeMismatch(String),
+    WrongNetwork(String),
+    DuplicateKey(String),
+    NotEnoughKeys,
+    TooManyKeys(usize),
+    InvalidThreshold {
+        threshold: usize,
+        keys: usize,
+    },
+    /// Saved wallet with only one of the policy and its compiled descriptor
+    IncompletePolicy,
+}
+
+impl std::error::Error for Error {}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::IO(e) => write!(f, "IO: {e}"),
+            Self::Descriptors(e) => write!(f, "Descriptors: {e}"),
+            Self::Coldcard(e) => write!(f, "Coldcard: {e}"),
+            Self::Miniscript(e) => write!(f, "Miniscript: {e}"),
+            Self::Conversion(e) => write!(f, "Conversion: {e}"),
+            Self::InvalidName => write!(f, "Invalid name"),
+            Self::InvalidCosigner => write!(
+                f,
+                "Invalid cosigner: expected a Coldcard generic JSON, a descriptor or a key expression"
+            ),
+            Self::UnsupportedDescriptor => write!(
+                f,
+                "Unsupported descriptor: expected a `sortedmulti` of extended keys with origin"
+            ),
+            Self::AmbiguousCosigner(keys) => {
+                write!(f, "Ambiguous cosigner: expected a single key, found {keys}")
+            }
+            Self::UnsupportedKey(key) => write!(
+                f,
+                "Unsupported key {key}: expected an extended public key with origin"
+            ),
+            Self::ScriptTypeMismatch(key) => {
+                write!(f, "Key {key} was derived for another script type")
+            }
+            Self::WrongNetwork(key) => write!(f, "Key {key} belongs to another network"),
+            Self::DuplicateKey(key) => write!(f, "Duplicate key {key}"),
+            Self::NotEnoughKeys => write!(f, "A multisig needs at least 2 keys"),
+            Self::TooManyKeys(max) => write!(f, "Too many keys: max {max}"),
+            Self::InvalidThreshold { threshold, keys } => {
+                write!(f, "Invalid threshold {threshold} for {keys} keys")
+            }
+            Self::IncompletePolicy => {
+                write!(f, "Invalid wallet: the policy and its descriptor must be saved together")
+            }
+        }
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Self::IO(e)
+    }
+}
+
+impl From<descriptors::Error> for Error {
+    fn from(e: descriptors::Error) -> Self {
+        Self::Descriptors(e)
+    }
+}
+
+impl From<coldcard::Error> for Error {
+    fn from(e: coldcard::Error) -> Self {
+        Self::Coldcard(e)
+    }
+}
+
+impl From<bdk::miniscript::Error> for Error {
+    fn from(e: bdk::miniscript::Error) -> Self {
+        Self::Miniscript(e)
+    }
+}
+
+impl From<ConversionError> for Error {
+    fn from(e: ConversionError) -> Self {
+        Self::Conversion(e)
+    }
+}
+
+/// Cosigner key source
+#[derive(Debug, Clone)]
+pub enum Cosigner {
+    /// Coldcard generic JSON (the BIP48 key of the requested script type is used)
+    Coldcard(ColdcardGenericJson),
+    /// Key expression with origin (ex. `[fingerprint/48'/0'/0'/2']xpub...`)
+    Key(DescriptorPublicKey),
+    /// Descriptor: every key it contains is a cosigner
+    Descriptor(Descriptor<DescriptorPublicKey>),
+}
+
+impl FromStr for Cosigner {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s: &str = s.trim();
+        if s.starts_with('{') {
+            return Ok(Self::Coldcard(ColdcardGenericJson::from_json(s)?));
+        }
+        // Descriptors and key expressions can also use SLIP132 keys (ex. `Zpub`)
+        match descriptors::parse_descriptor(s) {
+            Ok(desc) => return Ok(Self::Descriptor(desc)),
+            Err(e @ descriptors::Error::Slip132Mismatch { .. }) => return Err(e.into()),
+            Err(_) => (),
+        }
+        match descriptors::parse_key(s) {
+            Ok(key) => Ok(Self::Key(key)),
+            Err(e @ descriptors::Error::Slip132Mismatch { .. }) => Err(e.into()),
+            Err(_) => Err(Error::InvalidCosigner),
+        }
+    }
+}
+
+impl Cosigner {
+    pub fn from_file<P>(path: P) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+    {
+        let mut file: File = File::open(path)?;
+        let mut content: String = String::new();
+        file.read_to_string(&mut content)?;
+        Self::from_str(&content)
+    }
+
+    /// Account keys (with origin and without derivation suffix)
+    pub fn keys(&self, script: ScriptType) -> Result<Vec<DescriptorPublicKey>, Error> {
+        match self {
+            Self::Coldcard(json) => Ok(vec![json.multisig_key(script)?]),
+            Self::Key(key) => Ok(vec![account_key(key)?]),
+            Self::Descriptor(desc) => {
+                let mut keys: Vec<DescriptorPublicKey> = Vec::new();
+                desc.for_each_key(|key| {
+                    keys.push(key.clone());
+                    true
+                });
+                keys.iter().map(account_key).collect()
+            }
+        }
+    }
+
+    /// Account key of a single-key cosigner
+    pub fn key(&self, script: ScriptType) -> Result<DescriptorPublicKey, Error> {
+        let mut keys: Vec<DescriptorPublicKey> = self.keys(script)?;
+        keys.dedup();
+        if keys.len() == 1 {
+            Ok(keys.remove(0))
+        } else {
+            Err(Error::AmbiguousCosigner(keys.len()))
+        }
+    }
+}
+
+/// Strip the `/0/*`, `/1/*` or `/<0;1>/*` suffix from an extended key with origin
+pub(crate) fn account_key(key: &DescriptorPublicKey) -> Result<DescriptorPublicKey, Error> {
+    let (origin, xpub) = match key {
+        DescriptorPublicKey::XPub(xkey) => {
+            let supported: bool = match (xkey.derivation_path.as_ref(), xkey.wildcard) {
+                ([], Wildcard::None) => true,
+                ([ChildNumber::Normal { index: 0 }], Wildcard::Unhardened) => true,
+                ([ChildNumber::Normal { index: 1 }], Wildcard::Unhardened) => true,
+                _ => false,
+            };
+            if !supported {
+                return Err(Error::UnsupportedKey(key.to_string()));
+            }
+            (xkey.origin.clone(), xkey.xkey)
+        }
+        DescriptorPublicKey::MultiXPub(xkey) => (xkey.origin.clone(), xkey.xkey),
+        DescriptorPublicKey::Single(..) => return Err(Error::UnsupportedKey(key.to_string())),
+    };
+    match origin {
+        Some((fingerprint, path)) => Ok(key_expression(fingerprint, xpub, &path)?),
+        None => Err(Error::UnsupportedKey(key.to_string())),
+    }
+}
+
+/// Multisig wallet (`sortedmulti` or compiled from a miniscript policy)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "MultisigWalletRaw", into = "MultisigWalletRaw")]
+pub struct MultisigWallet {
+    name: String,
+    script: ScriptType,
+    threshold: usize,
+    keys: Vec<DescriptorPublicKey>,
+    /// Source policy and compiled multipath descriptor (`/<0;1>/*`), instead of `sortedmulti`
+    policy: Option<(String, Descriptor<DescriptorPublicKey>)>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct MultisigWalletRaw {
+    name: String,
+    script: ScriptType,
+    threshold: usize,
+    keys: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    policy: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    descriptor: Option<String>,
+}
+
+impl From<MultisigWallet> for MultisigWalletRaw {
+    fn from(wallet: MultisigWallet) -> Self {
+        Self {
+            name: wallet.name,
+            script: wallet.script,
+            threshold: wallet.threshold,
+            keys: wallet.keys.iter().map(|k| k.to_string()).collect(),
+            policy: wallet.policy.as_ref().map(|(policy, _)| policy.clone()),
+            descriptor: wallet.policy.as_ref().map(|(_, desc)| desc.to_string()),
+        }
+    }
+}
+
+impl TryFrom<MultisigWalletRaw> for MultisigWallet {
+    type Error = Error;
+
+    fn try_from(raw: MultisigWalletRaw) -> Result<Self, Self::Error> {
+        let mut keys: Vec<DescriptorPublicKey> = Vec::with_capacity(raw.keys.len());
+        for key in raw.keys.iter() {
+            keys.push(DescriptorPublicKey::from_str(key).map_err(descriptors::Error::from)?);
+        }
+        let policy = match (raw.policy, raw.descriptor) {
+            (Some(policy), Some(desc)) => Some((policy, Descriptor::from_str(&desc)?)),
+            (None, None) => None,
+            // Saved together: one without the other is a corrupted wallet
+            _ => return Err(Error::IncompletePolicy),
+        };
+        Ok(Self {
+            name: raw.name,
+            script: raw.script,
+            threshold: raw.threshold,
+            keys,
+            policy,
+        })
+    }
+}
+
+impl MultisigWallet {
+    /// New multisig wallet from account keys (with origin)
+    pub fn new<S>(
+        name: S,
+        script: ScriptType,
+        threshold: usize,
+        keys: Vec<DescriptorPublicKey>,
+        network: Network,
+    ) -> Result<Self, Error>
+    where
+        S: Into<String>,
+    {
+        let name: String = name.into();
+        if name.trim().is_empty() {
+            return Err(Error::InvalidName);
+        }
+
+        if keys.len() < 2 {
+            return Err(Error::NotEnoughKeys);
+        }
+
+        if script != ScriptType::P2TR && keys.len() > MAX_MULTI_KEYS {
+            return Err(Error::TooManyKeys(MAX_MULTI_KEYS));
+        }
+
+        if threshold == 0 || threshold > keys.len() {
+            return Err(Error::InvalidThreshold {
+                threshold,
+                keys: keys.len(),
+            });
+        }
+
+        let mut checked: Vec<DescriptorPublicKey> = Vec::with_capacity(keys.len());
+        for key in keys.iter() {
+            let key: DescriptorPublicKey = account_key(key)?;
+            check_key(&key, script, network)?;
+            if checked.contains(&key) {
+                return Err(Error::DuplicateKey(key.to_string()));
+            }
+            checked.push(key);
+        }
+
+        let wallet = Self {
+            name,
+            script,
+            threshold,
+            keys: checked,
+            policy: None,
+        };
+
+        // Check that miniscript accepts it
+        wallet.descriptor(false)?;
+
+        Ok(wallet)
+    }
+
+    /// Assemble the wallet from our BIP48 `key` and the cosigners
+    ///
+    /// Keys repeated across the cosigners (ex. ours inside a descriptor file) are counted once.
+    pub fn from_cosigners<S>(
+        name: S,
+        script: ScriptType,
+        threshold: usize,
+        key: DescriptorPublicKey,
+        cosigners: &[Cosigner],
+        network: Network,
+    ) -> Result<Self, Error>
+    where
+        S: Into<String>,
+    {
+        let mut keys: Vec<DescriptorPublicKey> = vec![account_key(&key)?];
+        for cosigner in cosigners.iter() {
+            for key in cosigner.keys(script)?.into_iter() {
+                if !keys.contains(&key) {
+                    keys.push(key);
+                }
+            }
+        }
+        Self::new(name, script, threshold, keys, network)
+    }
+
+    /// Wallet compiled from a miniscript policy (see [`crate::policy::compile`])
+    pub fn from_policy<S>(
+        name: S,
+        compiled: &CompiledPolicy,
+        network: Network,
+    ) -> Result<Self, Error>
+    where
+        S: Into<String>,
+    {
+        let name: String = name.into();
+        if name.trim().is_empty() {
+            return Err(Error::InvalidName);
+        }
+
+        let keys: Vec<DescriptorPublicKey> =
+            compiled.keys.iter().map(|(_, key)| key.clone()).collect();
+        for key in keys.iter() {
+            check_key(key, compiled.script, network)?;
+        }
+
+        let wallet = Self {
+            name,
+            script: compiled.script,
+            threshold: compiled.threshold(),
+            keys,
+            policy: Some((compiled.policy.clone(), compiled.descriptor.clone())),
+        };
+
+        // Check that both the chains can be derived
+        wallet.descriptor(false)?;
+        wallet.descriptor(true)?;
+
+        Ok(wallet)
+    }
+
+    /// Wallet of a `sortedmulti` descriptor (ex. `wsh(sortedmulti(2,[fingerprint/48'/0'/0'/2']xpub.../<0;1>/*,...))`)
+    pub fn from_descriptor<S>(
+        name: S,
+        desc: &Descriptor<DescriptorPublicKey>,
+        network: Network,
+    ) -> Result<Self, Error>
+    where
+        S: Into<String>,
+    {
+        let (script, threshold): (ScriptType, usize) = match desc {
+            Descriptor::Sh(sh) => match sh.as_inner() {
+                ShInner::Wsh(wsh) => match wsh.as_inner() {
+                    WshInner::SortedMulti(multi) => (ScriptType::P2SHWSH, multi.k),
+                    _ => return Err(Error::UnsupportedDescriptor),
+                },
+                _ => return Err(Error::UnsupportedDescriptor),
+            },
+            Descriptor::Wsh(wsh) => match wsh.as_inner() {
+                WshInner::SortedMulti(multi) => (ScriptType::P2WSH, multi.k),
+                _ => return Err(Error::UnsupportedDescriptor),
+            },
+            // Single `sortedmulti_a` leaf
+            Descriptor::Tr(tr) => {
+                let mut leaves = tr.iter_scripts();
+                match (leaves.next(), leaves.next()) {
+                    (Some((_, ms)), None) => match &ms.node {
+                        Terminal::MultiA(k, ..) => (ScriptType::P2TR, *k),
+                        _ => return Err(Error::UnsupportedDescriptor),
+                    },
+                    _ => return Err(Error::UnsupportedDescriptor),
+                }
+            }
+            _ => return Err(Error::UnsupportedDescriptor),
+        };
+
+        let mut keys: Vec<DescriptorPublicKey> = Vec::new();
+        desc.for_each_key(|key| {
+            // Taproot internal key
+            if !matches!(key, DescriptorPublicKey::Single(..)) {
+                keys.push(key.clone());
+            }
+            true
+        });
+        let keys: Vec<DescriptorPublicKey> =
+            keys.iter().map(account_key).collect::<Result<_, _>>()?;
+
+        let wallet: Self = Self::new(name, script, threshold, keys, network)?;
+
+        // Check that the descriptor is exactly the `sortedmulti` of the wallet
+        let chains: Vec<Descriptor<DescriptorPublicKey>> =
+            desc.clone().into_single_descriptors()?;
+        let expected: Vec<Descriptor<DescriptorPublicKey>> =
+            vec![wallet.descriptor(false)?, wallet.descriptor(true)?];
+        if !expected.starts_with(&chains) {
+            return Err(Error::UnsupportedDescriptor);
+        }
+
+        Ok(wallet)
+    }
+
+    pub fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    pub fn script(&self) -> ScriptType {
+        self.script
+    }
+
+    /// Min number of signatures required to spend
+    pub fn threshold(&self) -> usize {
+        self.threshold
+    }
+
+    /// Miniscript policy the wallet was compiled from
+    pub fn policy(&self) -> Option<String> {
+        self.policy.as_ref().map(|(policy, _)| policy.clone())
+    }
+
+    /// Account keys (with origin)
+    pub fn keys(&self) -> Vec<DescriptorPublicKey> {
+        self.keys.clone()
+    }
+
+    pub fn descriptor(&self, change: bool) -> Result<Descriptor<DescriptorPublicKey>, Error> {
+        if let Some((_, desc)) = &self.policy {
+            return desc
+                .clone()
+                .into_single_descriptors()?
+                .into_iter()
+                .nth(usize::from(change))
+                .ok_or(Error::Descriptors(descriptors::Error::DescriptorNotFound));
+        }
+
+        let keys: Vec<String> = self
+            .keys
+            .iter()
+            .map(|key| format!("{key}/{}/*", u8::from(change)))
+            .collect();
+        let keys: String = keys.join(",");
+        let desc: String = match self.script {
+            ScriptType::P2SHWSH => format!("sh(wsh(sortedmulti({},{keys})))", self.threshold),
+            ScriptType::P2WSH => format!("wsh(sortedmulti({},{keys}))", self.threshold),
+            ScriptType::P2TR => format!(
+                "tr({UNSPENDABLE_KEY},sortedmulti_a({},{keys}))",
+                self.threshold
+            ),
+        };
+        Ok(Descriptor::from_str(&desc)?)
+    }
+
+    /// Root fingerprints of the cosigners
+    pub fn fingerprints(&self) -> Vec<Fingerprint> {
+        self.keys
+            .iter()
+            .map(|key| key.master_fingerprint())
+            .collect()
+    }
+
+    /// Descriptor derived at `change`/`index`
+    pub fn derive(
+        &self,
+        change: bool,
+        index: u32,
+    ) -> Result<Descriptor<DefiniteDescriptorKey>, Error> {
+        Ok(self.descriptor(change)?.at_derivation_index(index)?)
+    }
+
+    /// Change and index of a key origin `path` that derives from one of the wallet keys
+    pub fn find_derivation(
+        &self,
+        fingerprint: Fingerprint,
+        path: &DerivationPath,
+    ) -> Option<(bool, u32)> {
+        let path: &[ChildNumber] = path.as_ref();
+        for key in self.keys.iter() {
+            if let DescriptorPublicKey::XPub(xkey) = key {
+                if let Some((key_fingerprint, origin)) = &xkey.origin {
+                    let origin: &[ChildNumber] = origin.as_ref();
+                    if *key_fingerprint != fingerprint
+                        || path.len() != origin.len() + 2
+                        || !path.starts_with(origin)
+                    {
+                        continue;
+                    }
+                    if let [ChildNumber::Normal { index: change }, ChildNumber::Normal { index }] =
+                        &path[origin.len()..]
+                    {
+                        if *change <= 1 {
+                            return Some((*change == 1, *index));
+                        }
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// Descriptor to sign with (external)
+    pub fn signing_descriptor(&self) -> Result<Descriptor<String>, Error> {
+        Ok(Descriptor::from_str(&self.descriptor(false)?.to_string())?)
+    }
+
+    pub fn address(&self, change: bool, index: u32, network: Network) -> Result<Address, Error> {
+        Ok(self.derive(change, index)?.address(network)?)
+    }
+
+    /// Derive addresses, with the derivation path of the key with root `fingerprint`
+    pub fn addresses(
+        &self,
+        fingerprint: Fingerprint,
+        change: bool,
+        from: u32,
+        count: u32,
+        network: Network,
+    ) -> Result<Vec<DerivedAddress>, Error> {
+        let desc: Descriptor<DescriptorPublicKey> = self.descriptor(change)?;
+        Ok(descriptors::derive_addresses(
+            &desc,
+            Some(fingerprint),
+            from,
+            count,
+            network,
+        )?)
+    }
+}
+
+/// Check network and, for BIP48 origins, the script type
+fn check_key(key: &DescriptorPublicKey, script: ScriptType, network: Network) -> Result<(), Error> {
+    if let DescriptorPublicKey::XPub(xkey) = key {
+        if (xkey.xkey.network == Network::Bitcoin) != (network == Network::Bitcoin) {
+            return Err(Error::WrongNetwork(key.to_string()));
+        }
+
+        if let Some((_, path)) = &xkey.origin {
+            if let [ChildNumber::Hardened { index: 48 }, _, _, script_type] = path.as_ref() {
+                let expected = ChildNumber::Hardened {
+                    index: script.as_u32(),
+                };
+                if *script_type != expected {
+                    return Err(Error::ScriptTypeMismatch(key.to_string()));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY_A: &str = "[0f056943/48'/1'/0'/2']tpubDF2rnouQaaYrXF4noGTv6rQYmx87cQ4GrUdhpvXkhtChwQPbdGTi8GA88NUaSrwZBwNsTkC9bFkkC8vDyGBVVAQTZ2AS6gs68RQXtXcCvkP";
+    const KEY_B: &str = "[91ef223d/48'/1'/0'/2']tpubDE2zNGQxK1h4rn7kM66E61RzvxvSZE1soaGwbwMmdD1LANkgupjT4qpgNZ2UjwwoNLyq4Xjs3XMBHpLjynChgBfgp6hKSfoT6i1BXg1cEtL";
+
+    #[test]
+    fn test_multisig_wallet() {
+        let key_a = DescriptorPublicKey::from_str(KEY_A).unwrap();
+        let cosigner =
+            Cosigner::from_str(&format!("wsh(sortedmulti(2,{KEY_A}/0/*,{KEY_B}/0/*))")).unwrap();
+        let wallet = MultisigWallet::from_cosigners(
+            "Vault",
+            ScriptType::P2WSH,
+            2,
+            key_a,
+            &[cosigner],
+            Network::Testnet,
+        )
+        .unwrap();
+        assert_eq!(wallet.keys().len(), 2);
+        assert_eq!(
+            wallet
+                .address(false, 0, Network::Testnet)
+                .unwrap()
+                .to_string(),
+            "tb1qr7ksklc4czg24lka9v33j6z9nsypluz7mlm60v9fwzzjagxxwq5q8afnje"
+        );
+        assert_eq!(
+            wallet
+                .address(true, 0, Network::Testnet)
+                .unwrap()
+                .to_string(),
+            "tb1qs6t98yah4gjg5nfu6dwdf67nvmtrt8xtq6wn9xlxetknqpk45jtqwe7pd4"
+        );
+        assert!(wallet.descriptor(false).unwrap().to_string().contains('#'));
+
+        let addresses = wallet
+            .addresses(
+                Fingerprint::from_str("91ef223d").unwrap(),
+                true,
+                0,
+                3,
+                Network::Testnet,
+            )
+            .unwrap();
+        assert_eq!(addresses.len(), 3);
+        assert_eq!(
+            addresses[0].address.to_string(),
+            "tb1qs6t98yah4gjg5nfu6dwdf67nvmtrt8xtq6wn9xlxetknqpk45jtqwe7pd4"
+        );
+        assert_eq!(
+            addresses[2].path,
+            DerivationPath::from_str("m/48'/1'/0'/2'/1/2").unwrap()
+        );
+
+        // Serialization
+        let json = serde_json::to_string(&wallet).unwrap();
+        let deserialized: MultisigWallet = serde_json::from_str(&json).unwrap();
+        assert_eq!(wallet, deserialized);
+
+        // From descriptor
+        let desc = Descriptor::from_str(&format!(
+            "wsh(sortedmulti(2,{KEY_A}/<0;1>/*,{KEY_B}/<0;1>/*))"
+        ))
+        .unwrap();
+        assert_eq!(
+            MultisigWallet::from_descriptor("Vault", &desc, Network::Testnet).unwrap(),
+            wallet
+        );
+        let desc = Descriptor::from_str(&format!(
+            "wsh(sortedmulti(1,{KEY_A}/<0;1>/*,{KEY_B}/<0;1>/*))"
+        ))
+        .unwrap();
+        let single = MultisigWallet::from_descriptor("Vault", &desc, Network::Testnet).unwrap();
+        assert_eq!(single.threshold(), 1);
+        let desc = Descriptor::from_str(&format!("wsh(multi(2,{KEY_A}/0/*,{KEY_B}/0/*))")).unwrap();
+        assert!(matches!(
+            MultisigWallet::from_descriptor("Vault", &desc, Network::Testnet),
+            Err(Error::UnsupportedDescriptor)
+        ));
+    }
+
+    #[test]
+    fn test_slip132_cosigner() {
+        let key_a = DescriptorPublicKey::from_str(KEY_A).unwrap();
+
+        // KEY_A encoded as Vpub
+        let vpub: &str = "[0f056943/48'/1'/0'/2']Vpub5mtnnUUL8u4oyRf5d2NZJqDypgmpx8FontedpqxNyjXTi6fLp8fmpp2wedS6UyuNpDgLDoVH23c6rYpFSEfB9jhdbD8gek2stjxhwJeE1Eq";
+        let cosigner = Cosigner::from_str(vpub).unwrap();
+        assert_eq!(cosigner.key(ScriptType::P2WSH).unwrap(), key_a);
+
+        let cosigner =
+            Cosigner::from_str(&format!("wsh(sortedmulti(2,{vpub}/0/*,{KEY_B}/0/*))")).unwrap();
+        assert_eq!(cosigner.keys(ScriptType::P2WSH).unwrap()[0], key_a);
+
+        // Upub (P2SH-P2WSH) with a P2WSH origin
+        let upub: &str = "[0f056943/48'/1'/0'/2']Upub5T4XUooQzDXL88Txnfaw6k8UeidP1WGJsn8R3T4Vbj9aezr7ZUWDCkNodRUWV5FTQaZXUKtiZPFYyGCgiYFAMW22isSG4qDPd1u4YibvNAK";
+        assert!(matches!(
+            Cosigner::from_str(upub),
+            Err(Error::Descriptors(
+                descriptors::Error::Slip132Mismatch { .. }
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_policy_wallet() {
+        let keys = vec![
+            (
+                String::from("me"),
+                DescriptorPublicKey::from_str(KEY_A).unwrap(),
+            ),
+            (
+                String::from("heir"),
+                DescriptorPublicKey::from_str(KEY_B).unwrap(),
+            ),
+        ];
+        let compiled = crate::policy::compile(
+            "or(pk(@me),and(pk(@heir),after(52560)))",
+            ScriptType::P2WSH,
+            &keys,
+        )
+        .unwrap();
+        let wallet = MultisigWallet::from_policy("Will", &compiled, Network::Testnet).unwrap();
+        assert_eq!(wallet.threshold(), 1);
+        assert_eq!(wallet.keys().len(), 2);
+        assert!(wallet.policy().is_some());
+        assert_ne!(
+            wallet.address(false, 0, Network::Testnet).unwrap(),
+            wallet.address(true, 0, Network::Testnet).unwrap()
+        );
+        assert_eq!(
+            wallet.find_derivation(
+                Fingerprint::from_str("91ef223d").unwrap(),
+                &DerivationPath::from_str("m/48'/1'/0'/2'/1/7").unwrap()
+            ),
+            Some((true, 7))
+        );
+
+        // Serialization
+        let json = serde_json::to_string(&wallet).unwrap();
+        let deserialized: MultisigWallet = serde_json::from_str(&json).unwrap();
+        assert_eq!(wallet, deserialized);
+
+        // Policy without its descriptor
+        let mut value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        value.as_object_mut().unwrap().remove("descriptor");
+        assert!(serde_json::from_value::<MultisigWallet>(value).is_err());
+
+        assert!(matches!(
+            MultisigWallet::from_policy("Will", &compiled, Network::Bitcoin),
+            Err(Error::WrongNetwork(..))
+        ));
+    }
+
+    #[test]
+    fn test_invalid_multisig_wallet() {
+        let keys = vec![
+            DescriptorPublicKey::from_str(KEY_A).unwrap(),
+            DescriptorPublicKey::from_str(KEY_B).unwrap(),
+        ];
+        assert!(matches!(
+            MultisigWallet::new(
+                "Vault",
+                ScriptType::P2WSH,
+                3,
+                keys.clone(),
+                Network::Testnet
+            ),
+            Err(Error::InvalidThreshold { .. })
+        ));
+        assert!(matches!(
+            MultisigWallet::new(
+                "Vault",
+                ScriptType::P2SHWSH,
+                2,
+                keys.clone(),
+                Network::Testnet
+            ),
+            Err(Error::ScriptTypeMismatch(..))
+        ));
+        assert!(matches!(
+            MultisigWallet::new(
+                "Vault",
+                ScriptType::P2WSH,
+                2,
+                keys.clone(),
+                Network::Bitcoin
+            ),
+            Err(Error::WrongNetwork(..))
+        ));
+        assert!(matches!(
+            MultisigWallet::new(
+                "Vault",
+                ScriptType::P2WSH,
+                1,
+                vec![keys[0].clone(), keys[0].clone()],
+                Network::Testnet
+            ),
+            Err(Error::DuplicateKey(..))
+        ));
+    }
+}
@@ -0,0 +1,623 @@
+// Copyright (c) 2022-2023 Yuki Kishimoto
+// Distributed under the MIT software license
+
+//! Miniscript policy compiler
+//!
+//! Compile spending policies with key aliases (ex. `or(pk(@me),and(pk(@heir),after(52560)))`)
+//! to `wsh`, `sh(wsh)` or `tr` descriptors and estimate the cost of every spending path.
+
+use core::fmt;
+use core::str::FromStr;
+
+use bdk::bitcoin::absolute::LockTime;
+use bdk::bitcoin::ecdsa::Signature as EcdsaSignature;
+use bdk::bitcoin::secp256k1::ecdsa;
+use bdk::bitcoin::sighash::EcdsaSighashType;
+use bdk::bitcoin::taproot::{Signature as SchnorrSignature, TapLeafHash};
+use bdk::bitcoin::{PublicKey, Sequence, VarInt};
+use bdk::miniscript::descriptor::{
+    ConversionError, DefiniteDescriptorKey, Descriptor, DescriptorPublicKey, ShInner, WshInner,
+};
+use bdk::miniscript::policy::compiler::CompilerError;
+use bdk::miniscript::policy::Concrete;
+use bdk::miniscript::{
+    Miniscript, MiniscriptKey, Preimage32, Satisfier, ScriptContext, Segwitv0, ToPublicKey,
+    Translator,
+};
+
+use crate::bips::bip48::ScriptType;
+use crate::descriptors;
+use crate::multisig::{self, UNSPENDABLE_KEY};
+
+/// Max number of spending paths to analyze
+const MAX_SPENDING_PATHS: usize = 256;
+
+#[derive(Debug)]
+pub enum Error {
+    Miniscript(bdk::miniscript::Error),
+    Compiler(CompilerError),
+    Conversion(ConversionError),
+    Descriptors(descriptors::Error),
+    Multisig(multisig::Error),
+    InvalidAlias(String),
+    AliasNotFound(String),
+    InvalidHash(String),
+    TooManyPaths(usize),
+}
+
+impl std::error::Error for Error {}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Miniscript(e) => write!(f, "Miniscript: {e}"),
+            Self::Compiler(e) => write!(f, "Compiler: {e}"),
+            Self::Conversion(e) => write!(f, "Conversion: {e}"),
+            Self::Descriptors(e) => write!(f, "Descriptors: {e}"),
+            Self::Multisig(e) => write!(f, "Multisig: {e}"),
+            Self::InvalidAlias(key) => {
+                write!(f, "Invalid key {key}: expected an alias (ex. `@me`)")
+            }
+            Self::AliasNotFound(alias) => write!(f, "Key not found for alias {alias}"),
+            Self::InvalidHash(hash) => write!(f, "Invalid hash {hash}"),
+            Self::TooManyPaths(max) => write!(f, "Too many spending paths: max {max}"),
+        }
+    }
+}
+
+impl From<bdk::miniscript::Error> for Error {
+    fn from(e: bdk::miniscript::Error) -> Self {
+        Self::Miniscript(e)
+    }
+}
+
+impl From<CompilerError> for Error {
+    fn from(e: CompilerError) -> Self {
+        Self::Compiler(e)
+    }
+}
+
+impl From<ConversionError> for Error {
+    fn from(e: ConversionError) -> Self {
+        Self::Conversion(e)
+    }
+}
+
+impl From<descriptors::Error> for Error {
+    fn from(e: descriptors::Error) -> Self {
+        Self::Descriptors(e)
+    }
+}
+
+impl From<multisig::Error> for Error {
+    fn from(e: multisig::Error) -> Self {
+        Self::Multisig(e)
+    }
+}
+
+/// Way to satisfy the policy, with its cost in the compiled descriptor
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpendingPath {
+    /// Conditions, with the key aliases (ex. `pk(@heir)`, `after(52560)`)
+    pub conditions: Vec<String>,
+    /// Number of signatures
+    pub signatures: usize,
+    /// Taproot key path spend
+    pub key_path: bool,
+    /// Size of the script revealed when spending (`0` for key path spends)
+    pub script_size: usize,
+    /// Witness size (weight units), with 71 bytes ECDSA and 64 bytes Schnorr signatures
+    pub witness_size: usize,
+}
+
+/// Policy compiled to a multipath descriptor (`/<0;1>/*`)
+#[derive(Debug, Clone)]
+pub struct CompiledPolicy {
+    pub policy: String,
+    pub script: ScriptType,
+    /// Account keys (with origin) by alias, in order of appearance
+    pub keys: Vec<(String, DescriptorPublicKey)>,
+    pub descriptor: Descriptor<DescriptorPublicKey>,
+    /// Spending paths, sorted by witness size
+    pub paths: Vec<SpendingPath>,
+}
+
+impl CompiledPolicy {
+    /// Min number of signatures required to spend
+    pub fn threshold(&self) -> usize {
+        self.paths
+            .iter()
+            .map(|path| path.signatures)
+            .min()
+            .unwrap_or_default()
+    }
+}
+
+/// Key alias resolved to an account key
+#[derive(Debug, Clone)]
+struct Alias {
+    name: String,
+    key: DescriptorPublicKey,
+    multipath: DescriptorPublicKey,
+    /// Key of the first external address, to estimate the witness sizes
+    pubkey: PublicKey,
+}
+
+impl Alias {
+    fn new(name: &str, key: &DescriptorPublicKey) -> Result<Self, Error> {
+        let key: DescriptorPublicKey = multisig::account_key(key)?;
+        let multipath: DescriptorPublicKey =
+            DescriptorPublicKey::from_str(&format!("{key}/<0;1>/*"))
+                .map_err(descriptors::Error::from)?;
+        let first: DefiniteDescriptorKey = DefiniteDescriptorKey::from_str(&format!("{key}/0/0"))
+            .map_err(descriptors::Error::from)?;
+        Ok(Self {
+            name: name.to_string(),
+            key,
+            multipath,
+            pubkey: first.to_public_key(),
+        })
+    }
+}
+
+struct AliasTranslator<'a> {
+    keys: &'a [(String, DescriptorPublicKey)],
+    used: Vec<Alias>,
+}
+
+impl Translator<String, DescriptorPublicKey, Error> for AliasTranslator<'_> {
+    fn pk(&mut self, pk: &String) -> Result<DescriptorPublicKey, Error> {
+        let name: &str = pk
+            .strip_prefix('@')
+            .ok_or_else(|| Error::InvalidAlias(pk.clone()))?;
+        if let Some(alias) = self.used.iter().find(|alias| alias.name == name) {
+            return Ok(alias.multipath.clone());
+        }
+        let key: &DescriptorPublicKey = self
+            .keys
+            .iter()
+            .find(|(alias, _)| alias == name)
+            .map(|(_, key)| key)
+            .ok_or_else(|| Error::AliasNotFound(pk.clone()))?;
+        let alias = Alias::new(name, key)?;
+        let multipath: DescriptorPublicKey = alias.multipath.clone();
+        self.used.push(alias);
+        Ok(multipath)
+    }
+
+    fn sha256(
+        &mut self,
+        hash: &String,
+    ) -> Result<<DescriptorPublicKey as MiniscriptKey>::Sha256, Error> {
+        FromStr::from_str(hash).map_err(|_| Error::InvalidHash(hash.clone()))
+    }
+
+    fn hash256(
+        &mut self,
+        hash: &String,
+    ) -> Result<<DescriptorPublicKey as MiniscriptKey>::Hash256, Error> {
+        FromStr::from_str(hash).map_err(|_| Error::InvalidHash(hash.clone()))
+    }
+
+    fn ripemd160(
+        &mut self,
+        hash: &String,
+    ) -> Result<<DescriptorPublicKey as MiniscriptKey>::Ripemd160, Error> {
+        FromStr::from_str(hash).map_err(|_| Error::InvalidHash(hash.clone()))
+    }
+
+    fn hash160(
+        &mut self,
+        hash: &String,
+    ) -> Result<<DescriptorPublicKey as MiniscriptKey>::Hash160, Error> {
+        FromStr::from_str(hash).map_err(|_| Error::InvalidHash(hash.clone()))
+    }
+}
+
+/// Compile `policy` for `script`
+///
+/// Keys are referenced with `@alias` and resolved from `keys` (account keys with origin, without `@`).
+pub fn compile<S>(
+    policy: S,
+    script: ScriptType,
+    keys: &[(String, DescriptorPublicKey)],
+) -> Result<CompiledPolicy, Error>
+where
+    S: Into<String>,
+{
+    let policy: String = policy.into().trim().to_string();
+    let parsed: Concrete<String> = Concrete::from_str(&policy)?;
+
+    let mut translator = AliasTranslator {
+        keys,
+        used: Vec::new(),
+    };
+    let translated: Concrete<DescriptorPublicKey> = parsed.translate_pk(&mut translator)?;
+    let aliases: Vec<Alias> = translator.used;
+
+    let descriptor: Descriptor<DescriptorPublicKey> = match script {
+        ScriptType::P2SHWSH => Descriptor::new_sh_wsh(translated.compile::<Segwitv0>()?)?,
+        ScriptType::P2WSH => Descriptor::new_wsh(translated.compile::<Segwitv0>()?)?,
+        ScriptType::P2TR => {
+            let unspendable =
+                DescriptorPublicKey::from_str(UNSPENDABLE_KEY).map_err(descriptors::Error::from)?;
+            translated.compile_tr(Some(unspendable))?
+        }
+    };
+
+    let mut paths: Vec<SpendingPath> = analyze(&descriptor, &aliases, &translated)?;
+    paths.sort_by_key(|path| path.witness_size);
+
+    Ok(CompiledPolicy {
+        policy,
+        script,
+        keys: aliases
+            .into_iter()
+            .map(|alias| (alias.name, alias.key))
+            .collect(),
+        descriptor,
+        paths,
+    })
+}
+
+/// Satisfier with dummy signatures and preimages for the conditions of a spending path
+struct PathSatisfier<'a> {
+    keys: Vec<PublicKey>,
+    conditions: &'a [Concrete<DescriptorPublicKey>],
+}
+
+impl PathSatisfier<'_> {
+    fn has<F>(&self, f: F) -> Option<Preimage32>
+    where
+        F: Fn(&Concrete<DescriptorPublicKey>) -> bool,
+    {
+        if self.conditions.iter().any(f) {
+            Some([0; 32])
+        } else {
+            None
+        }
+    }
+}
+
+impl Satisfier<DefiniteDescriptorKey> for PathSatisfier<'_> {
+    fn lookup_ecdsa_sig(&self, pk: &DefiniteDescriptorKey) -> Option<EcdsaSignature> {
+        if self.keys.contains(&pk.to_public_key()) {
+            Some(EcdsaSignature {
+                sig: ecdsa::Signature::from_compact(&[1; 64]).ok()?,
+                hash_ty: EcdsaSighashType::All,
+            })
+        } else {
+            None
+        }
+    }
+
+    fn lookup_tap_leaf_script_sig(
+        &self,
+        pk: &DefiniteDescriptorKey,
+        _: &TapLeafHash,
+    ) -> Option<SchnorrSignature> {
+        if self.keys.contains(&pk.to_public_key()) {
+            SchnorrSignature::from_slice(&[1; 64]).ok()
+        } else {
+            None
+        }
+    }
+
+    fn lookup_sha256(
+        &self,
+        hash: &<DefiniteDescriptorKey as MiniscriptKey>::Sha256,
+    ) -> Option<Preimage32> {
+        self.has(|c| matches!(c, Concrete::Sha256(h) if h == hash))
+    }
+
+    fn lookup_hash256(
+        &self,
+        hash: &<DefiniteDescriptorKey as MiniscriptKey>::Hash256,
+    ) -> Option<Preimage32> {
+        self.has(|c| matches!(c, Concrete::Hash256(h) if h == hash))
+    }
+
+    fn lookup_ripemd160(
+        &self,
+        hash: &<DefiniteDescriptorKey as MiniscriptKey>::Ripemd160,
+    ) -> Option<Preimage32> {
+        self.has(|c| matches!(c, Concrete::Ripemd160(h) if h == hash))
+    }
+
+    fn lookup_hash160(
+        &self,
+        hash: &<DefiniteDescriptorKey as MiniscriptKey>::Hash160,
+    ) -> Option<Preimage32> {
+        self.has(|c| matches!(c, Concrete::Hash160(h) if h == hash))
+    }
+
+    fn check_older(&self, sequence: Sequence) -> bool {
+        let lock = match sequence.to_relative_lock_time() {
+            Some(lock) => lock,
+            None => return false,
+        };
+        self.conditions.iter().any(|c| match c {
+            Concrete::Older(older) => older
+                .to_relative_lock_time()
+                .map_or(false, |ours| lock.is_implied_by(ours)),
+            _ => false,
+        })
+    }
+
+    fn check_after(&self, lock_time: LockTime) -> bool {
+        self.conditions.iter().any(|c| match c {
+            Concrete::After(after) => lock_time.is_implied_by(LockTime::from(*after)),
+            _ => false,
+        })
+    }
+}
+
+/// Cost of every spending path of the policy in the compiled descriptor (first external address)
+fn analyze(
+    descriptor: &Descriptor<DescriptorPublicKey>,
+    aliases: &[Alias],
+    policy: &Concrete<DescriptorPublicKey>,
+) -> Result<Vec<SpendingPath>, Error> {
+    let derived: Descriptor<DefiniteDescriptorKey> = descriptor
+        .clone()
+        .into_single_descriptors()?
+        .into_iter()
+        .next()
+        .ok_or(descriptors::Error::DescriptorNotFound)?
+        .at_derivation_index(0)?;
+
+    let mut spending_paths: Vec<SpendingPath> = Vec::new();
+    for conditions in spending_paths_of(policy)?.into_iter() {
+        let mut keys: Vec<PublicKey> = Vec::new();
+        let mut labels: Vec<String> = Vec::with_capacity(conditions.len());
+        for condition in conditions.iter() {
+            match condition {
+                Concrete::Key(key) => {
+                    if let Some(alias) = aliases.iter().find(|alias| alias.multipath == *key) {
+                        keys.push(alias.pubkey);
+                        labels.push(format!("pk(@{})", alias.name));
+                    }
+                }
+                condition => labels.push(condition.to_string()),
+            }
+        }
+        let satisfier = PathSatisfier {
+            keys,
+            conditions: &conditions,
+        };
+
+        let cost: Option<(bool, usize, usize)> = match &derived {
+            Descriptor::Wsh(wsh) => match wsh.as_inner() {
+                WshInner::Ms(ms) => script_path_cost(ms, &satisfier, None),
+                _ => None,
+            },
+            Descriptor::Sh(sh) => match sh.as_inner() {
+                ShInner::Wsh(wsh) => match wsh.as_inner() {
+                    WshInner::Ms(ms) => script_path_cost(ms, &satisfier, None),
+                    _ => None,
+                },
+                _ => None,
+            },
+            Descriptor::Tr(tr) => {
+                if satisfier.keys.contains(&tr.internal_key().to_public_key()) {
+                    // Signature (64 bytes) and the item count
+                    Some((true, 0, 66))
+                } else {
+                    tr.iter_scripts()
+                        .filter_map(|(depth, ms)| script_path_cost(ms, &satisfier, Some(depth)))
+                        .min_by_key(|(_, _, witness_size)| *witness_size)
+                }
+            }
+            _ => None,
+        };
+
+        // Paths that the compiled script can't satisfy (ex. conflicting timelocks) are skipped
+        if let Some((key_path, script_size, witness_size)) = cost {
+            spending_paths.push(SpendingPath {
+                conditions: labels,
+                signatures: satisfier.keys.len(),
+                key_path,
+                script_size,
+                witness_size,
+            });
+        }
+    }
+
+    Ok(spending_paths)
+}
+
+/// Script size and witness size (with the script and, for taproot leaves at `depth`, the control block)
+fn script_path_cost<Ctx>(
+    ms: &Miniscript<DefiniteDescriptorKey, Ctx>,
+    satisfier: &PathSatisfier,
+    depth: Option<u8>,
+) -> Option<(bool, usize, usize)>
+where
+    Ctx: ScriptContext,
+{
+    let witness: Vec<Vec<u8>> = ms.satisfy(satisfier).ok()?;
+    let script_size: usize = ms.script_size();
+    let mut items: Vec<usize> = witness.iter().map(Vec::len).collect();
+    items.push(script_size);
+    if let Some(depth) = depth {
+        items.push(33 + 32 * depth as usize);
+    }
+    let witness_size: usize = varint_len(items.len())
+        + items
+            .iter()
+            .map(|len| varint_len(*len) + *len)
+            .sum::<usize>();
+    Some((false, script_size, witness_size))
+}
+
+fn varint_len(n: usize) -> usize {
+    VarInt(n as u64).len()
+}
+
+/// Disjunctive normal form of the policy: every path is a list of conditions to satisfy together
+fn spending_paths_of(
+    policy: &Concrete<DescriptorPublicKey>,
+) -> Result<Vec<Vec<Concrete<DescriptorPublicKey>>>, Error> {
+    let paths: Vec<Vec<Concrete<DescriptorPublicKey>>> = match policy {
+        Concrete::Unsatisfiable => Vec::new(),
+        Concrete::Trivial => vec![Vec::new()],
+        Concrete::And(subs) => product(subs.iter().collect())?,
+        Concrete::Or(subs) => {
+            let mut paths = Vec::new();
+            for (_, sub) in subs.iter() {
+                paths.extend(spending_paths_of(sub)?);
+            }
+            paths
+        }
+        Concrete::Threshold(k, subs) => {
+            let mut paths = Vec::new();
+            for combination in combinations(subs.len(), *k)?.into_iter() {
+                paths.extend(product(
+                    combination.into_iter().map(|index| &subs[index]).collect(),
+                )?);
+            }
+            paths
+        }
+        condition => vec![vec![condition.clone()]],
+    };
+
+    if paths.len() > MAX_SPENDING_PATHS {
+        return Err(Error::TooManyPaths(MAX_SPENDING_PATHS));
+    }
+
+    Ok(paths)
+}
+
+/// Paths satisfying all the `policies`
+fn product(
+    policies: Vec<&Concrete<DescriptorPublicKey>>,
+) -> Result<Vec<Vec<Concrete<DescriptorPublicKey>>>, Error> {
+    let mut paths: Vec<Vec<Concrete<DescriptorPublicKey>>> = vec![Vec::new()];
+    for policy in policies.into_iter() {
+        let sub_paths = spending_paths_of(policy)?;
+        let mut next = Vec::with_capacity(paths.len() * sub_paths.len());
+        for path in paths.iter() {
+            for sub_path in sub_paths.iter() {
+                let mut path = path.clone();
+                path.extend(sub_path.iter().cloned());
+                next.push(path);
+            }
+        }
+        if next.len() > MAX_SPENDING_PATHS {
+            return Err(Error::TooManyPaths(MAX_SPENDING_PATHS));
+        }
+        paths = next;
+    }
+    Ok(paths)
+}
+
+/// `k`-combinations of the indexes `0..n`
+fn combinations(n: usize, k: usize) -> Result<Vec<Vec<usize>>, Error> {
+    let mut combinations: Vec<Vec<usize>> = Vec::new();
+    let mut current: Vec<usize> = Vec::with_capacity(k);
+    combine(0, n, k, &mut current, &mut combinations)?;
+    Ok(combinations)
+}
+
+fn combine(
+    start: usize,
+    n: usize,
+    k: usize,
+    current: &mut Vec<usize>,
+    combinations: &mut Vec<Vec<usize>>,
+) -> Result<(), Error> {
+    if current.len() == k {
+        if combinations.len() == MAX_SPENDING_PATHS {
+            return Err(Error::TooManyPaths(MAX_SPENDING_PATHS));
+        }
+        combinations.push(current.clone());
+        return Ok(());
+    }
+    for index in start..n {
+        current.push(index);
+        combine(index + 1, n, k, current, combinations)?;
+        current.pop();
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY_A: &str = "[0f056943/48'/1'/0'/2']tpubDF2rnouQaaYrXF4noGTv6rQYmx87cQ4GrUdhpvXkhtChwQPbdGTi8GA88NUaSrwZBwNsTkC9bFkkC8vDyGBVVAQTZ2AS6gs68RQXtXcCvkP";
+    const KEY_B: &str = "[91ef223d/48'/1'/0'/2']tpubDE2zNGQxK1h4rn7kM66E61RzvxvSZE1soaGwbwMmdD1LANkgupjT4qpgNZ2UjwwoNLyq4Xjs3XMBHpLjynChgBfgp6hKSfoT6i1BXg1cEtL";
+    const POLICY: &str = "or(99@pk(@me),1@and(pk(@heir),after(52560)))";
+
+    fn keys() -> Vec<(String, DescriptorPublicKey)> {
+        vec![
+            (
+                String::from("me"),
+                DescriptorPublicKey::from_str(KEY_A).unwrap(),
+            ),
+            (
+                String::from("heir"),
+                DescriptorPublicKey::from_str(KEY_B).unwrap(),
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_compile_wsh() {
+        let compiled = compile(POLICY, ScriptType::P2WSH, &keys()).unwrap();
+        assert!(compiled.descriptor.is_multipath());
+        assert!(compiled.descriptor.to_string().starts_with("wsh("));
+        assert_eq!(compiled.keys.len(), 2);
+        assert_eq!(compiled.threshold(), 1);
+
+        assert_eq!(compiled.paths.len(), 2);
+        assert_eq!(compiled.paths[0].conditions, vec!["pk(@me)"]);
+        assert_eq!(
+            compiled.paths[1].conditions,
+            vec!["pk(@heir)", "after(52560)"]
+        );
+        assert!(!compiled.paths[0].key_path);
+        assert_eq!(compiled.paths[0].script_size, compiled.paths[1].script_size);
+        assert!(compiled.paths[0].witness_size < compiled.paths[1].witness_size);
+    }
+
+    #[test]
+    fn test_compile_tr() {
+        let compiled = compile(POLICY, ScriptType::P2TR, &keys()).unwrap();
+        assert!(compiled.descriptor.to_string().starts_with("tr("));
+        assert_eq!(compiled.paths.len(), 2);
+        assert!(compiled.paths[0].key_path);
+        assert_eq!(compiled.paths[0].witness_size, 66);
+        assert!(!compiled.paths[1].key_path);
+        assert!(compiled.paths[1].script_size > 0);
+    }
+
+    #[test]
+    fn test_compile_errors() {
+        assert!(matches!(
+            compile("pk(@nobody)", ScriptType::P2WSH, &keys()),
+            Err(Error::AliasNotFound(..))
+        ));
+        assert!(matches!(
+            compile(format!("pk({KEY_A})"), ScriptType::P2WSH, &keys()),
+            Err(Error::InvalidAlias(..))
+        ));
+        assert!(matches!(
+            compile(
+                "thresh(1,pk(@me),pk(@heir),sha256(00),older(10))",
+                ScriptType::P2WSH,
+                &keys()
+            ),
+            Err(Error::InvalidHash(..))
+        ));
+    }
+
+    #[test]
+    fn test_combinations() {
+        assert_eq!(
+            combinations(3, 2).unwrap(),
+            vec![vec![0, 1], vec![0, 2], vec![1, 2]]
+        );
+        assert!(combinations(20, 10).is_err());
+    }
+}
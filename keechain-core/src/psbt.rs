@@ -10,19 +10,23 @@ use std::path::Path;
 use std::str::FromStr;
 use std::sync::Arc;
 
+use bdk::bitcoin::bip32::KeySource;
 use bdk::bitcoin::psbt::{self, PartiallySignedTransaction, PsbtParseError};
 use bdk::bitcoin::secp256k1::{Secp256k1, Signing};
-use bdk::bitcoin::{Network, PrivateKey};
+use bdk::bitcoin::{Address, Network, OutPoint, PrivateKey, Script, ScriptBuf, TxIn, TxOut};
 use bdk::miniscript::descriptor::DescriptorKeyParseError;
 use bdk::miniscript::Descriptor;
 use bdk::signer::{SignerContext, SignerOrdering, SignerWrapper};
 use bdk::{KeychainKind, SignOptions, Wallet};
 
-use crate::bips::bip32::{self, Bip32, DerivationPath, ExtendedPrivKey, Fingerprint};
+use crate::bips::bip32::{self, Bip32, ChildNumber, DerivationPath, ExtendedPrivKey, Fingerprint};
+use crate::bips::bip388::{self, WalletPolicy};
 use crate::bips::bip43::Purpose;
 use crate::bips::bip44::{self, ExtendedPath};
-use crate::bips::bip48::ScriptType;
-use crate::types::Seed;
+use crate::blinded::BlindedKey;
+use crate::multisig::MultisigWallet;
+use crate::seeds::electrum::ElectrumSeedVersion;
+use crate::types::{Seed, SeedKind};
 use crate::util::base64;
 use crate::{descriptors, Descriptors};
 
@@ -32,6 +36,7 @@ pub enum Error {
     Base64(base64::DecodeError),
     BIP32(bip32::Error),
     BIP44(bip44::Error),
+    BIP388(bip388::Error),
     Psbt(psbt::Error),
     PsbtParse(PsbtParseError),
     Descriptors(descriptors::Error),
@@ -42,6 +47,10 @@ pub enum Error {
     InvalidDerivationPath,
     NothingToSign,
     PsbtNotSigned,
+    /// Multisig inputs don't belong to a registered wallet
+    UnregisteredWallet,
+    /// Output carries the cosigners' key origins but doesn't derive from the wallet
+    ChangeMismatch(usize),
 }
 
 impl std::error::Error for Error {}
@@ -53,6 +62,7 @@ impl fmt::Display for Error {
             Self::Base64(e) => write!(f, "Base64: {e}"),
             Self::BIP32(e) => write!(f, "BIP32: {e}"),
             Self::BIP44(e) => write!(f, "BIP44: {e}"),
+            Self::BIP388(e) => write!(f, "BIP388: {e}"),
             Self::Psbt(e) => write!(f, "Psbt: {e}"),
             Self::PsbtParse(e) => write!(f, "Psbt parse: {e}"),
             Self::Descriptors(e) => write!(f, "Descriptors: {e}"),
@@ -63,6 +73,14 @@ impl fmt::Display for Error {
             Self::InvalidDerivationPath => write!(f, "Invalid derivation path"),
             Self::NothingToSign => write!(f, "Nothing to sign here"),
             Self::PsbtNotSigned => write!(f, "PSBT not signed"),
+            Self::UnregisteredWallet => write!(
+                f,
+                "Inputs don't belong to a registered multisig wallet: register it before signing"
+            ),
+            Self::ChangeMismatch(index) => write!(
+                f,
+                "Output #{index} claims to be change but doesn't derive from the wallet cosigners"
+            ),
         }
     }
 }
@@ -91,6 +109,12 @@ impl From<bip44::Error> for Error {
     }
 }
 
+impl From<bip388::Error> for Error {
+    fn from(e: bip388::Error) -> Self {
+        Self::BIP388(e)
+    }
+}
+
 impl From<psbt::Error> for Error {
     fn from(e: psbt::Error) -> Self {
         Self::Psbt(e)
@@ -127,6 +151,48 @@ impl From<bdk::descriptor::DescriptorError> for Error {
     }
 }
 
+/// Descriptor or BIP388 wallet policy to sign with
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SigningDescriptor {
+    Descriptor(Descriptor<String>),
+    WalletPolicy(WalletPolicy),
+}
+
+impl From<Descriptor<String>> for SigningDescriptor {
+    fn from(desc: Descriptor<String>) -> Self {
+        Self::Descriptor(desc)
+    }
+}
+
+impl From<WalletPolicy> for SigningDescriptor {
+    fn from(policy: WalletPolicy) -> Self {
+        Self::WalletPolicy(policy)
+    }
+}
+
+impl FromStr for SigningDescriptor {
+    type Err = bip388::Error;
+
+    /// Parse a descriptor or a wallet policy JSON
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s: &str = s.trim();
+        if s.starts_with('{') {
+            Ok(Self::WalletPolicy(WalletPolicy::from_json(s)?))
+        } else {
+            Ok(Self::Descriptor(Descriptor::from_str(s)?))
+        }
+    }
+}
+
+impl SigningDescriptor {
+    pub fn to_descriptor(&self) -> Result<Descriptor<String>, bip388::Error> {
+        match self {
+            Self::Descriptor(desc) => Ok(desc.clone()),
+            Self::WalletPolicy(policy) => policy.signing_descriptor(),
+        }
+    }
+}
+
 pub trait PsbtUtility: Sized {
     fn from_base64<S>(psbt: S) -> Result<Self, Error>
     where
@@ -158,16 +224,19 @@ pub trait PsbtUtility: Sized {
         self.sign_custom(seed, None, Vec::new(), network, secp)
     }
 
-    fn sign_with_descriptor<C>(
+    /// Sign with a descriptor or a BIP388 wallet policy
+    fn sign_with_descriptor<D, C>(
         &mut self,
         seed: &Seed,
-        descriptor: Descriptor<String>,
+        descriptor: D,
         network: Network,
         secp: &Secp256k1<C>,
     ) -> Result<bool, Error>
     where
+        D: Into<SigningDescriptor>,
         C: Signing,
     {
+        let descriptor: Descriptor<String> = descriptor.into().to_descriptor()?;
         self.sign_custom(seed, Some(descriptor), Vec::new(), network, secp)
     }
 
@@ -221,26 +290,392 @@ impl PsbtUtility for PartiallySignedTransaction {
     where
         C: Signing,
     {
-        match sign_psbt(
-            self,
-            seed,
-            descriptor.clone(),
-            custom_signers.clone(),
-            false,
-            network,
-            secp,
+        sign_psbt(self, seed, descriptor, custom_signers, network, secp)
+    }
+
+    fn as_base64(&self) -> String {
+        self.to_string()
+    }
+}
+
+/// Whether a PSBT input or output belongs to the wallet
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ownership {
+    Mine {
+        purpose: Purpose,
+        change: bool,
+        index: u32,
+    },
+    External,
+}
+
+impl fmt::Display for Ownership {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Mine {
+                purpose,
+                change,
+                index,
+            } => write!(
+                f,
+                "mine (BIP{}, {} #{index})",
+                purpose.as_u32(),
+                if *change { "change" } else { "receive" }
+            ),
+            Self::External => write!(f, "external"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct InputReview {
+    pub outpoint: OutPoint,
+    /// `None` if the PSBT doesn't include the previous output
+    pub amount: Option<u64>,
+    pub ownership: Ownership,
+}
+
+#[derive(Debug, Clone)]
+pub struct OutputReview {
+    pub address: Option<Address>,
+    pub amount: u64,
+    pub ownership: Ownership,
+}
+
+/// PSBT inputs and outputs, checked against the wallet descriptors
+///
+/// Ownership is verified by deriving the script at the index of the PSBT key origins,
+/// so a wrong `bip32_derivation` can't make an output look like change.
+#[derive(Debug, Clone)]
+pub struct PsbtReview {
+    pub inputs: Vec<InputReview>,
+    pub outputs: Vec<OutputReview>,
+}
+
+impl PsbtReview {
+    pub fn new(
+        psbt: &PartiallySignedTransaction,
+        descriptors: &Descriptors,
+        network: Network,
+    ) -> Self {
+        let mut inputs: Vec<InputReview> = Vec::with_capacity(psbt.inputs.len());
+        for (txin, input) in psbt.unsigned_tx.input.iter().zip(psbt.inputs.iter()) {
+            let prevout: Option<TxOut> = prevout(txin, input);
+            let paths = input
+                .bip32_derivation
+                .values()
+                .map(|(_, path)| path)
+                .chain(input.tap_key_origins.values().map(|(_, (_, path))| path));
+            let ownership: Ownership = match &prevout {
+                Some(txout) => ownership(descriptors, txout, paths),
+                None => Ownership::External,
+            };
+            inputs.push(InputReview {
+                outpoint: txin.previous_output,
+                amount: prevout.map(|txout| txout.value),
+                ownership,
+            });
+        }
+
+        let mut outputs: Vec<OutputReview> = Vec::with_capacity(psbt.outputs.len());
+        for (txout, output) in psbt.unsigned_tx.output.iter().zip(psbt.outputs.iter()) {
+            let paths = output
+                .bip32_derivation
+                .values()
+                .map(|(_, path)| path)
+                .chain(output.tap_key_origins.values().map(|(_, (_, path))| path));
+            outputs.push(OutputReview {
+                address: Address::from_script(&txout.script_pubkey, network).ok(),
+                amount: txout.value,
+                ownership: ownership(descriptors, txout, paths),
+            });
+        }
+
+        Self { inputs, outputs }
+    }
+
+    /// Fee (`None` if some input amount is unknown)
+    pub fn fee(&self) -> Option<u64> {
+        let mut total_in: u64 = 0;
+        for input in self.inputs.iter() {
+            total_in += input.amount?;
+        }
+        let total_out: u64 = self.outputs.iter().map(|o| o.amount).sum();
+        total_in.checked_sub(total_out)
+    }
+
+    /// Amount leaving the wallet (external outputs)
+    pub fn spending(&self) -> u64 {
+        self.outputs
+            .iter()
+            .filter(|o| o.ownership == Ownership::External)
+            .map(|o| o.amount)
+            .sum()
+    }
+}
+
+/// PSBT spending from a registered multisig wallet
+#[derive(Debug, Clone)]
+pub struct MultisigReview {
+    pub wallet: MultisigWallet,
+    /// Indexes of the outputs verified as change
+    pub change: Vec<usize>,
+}
+
+impl MultisigReview {
+    /// Whether the output at `index` was verified as change (otherwise it's external)
+    pub fn is_change(&self, index: usize) -> bool {
+        self.change.contains(&index)
+    }
+}
+
+/// Check a multisig PSBT against the registered wallets
+///
+/// Return `None` if no input spends a script (P2SH, P2WSH or taproot script path).
+/// Otherwise every input must derive from the same registered wallet
+/// and every output carrying key origins of its cosigners must derive from it too (change),
+/// otherwise the PSBT is refused.
+pub fn verify_multisig(
+    psbt: &PartiallySignedTransaction,
+    wallets: &[MultisigWallet],
+) -> Result<Option<MultisigReview>, Error> {
+    if !is_multisig(psbt) {
+        return Ok(None);
+    }
+
+    let wallet: &MultisigWallet = wallets
+        .iter()
+        .find(|wallet| {
+            psbt.unsigned_tx.input.iter().zip(psbt.inputs.iter()).all(
+                |(txin, input)| match prevout(txin, input) {
+                    Some(txout) => derives_from(
+                        wallet,
+                        &key_sources(input),
+                        &txout.script_pubkey,
+                        input.witness_script.as_ref(),
+                    ),
+                    None => false,
+                },
+            )
+        })
+        .ok_or(Error::UnregisteredWallet)?;
+
+    let fingerprints = wallet.fingerprints();
+    let mut change: Vec<usize> = Vec::new();
+    for (index, (txout, output)) in psbt
+        .unsigned_tx
+        .output
+        .iter()
+        .zip(psbt.outputs.iter())
+        .enumerate()
+    {
+        let sources: Vec<&KeySource> = output
+            .bip32_derivation
+            .values()
+            .chain(output.tap_key_origins.values().map(|(_, source)| source))
+            .collect();
+        if !sources.iter().any(|(fp, _)| fingerprints.contains(fp)) {
+            // External
+            continue;
+        }
+        if derives_from(
+            wallet,
+            &sources,
+            &txout.script_pubkey,
+            output.witness_script.as_ref(),
         ) {
-            Ok(finalized) => Ok(finalized),
-            Err(Error::PsbtNotSigned) => {
-                sign_psbt(self, seed, descriptor, custom_signers, true, network, secp)
+            change.push(index);
+        } else {
+            return Err(Error::ChangeMismatch(index));
+        }
+    }
+
+    Ok(Some(MultisigReview {
+        wallet: wallet.clone(),
+        change,
+    }))
+}
+
+/// Inputs spending a script (P2SH, P2WSH or taproot script path) instead of a single key
+///
+/// Decided from the scripts, not from the key origins: a coordinator can strip the cosigners'
+/// origins to make a multisig input look single-sig.
+fn is_multisig(psbt: &PartiallySignedTransaction) -> bool {
+    psbt.unsigned_tx
+        .input
+        .iter()
+        .zip(psbt.inputs.iter())
+        .any(|(txin, input)| is_script_spend(txin, input))
+}
+
+fn is_script_spend(txin: &TxIn, input: &psbt::Input) -> bool {
+    // P2SH-P2WPKH is the only single-key script with a redeem script
+    let is_p2sh_wpkh: bool = input
+        .redeem_script
+        .as_ref()
+        .map_or(false, |script| script.is_v0_p2wpkh());
+    if input.witness_script.is_some() || (input.redeem_script.is_some() && !is_p2sh_wpkh) {
+        return true;
+    }
+
+    if !input.tap_scripts.is_empty()
+        || input
+            .tap_key_origins
+            .values()
+            .any(|(leaf_hashes, _)| !leaf_hashes.is_empty())
+    {
+        return true;
+    }
+
+    match prevout(txin, input) {
+        Some(txout) => {
+            txout.script_pubkey.is_v0_p2wsh() || (txout.script_pubkey.is_p2sh() && !is_p2sh_wpkh)
+        }
+        None => false,
+    }
+}
+
+fn key_sources(input: &psbt::Input) -> Vec<&KeySource> {
+    input
+        .bip32_derivation
+        .values()
+        .chain(input.tap_key_origins.values().map(|(_, source)| source))
+        .collect()
+}
+
+fn prevout(txin: &TxIn, input: &psbt::Input) -> Option<TxOut> {
+    input.witness_utxo.clone().or_else(|| {
+        input
+            .non_witness_utxo
+            .as_ref()
+            .and_then(|tx| tx.output.get(txin.previous_output.vout as usize).cloned())
+    })
+}
+
+/// Check that the script (and the witness script, if any) derives from `wallet` at one of the key origins
+fn derives_from(
+    wallet: &MultisigWallet,
+    sources: &[&KeySource],
+    script_pubkey: &Script,
+    witness_script: Option<&ScriptBuf>,
+) -> bool {
+    for (fingerprint, path) in sources.iter() {
+        if let Some((change, index)) = wallet.find_derivation(*fingerprint, path) {
+            if let Ok(derived) = wallet.derive(change, index) {
+                if derived.script_pubkey().as_script() != script_pubkey {
+                    continue;
+                }
+                match (witness_script, derived.explicit_script()) {
+                    (Some(witness_script), Ok(expected)) if *witness_script != expected => continue,
+                    _ => return true,
+                }
             }
-            Err(e) => Err(e),
         }
     }
+    false
+}
 
-    fn as_base64(&self) -> String {
-        self.to_string()
+fn ownership<'a, I>(descriptors: &Descriptors, txout: &TxOut, paths: I) -> Ownership
+where
+    I: Iterator<Item = &'a DerivationPath>,
+{
+    for path in paths {
+        if let Some((purpose, change, index)) = descriptors.find_script(&txout.script_pubkey, path)
+        {
+            return Ownership::Mine {
+                purpose,
+                change,
+                index,
+            };
+        }
+    }
+    Ownership::External
+}
+
+/// Signer context of an ECDSA key, from the script type of the input
+fn ecdsa_signer_context(txin: &TxIn, input: &psbt::Input) -> SignerContext {
+    let is_segwit: bool = input.witness_script.is_some()
+        || input
+            .redeem_script
+            .as_ref()
+            .map_or(false, |script| script.is_witness_program())
+        || prevout(txin, input).map_or(false, |txout| txout.script_pubkey.is_witness_program());
+    if is_segwit {
+        SignerContext::Segwitv0
+    } else {
+        SignerContext::Legacy
+    }
+}
+
+/// Our key paths and the signer context of every key, inferred from the inputs
+fn signing_paths<'a>(
+    psbt: &'a PartiallySignedTransaction,
+    root_fingerprint: &Fingerprint,
+) -> Vec<(&'a DerivationPath, SignerContext)> {
+    let mut paths: Vec<(&DerivationPath, SignerContext)> = Vec::new();
+
+    for (txin, input) in psbt.unsigned_tx.input.iter().zip(psbt.inputs.iter()) {
+        for (fingerprint, path) in input.bip32_derivation.values() {
+            if fingerprint.eq(root_fingerprint) {
+                paths.push((path, ecdsa_signer_context(txin, input)));
+            }
+        }
+
+        for (key, (leaf_hashes, (fingerprint, path))) in input.tap_key_origins.iter() {
+            if fingerprint.eq(root_fingerprint) {
+                if input.tap_internal_key == Some(*key) {
+                    paths.push((
+                        path,
+                        SignerContext::Tap {
+                            is_internal_key: true,
+                        },
+                    ));
+                }
+                if !leaf_hashes.is_empty() {
+                    paths.push((
+                        path,
+                        SignerContext::Tap {
+                            is_internal_key: false,
+                        },
+                    ));
+                }
+            }
+        }
+    }
+
+    paths
+}
+
+/// Signers of the keys derived from our blinded xpubs, for the key origins that start at the blinded xpub
+///
+/// Coordinators that drop the blinded origin use the fingerprint of the blinded xpub as root:
+/// the full path is the blinded key path followed by the path of the origin.
+pub fn blinded_signers<C>(
+    psbt: &PartiallySignedTransaction,
+    seed: &Seed,
+    blinded_keys: &[BlindedKey],
+    network: Network,
+    secp: &Secp256k1<C>,
+) -> Result<Vec<SignerWrapper<PrivateKey>>, Error>
+where
+    C: Signing,
+{
+    let root: ExtendedPrivKey = seed.to_bip32_root_key(network)?;
+    let root_fingerprint: Fingerprint = root.fingerprint(secp);
+
+    let mut signers: Vec<SignerWrapper<PrivateKey>> = Vec::new();
+    for blinded in blinded_keys
+        .iter()
+        .filter(|blinded| blinded.root_fingerprint() == root_fingerprint)
+    {
+        for (path, signer_ctx) in signing_paths(psbt, &blinded.fingerprint()).into_iter() {
+            let path: DerivationPath = blinded.path().extend(path);
+            let child_priv: ExtendedPrivKey = root.derive_priv(secp, &path)?;
+            let private_key: PrivateKey = PrivateKey::new(child_priv.private_key, network);
+            signers.push(SignerWrapper::new(private_key, signer_ctx));
+        }
     }
+    Ok(signers)
 }
 
 fn sign_psbt<C>(
@@ -248,7 +683,6 @@ fn sign_psbt<C>(
     seed: &Seed,
     descriptor: Option<Descriptor<String>>,
     custom_signers: Vec<SignerWrapper<PrivateKey>>,
-    use_tr_internal_key: bool,
     network: Network,
     secp: &Secp256k1<C>,
 ) -> Result<bool, Error>
@@ -258,21 +692,8 @@ where
     let root: ExtendedPrivKey = seed.to_bip32_root_key(network)?;
     let root_fingerprint: Fingerprint = root.fingerprint(secp);
 
-    let mut paths: Vec<&DerivationPath> = Vec::new();
-
-    for input in psbt.inputs.iter() {
-        for (fingerprint, path) in input.bip32_derivation.values() {
-            if fingerprint.eq(&root_fingerprint) {
-                paths.push(path);
-            }
-        }
-
-        for (_, (fingerprint, path)) in input.tap_key_origins.values() {
-            if fingerprint.eq(&root_fingerprint) {
-                paths.push(path);
-            }
-        }
-    }
+    let base_psbt: PartiallySignedTransaction = psbt.clone();
+    let paths: Vec<(&DerivationPath, SignerContext)> = signing_paths(&base_psbt, &root_fingerprint);
 
     if paths.is_empty() && custom_signers.is_empty() {
         return Err(Error::NothingToSign);
@@ -281,39 +702,58 @@ where
     let descriptor: String = match descriptor {
         Some(desc) => desc.to_string(),
         None => {
-            let path = paths.first().ok_or(Error::NothingToSign)?;
-            let extended_path = ExtendedPath::from_derivation_path(path)?;
-
-            let descriptors = Descriptors::new(seed, network, Some(extended_path.account), secp)?;
-            let descriptor =
-                descriptors.get_by_purpose(extended_path.purpose, extended_path.change)?;
-            descriptor.to_string()
+            let path: Option<&DerivationPath> = paths.first().map(|(path, _)| *path);
+            match (path, &seed.kind()) {
+                // Electrum seeds don't follow the BIP43 paths
+                (Some(path), SeedKind::Electrum(mnemonic)) => {
+                    // Path: <root>/<change>/<index>
+                    let version: ElectrumSeedVersion = mnemonic.version();
+                    let depth: usize = version.account_extended_path().len();
+                    let change: bool = matches!(
+                        path.into_iter().nth(depth),
+                        Some(ChildNumber::Normal { index: 1 })
+                    );
+                    let descriptors = Descriptors::new(seed, network, None, secp)?;
+                    let descriptor = descriptors.get_by_purpose(version.purpose(), change)?;
+                    descriptor.to_string()
+                }
+                (path, _) => match path.map(ExtendedPath::from_derivation_path) {
+                    Some(Ok(ExtendedPath {
+                        purpose:
+                            purpose
+                            @ (Purpose::BIP44 | Purpose::BIP49 | Purpose::BIP84 | Purpose::BIP86),
+                        account,
+                        change,
+                        ..
+                    })) => {
+                        let descriptors = Descriptors::new(seed, network, Some(account), secp)?;
+                        let descriptor = descriptors.get_by_purpose(purpose, change)?;
+                        descriptor.to_string()
+                    }
+                    // BIP45, custom and non-standard paths (or only custom signers) have no
+                    // single-sig descriptor: the keys are signed by path, the wallet only holds the signers
+                    _ => {
+                        // Electrum seeds have only the descriptor of their version
+                        let purpose: Purpose = match &seed.kind() {
+                            SeedKind::Electrum(mnemonic) => mnemonic.version().purpose(),
+                            _ => Purpose::BIP84,
+                        };
+                        let descriptors = Descriptors::new(seed, network, None, secp)?;
+                        let descriptor = descriptors.get_by_purpose(purpose, false)?;
+                        descriptor.to_string()
+                    }
+                },
+            }
         }
     };
 
     let mut wallet: Wallet = Wallet::new_no_persist(&descriptor, None, network)?;
 
-    let base_psbt: PartiallySignedTransaction = psbt.clone();
     let mut counter: usize = 0;
 
-    for path in paths.into_iter() {
+    for (path, signer_ctx) in paths.into_iter() {
         let child_priv: ExtendedPrivKey = root.derive_priv(secp, path)?;
         let private_key: PrivateKey = PrivateKey::new(child_priv.private_key, network);
-        let extended_path = ExtendedPath::from_derivation_path(path)?;
-        let signer_ctx: SignerContext = match extended_path.purpose {
-            Purpose::BIP44 => SignerContext::Legacy,
-            Purpose::BIP48 { script } => match script {
-                ScriptType::P2SHWSH | ScriptType::P2WSH => SignerContext::Segwitv0,
-                ScriptType::P2TR => SignerContext::Tap {
-                    is_internal_key: false,
-                },
-            },
-            Purpose::BIP49 | Purpose::BIP84 => SignerContext::Segwitv0,
-            Purpose::BIP86 => SignerContext::Tap {
-                is_internal_key: use_tr_internal_key,
-            },
-        };
-
         let signer: SignerWrapper<PrivateKey> = SignerWrapper::new(private_key, signer_ctx);
         wallet.add_signer(
             KeychainKind::External,
@@ -350,6 +790,8 @@ mod tests {
     use bip39::Mnemonic;
 
     use super::*;
+    use crate::bips::bip48::ScriptType;
+    use crate::seeds::electrum::ElectrumMnemonic;
     use crate::types::Seed;
 
     const NETWORK: Network = Network::Testnet;
@@ -364,6 +806,50 @@ mod tests {
         assert!(finalized);
     }
 
+    #[test]
+    fn test_psbt_sign_electrum_seed() {
+        let secp = Secp256k1::new();
+
+        // Standard
+        let mnemonic = ElectrumMnemonic::from_str(
+            "cycle rocket west magnet parrot shuffle foot correct salt library feed song",
+        )
+        .unwrap();
+        let seed = Seed::with_kind::<&str>(SeedKind::Electrum(mnemonic), None);
+        let mut psbt = PartiallySignedTransaction::from_base64("cHNidP8BAFUCAAAAAc2lSQg2z1cxX6AQn2RrW/AY1YAZ3I5VSy8DSVnGWD6DAAAAAAD9////AThKAAAAAAAAGXapFKk+yUXzH5fhXcL+Zvy0NX0h/gUMiKwAAAAAAAEAVQIAAAABEREREREREREREREREREREREREREREREREREREREREREAAAAAAP////8BIE4AAAAAAAAZdqkUqT7JRfMfl+Fdwv5m/LQ1fSH+BQyIrAAAAAAiBgKPHJRBwo740w5XdQS1K/7cA6+uLMY9ALmgDwMqkz8TLAxIrcegAAAAAAMAAAAAAA==").unwrap();
+        let finalized = psbt.sign_with_seed(&seed, NETWORK, &secp).unwrap();
+        assert!(finalized);
+
+        // Segwit
+        let mnemonic = ElectrumMnemonic::from_str(
+            "bitter grass shiver impose acquire brush forget axis eager alone wine silver",
+        )
+        .unwrap();
+        let seed = Seed::with_kind::<&str>(SeedKind::Electrum(mnemonic), None);
+        let mut psbt = PartiallySignedTransaction::from_base64("cHNidP8BAFICAAAAAZZEBxbOQFRQT8rM4dE7yHsv9829pCBZMadtWxat5JcnAAAAAAD9////AThKAAAAAAAAFgAUKEUgyBWYDUJiZHZtjZMAE90gqmAAAAAAAAEAUgIAAAABEREREREREREREREREREREREREREREREREREREREREREAAAAAAP////8BIE4AAAAAAAAWABQoRSDIFZgNQmJkdm2NkwAT3SCqYAAAAAABAR8gTgAAAAAAABYAFChFIMgVmA1CYmR2bY2TABPdIKpgIgYCHxEJCd7WU4KKJUUVtYSYprr8lnmfsIUVVEY+1Eyn2doQsuNafQAAAIAAAAAAAQAAAAAA").unwrap();
+        let finalized = psbt.sign_with_seed(&seed, NETWORK, &secp).unwrap();
+        assert!(finalized);
+    }
+
+    #[test]
+    fn test_psbt_sign_custom_paths() {
+        let secp = Secp256k1::new();
+        let mnemonic = Mnemonic::from_str("easy uncover favorite crystal bless differ energy seat ecology match carry group refuse together chat observe hidden glad brave month diesel sustain depth salt").unwrap();
+        let seed = Seed::new::<&str>(mnemonic, None);
+
+        // P2WPKH at m/0'/0'/5'
+        let mut psbt = PartiallySignedTransaction::from_base64("cHNidP8BAFICAAAAAfMi1PZBOrnkprgAQgm7xwvuAJBcyphDTRbNg0WHxwYnAAAAAAD9////ASgjAAAAAAAAFgAUXiKiv+eM13C/pOMGJ0YWnjTKdiUAAAAAAAEAUgIAAAABEREREREREREREREREREREREREREREREREREREREREREAAAAAAP3///8BECcAAAAAAAAWABReIqK/54zXcL+k4wYnRhaeNMp2JQAAAAABAR8QJwAAAAAAABYAFF4ior/njNdwv6TjBidGFp40ynYlIgYDYjdYbuarvgF8fbH6BdYPWYX6eFoQbqH7/09hylh9D0QQm/Q1SwAAAIAAAACABQAAgAAA").unwrap();
+        psbt.sign_with_seed(&seed, NETWORK, &secp).unwrap();
+        assert_eq!(psbt.inputs[0].partial_sigs.len(), 1);
+        psbt.finalize_mut(&secp).unwrap();
+
+        // BIP45 2-of-2 P2SH at m/45'/0/0/0
+        let mut psbt = PartiallySignedTransaction::from_base64("cHNidP8BAFMCAAAAAWQcdyFU4rzIJyt5s8fMqidr1y5OCUeksc94UNpM0LAHAAAAAAD9////ASgjAAAAAAAAF6kUcH83UuPl2fdx7qGhI3dYxsbErXOHAAAAAAABAFMCAAAAARERERERERERERERERERERERERERERERERERERERERERAAAAAAD9////ARAnAAAAAAAAF6kUcH83UuPl2fdx7qGhI3dYxsbErXOHAAAAAAEER1IhAmUZQDIIXW1lnGbru3HuD4kBnWFa6fnZa31MJC6C7jmdIQMbhMVWexJkQJldPtWqugVl1x4YNGBIGf+cF/Xp1d0Hj1KuIgYCZRlAMghdbWWcZuu7ce4PiQGdYVrp+dlrfUwkLoLuOZ0Um/Q1Sy0AAIAAAAAAAAAAAAAAAAAiBgMbhMVWexJkQJldPtWqugVl1x4YNGBIGf+cF/Xp1d0HjxQSNFZ4LQAAgAEAAAAAAAAAAAAAAAAA").unwrap();
+        let finalized = psbt.sign_with_seed(&seed, NETWORK, &secp).unwrap();
+        assert!(!finalized);
+        assert_eq!(psbt.inputs[0].partial_sigs.len(), 1);
+    }
+
     #[test]
     fn test_psbt_sign_custom_internal() {
         let secp = Secp256k1::new();
@@ -396,6 +882,8 @@ mod tests {
         )
         .unwrap();
         let seed = Seed::from_mnemonic(mnemonic);
+        let policy = SigningDescriptor::from_str(r#"{"descriptor_template":"tr(@0/**,{pk(@1/**),pk(@2/**)})","keys_info":["[5cb492a5/86'/1'/784923']tpubDD56LAR1MR7X5EeZYMpvivk2Lh3HMo4vdDNQ8jAv4oBjLPEddQwxaxNypvrHbMk2qTxAj44YLzqHrzwy5LDNmVyYZBesm6aShhmhYrA8veT","[76fdbca2/86'/1'/784923']tpubDCDepsNyAPWySAgXx1Por6sHpSWzxsTB9XJp5erEN7NumgdZMhhmycJGMQ1cHZwx66KyZr6psjttDDQ7mV4uJGV2DvB9Mri1nTVmpquvTDR","[3b8ae29b/86'/1'/784923']tpubDDpkQsJQTpHi2bH5Cg7L1pThUxeEStcn9ZsQ53XHkW8Fs81h71XobqpwYf2Jb8ECmW1mUUJxQhZstmwFUg5wQ6EVzH5HmF3cpHcyxjvF1Ep"]}"#).unwrap();
+        assert_eq!(policy.to_descriptor().unwrap(), descriptor);
 
         for (index, psbt_str) in psbts.into_iter().enumerate() {
             dbg!(index);
@@ -405,6 +893,206 @@ mod tests {
                 .unwrap();
             assert!(finalized);
             psbt.finalize_mut(&secp).unwrap();
+
+            // Same wallet, as a BIP388 wallet policy
+            let mut psbt = PartiallySignedTransaction::from_base64(psbt_str).unwrap();
+            let finalized = psbt
+                .sign_with_descriptor(&seed, policy.clone(), Network::Testnet, &secp)
+                .unwrap();
+            assert!(finalized);
+            psbt.finalize_mut(&secp).unwrap();
         }
     }
+
+    #[test]
+    fn test_psbt_sign_blinded() {
+        use bdk::bitcoin::absolute::LockTime;
+        use bdk::bitcoin::{PublicKey, Transaction};
+
+        let secp = Secp256k1::new();
+        let mnemonic = Mnemonic::from_str("easy uncover favorite crystal bless differ energy seat ecology match carry group refuse together chat observe hidden glad brave month diesel sustain depth salt").unwrap();
+        let seed = Seed::new::<&str>(mnemonic, None);
+        let root: ExtendedPrivKey = seed.to_bip32_root_key(NETWORK).unwrap();
+        let account = DerivationPath::from_str("m/48'/1'/0'/2'").unwrap();
+        let blinding = DerivationPath::from_str("m/1534566890/7/2147483647/0").unwrap();
+        let blinded = BlindedKey::new("Provider", &root, &account, &blinding, &secp).unwrap();
+
+        // Key at <blinded xpub>/0/3, spent from a P2WPKH output
+        let child_path = DerivationPath::from_str("m/0/3").unwrap();
+        let child: ExtendedPrivKey = root
+            .derive_priv(&secp, &blinded.path().extend(&child_path))
+            .unwrap();
+        let public_key = PublicKey::new(child.private_key.public_key(&secp));
+        let prev = Transaction {
+            version: 2,
+            lock_time: LockTime::ZERO,
+            input: vec![TxIn::default()],
+            output: vec![TxOut {
+                value: 20_000,
+                script_pubkey: ScriptBuf::new_v0_p2wpkh(&public_key.wpubkey_hash().unwrap()),
+            }],
+        };
+        let tx = Transaction {
+            version: 2,
+            lock_time: LockTime::ZERO,
+            input: vec![TxIn {
+                previous_output: OutPoint::new(prev.txid(), 0),
+                ..Default::default()
+            }],
+            output: vec![TxOut {
+                value: 19_000,
+                script_pubkey: Address::from_str("tb1qupyd58ndsh7lut0et0vtrq432jvu9jtdyws9n9")
+                    .unwrap()
+                    .assume_checked()
+                    .script_pubkey(),
+            }],
+        };
+        let mut unsigned = PartiallySignedTransaction::from_unsigned_tx(tx).unwrap();
+        unsigned.inputs[0].witness_utxo = Some(prev.output[0].clone());
+        unsigned.inputs[0].non_witness_utxo = Some(prev);
+
+        // Full blinded origin: [root/48'/1'/0'/2'/a/b/c/d]/0/3
+        let mut psbt = unsigned.clone();
+        psbt.inputs[0].bip32_derivation.insert(
+            public_key.inner,
+            (root.fingerprint(&secp), blinded.path().extend(&child_path)),
+        );
+        psbt.sign_with_seed(&seed, NETWORK, &secp).unwrap();
+        assert!(psbt.inputs[0].partial_sigs.contains_key(&public_key));
+
+        // Origin rooted at the blinded xpub: [blinded fingerprint]/0/3
+        let mut psbt = unsigned;
+        psbt.inputs[0]
+            .bip32_derivation
+            .insert(public_key.inner, (blinded.fingerprint(), child_path));
+        assert!(matches!(
+            psbt.clone().sign_with_seed(&seed, NETWORK, &secp),
+            Err(Error::NothingToSign)
+        ));
+        let signers = blinded_signers(&psbt, &seed, &[blinded], NETWORK, &secp).unwrap();
+        assert_eq!(signers.len(), 1);
+        psbt.sign_custom(&seed, None, signers, NETWORK, &secp).unwrap();
+        assert!(psbt.inputs[0].partial_sigs.contains_key(&public_key));
+
+        // Keys of another seed are ignored
+        let other = Seed::new::<&str>(
+            Mnemonic::from_str(
+                "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+            )
+            .unwrap(),
+            None,
+        );
+        let blinded = BlindedKey::new("Provider", &root, &account, &blinding, &secp).unwrap();
+        assert!(blinded_signers(&psbt, &other, &[blinded], NETWORK, &secp)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_verify_multisig() {
+        use std::collections::BTreeMap;
+
+        use bdk::bitcoin::absolute::LockTime;
+        use bdk::bitcoin::bip32::ExtendedPubKey;
+        use bdk::bitcoin::secp256k1::PublicKey;
+        use bdk::bitcoin::Transaction;
+        use bdk::miniscript::DescriptorPublicKey;
+
+        let secp = Secp256k1::new();
+        let cosigners: Vec<(&str, &str)> = vec![
+            ("0f056943", "tpubDF2rnouQaaYrXF4noGTv6rQYmx87cQ4GrUdhpvXkhtChwQPbdGTi8GA88NUaSrwZBwNsTkC9bFkkC8vDyGBVVAQTZ2AS6gs68RQXtXcCvkP"),
+            ("91ef223d", "tpubDE2zNGQxK1h4rn7kM66E61RzvxvSZE1soaGwbwMmdD1LANkgupjT4qpgNZ2UjwwoNLyq4Xjs3XMBHpLjynChgBfgp6hKSfoT6i1BXg1cEtL"),
+        ];
+        let keys: Vec<DescriptorPublicKey> = cosigners
+            .iter()
+            .map(|(fingerprint, xpub)| {
+                DescriptorPublicKey::from_str(&format!("[{fingerprint}/48'/1'/0'/2']{xpub}"))
+                    .unwrap()
+            })
+            .collect();
+        let wallet = MultisigWallet::new("Vault", ScriptType::P2WSH, 2, keys, NETWORK).unwrap();
+
+        let derivation = |change: u32, index: u32| {
+            let mut map: BTreeMap<PublicKey, KeySource> = BTreeMap::new();
+            let children = [
+                ChildNumber::from_normal_idx(change).unwrap(),
+                ChildNumber::from_normal_idx(index).unwrap(),
+            ];
+            for (fingerprint, xpub) in cosigners.iter() {
+                let xpub = ExtendedPubKey::from_str(xpub).unwrap();
+                let child = xpub.derive_pub(&secp, &children).unwrap();
+                let path = DerivationPath::from_str("m/48'/1'/0'/2'")
+                    .unwrap()
+                    .extend(children);
+                map.insert(
+                    child.public_key,
+                    (Fingerprint::from_str(fingerprint).unwrap(), path),
+                );
+            }
+            map
+        };
+
+        let external = Address::from_str("tb1qupyd58ndsh7lut0et0vtrq432jvu9jtdyws9n9")
+            .unwrap()
+            .assume_checked();
+        let tx = Transaction {
+            version: 2,
+            lock_time: LockTime::ZERO,
+            input: vec![TxIn::default()],
+            output: vec![
+                TxOut {
+                    value: 10_000,
+                    script_pubkey: external.script_pubkey(),
+                },
+                TxOut {
+                    value: 5_000,
+                    script_pubkey: wallet.address(true, 0, NETWORK).unwrap().script_pubkey(),
+                },
+            ],
+        };
+        let mut psbt = PartiallySignedTransaction::from_unsigned_tx(tx).unwrap();
+        psbt.inputs[0].witness_utxo = Some(TxOut {
+            value: 20_000,
+            script_pubkey: wallet.address(false, 0, NETWORK).unwrap().script_pubkey(),
+        });
+        psbt.inputs[0].bip32_derivation = derivation(0, 0);
+        psbt.outputs[1].bip32_derivation = derivation(1, 0);
+
+        let review = verify_multisig(&psbt, &[wallet.clone()]).unwrap().unwrap();
+        assert_eq!(review.wallet, wallet);
+        assert_eq!(review.change, vec![1]);
+
+        // Not registered
+        assert!(matches!(
+            verify_multisig(&psbt, &[]),
+            Err(Error::UnregisteredWallet)
+        ));
+
+        // Cosigner origins stripped by the coordinator: still a P2WSH input
+        let mut stripped = psbt.clone();
+        let ours: Fingerprint = Fingerprint::from_str(cosigners[0].0).unwrap();
+        stripped.inputs[0]
+            .bip32_derivation
+            .retain(|_, (fingerprint, _)| *fingerprint == ours);
+        stripped.outputs[1]
+            .bip32_derivation
+            .retain(|_, (fingerprint, _)| *fingerprint == ours);
+        assert_eq!(stripped.inputs[0].bip32_derivation.len(), 1);
+        assert!(matches!(
+            verify_multisig(&stripped, &[]),
+            Err(Error::UnregisteredWallet)
+        ));
+        let review = verify_multisig(&stripped, &[wallet.clone()])
+            .unwrap()
+            .unwrap();
+        assert!(review.is_change(1));
+        assert!(!review.is_change(0));
+
+        // Change that doesn't pay to the wallet
+        psbt.unsigned_tx.output[1].script_pubkey = external.script_pubkey();
+        assert!(matches!(
+            verify_multisig(&psbt, &[wallet]),
+            Err(Error::ChangeMismatch(1))
+        ));
+    }
 }
@@ -0,0 +1,222 @@
+// Copyright (c) 2022-2023 Yuki Kishimoto
+// Distributed under the MIT software license
+
+//! Address ownership search
+//!
+//! Find the account, chain and index of the keychain that produced an address.
+//! The script type is inferred from the address, so only the matching purposes are searched.
+
+use std::sync::Arc;
+
+use bdk::bitcoin::secp256k1::{Secp256k1, Signing};
+use bdk::bitcoin::{Address, AddressType, Network, ScriptBuf};
+use bdk::miniscript::descriptor::{Descriptor, DescriptorPublicKey};
+
+use super::Error;
+use crate::bips::bip32::{Bip32, DerivationPath, Fingerprint};
+use crate::bips::bip43::Purpose;
+use crate::bips::bip48::ScriptType;
+use crate::descriptors::{self, DerivedAddress, Descriptors};
+use crate::multisig::MultisigWallet;
+use crate::types::{Seed, SeedKind};
+
+/// Search bounds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bounds {
+    /// Number of accounts, starting from 0
+    pub accounts: u32,
+    /// Number of indexes of every chain (receive and change), starting from 0
+    pub indexes: u32,
+}
+
+impl Default for Bounds {
+    fn default() -> Self {
+        Self {
+            accounts: 10,
+            indexes: 1000,
+        }
+    }
+}
+
+/// Where the address comes from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AddressOrigin {
+    /// Multisig wallet name (`None` for single-sig accounts)
+    pub wallet: Option<String>,
+    pub purpose: Purpose,
+    pub change: bool,
+    pub index: u32,
+    /// Full derivation path (of our key, for multisig wallets)
+    pub path: DerivationPath,
+}
+
+/// Descriptor of a chain to scan
+struct Chain {
+    wallet: Option<String>,
+    purpose: Purpose,
+    change: bool,
+    descriptor: Descriptor<DescriptorPublicKey>,
+}
+
+/// Single-sig purpose and multisig script type that can produce the address type
+fn candidates(address_type: AddressType) -> (Option<Purpose>, Option<ScriptType>) {
+    match address_type {
+        AddressType::P2pkh => (Some(Purpose::BIP44), None),
+        AddressType::P2sh => (Some(Purpose::BIP49), Some(ScriptType::P2SHWSH)),
+        AddressType::P2wpkh => (Some(Purpose::BIP84), None),
+        AddressType::P2wsh => (None, Some(ScriptType::P2WSH)),
+        AddressType::P2tr => (Some(Purpose::BIP86), Some(ScriptType::P2TR)),
+    }
+}
+
+/// Search the single-sig accounts and the multisig `wallets` for `address`
+///
+/// Stops at the first match. `progress` gets the tested indexes and their total.
+pub fn search<P, C>(
+    seed: &Seed,
+    wallets: &[MultisigWallet],
+    address: &Address,
+    network: Network,
+    bounds: Bounds,
+    threads: Option<usize>,
+    progress: P,
+    secp: &Secp256k1<C>,
+) -> Result<Option<AddressOrigin>, Error>
+where
+    P: FnMut(u64, u64),
+    C: Signing,
+{
+    let address_type: AddressType = address.address_type().ok_or(Error::UnsupportedAddress)?;
+    let (purpose, script) = candidates(address_type);
+
+    // Derive the account xpubs once, the workers only derive the chain and index children
+    let mut chains: Vec<Chain> = Vec::new();
+    if let Some(purpose) = purpose {
+        // Electrum seeds have a single account
+        let accounts: u32 = match seed.kind() {
+            SeedKind::Electrum(..) => bounds.accounts.min(1),
+            _ => bounds.accounts,
+        };
+        for account in 0..accounts {
+            let descriptors = Descriptors::new(seed, network, Some(account), secp)?;
+            for change in [false, true] {
+                if let Ok(descriptor) = descriptors.get_by_purpose(purpose, change) {
+                    chains.push(Chain {
+                        wallet: None,
+                        purpose,
+                        change,
+                        descriptor,
+                    });
+                }
+            }
+        }
+    }
+    if let Some(script) = script {
+        for wallet in wallets.iter().filter(|wallet| wallet.script() == script) {
+            for change in [false, true] {
+                chains.push(Chain {
+                    wallet: Some(wallet.name()),
+                    purpose: Purpose::BIP48 { script },
+                    change,
+                    descriptor: wallet.descriptor(change)?,
+                });
+            }
+        }
+    }
+
+    let fingerprint: Fingerprint = seed.fingerprint(network, secp)?;
+    let target: ScriptBuf = address.script_pubkey();
+    let indexes: u64 = u64::from(bounds.indexes);
+    let total: u64 = chains.len() as u64 * indexes;
+    let chains: Arc<Vec<Chain>> = Arc::new(chains);
+    super::search(
+        total,
+        threads,
+        move |candidate| {
+            let chain: &Chain = &chains[(candidate / indexes) as usize];
+            let index: u32 = (candidate % indexes) as u32;
+            let derived = chain
+                .descriptor
+                .at_derivation_index(index)
+                .map_err(descriptors::Error::from)?;
+            if derived.script_pubkey() != target {
+                return Ok(None);
+            }
+
+            let fingerprint: Option<Fingerprint> = chain.wallet.as_ref().map(|_| fingerprint);
+            let derived: Vec<DerivedAddress> =
+                descriptors::derive_addresses(&chain.descriptor, fingerprint, index, 1, network)?;
+            Ok(derived.into_iter().next().map(|derived| AddressOrigin {
+                wallet: chain.wallet.clone(),
+                purpose: chain.purpose,
+                change: chain.change,
+                index,
+                path: derived.path,
+            }))
+        },
+        progress,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use core::str::FromStr;
+
+    use super::*;
+    use crate::bips::bip39::Mnemonic;
+
+    const MNEMONIC: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+    fn find(seed: &Seed, address: &str, bounds: Bounds) -> Option<AddressOrigin> {
+        let secp = Secp256k1::new();
+        let address = Address::from_str(address)
+            .unwrap()
+            .require_network(Network::Bitcoin)
+            .unwrap();
+        search(
+            seed,
+            &[],
+            &address,
+            Network::Bitcoin,
+            bounds,
+            Some(2),
+            |_, _| (),
+            &secp,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_search() {
+        let seed = Seed::new(Mnemonic::from_str(MNEMONIC).unwrap(), None::<String>);
+        let bounds = Bounds {
+            accounts: 3,
+            indexes: 10,
+        };
+
+        let found = find(&seed, "bc1qk25senelucp2c8ejm8lyx6l3xfrmfltx0z9gp7", bounds).unwrap();
+        assert_eq!(found.wallet, None);
+        assert_eq!(found.purpose, Purpose::BIP84);
+        assert!(found.change);
+        assert_eq!(found.index, 7);
+        assert_eq!(found.path.to_string(), "m/84'/0'/2'/1/7");
+
+        let found = find(&seed, "1MVGa13XFvvpKGZdX389iU8b3qwtmAyrsJ", bounds).unwrap();
+        assert_eq!(found.purpose, Purpose::BIP44);
+        assert_eq!(found.path.to_string(), "m/44'/0'/0'/0/3");
+
+        // Out of bounds
+        let address: &str = "bc1pc2aen5mtpddrpe6qcl3zdjgnwzgffac3ktc49pfyrd66nllmteeqg3p4he";
+        assert_eq!(find(&seed, address, bounds), None);
+        let found = find(
+            &seed,
+            address,
+            Bounds {
+                accounts: 1,
+                indexes: 31,
+            },
+        )
+        .unwrap();
+        assert_eq!(found.path.to_string(), "m/86'/0'/0'/0/30");
+    }
+}
@@ -0,0 +1,289 @@
+// Copyright (c) 2022-2023 Yuki Kishimoto
+// Distributed under the MIT software license
+
+//! Partial mnemonic recovery
+//!
+//! Template syntax (English wordlist), one token per word:
+//! * `?`: unknown word
+//! * `word1|word2|...`: candidate alternatives
+//! * a word not in the wordlist is replaced by the similar ones (misspelled)
+//!
+//! With one word less than a valid mnemonic, the missing word is searched at every position.
+
+use core::str::FromStr;
+use std::sync::Arc;
+
+use bdk::bitcoin::hashes::{sha256, Hash};
+use bdk::bitcoin::secp256k1::{All, Secp256k1};
+use bdk::bitcoin::Network;
+
+use super::{Error, Target};
+use crate::bips::bip39::{Language, Mnemonic};
+use crate::types::Seed;
+
+const WORDLIST_SIZE: u16 = 2048;
+const VALID_WORD_COUNTS: [usize; 5] = [12, 15, 18, 21, 24];
+/// Max edit distance of the candidates for a misspelled word
+const MAX_EDIT_DISTANCE: usize = 2;
+
+/// Mnemonic with unknown or uncertain words
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MnemonicTemplate {
+    /// Word indexes candidates, for every position
+    variants: Vec<Vec<Vec<u16>>>,
+}
+
+impl FromStr for MnemonicTemplate {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut positions: Vec<Vec<u16>> = Vec::new();
+        for token in s.split_whitespace() {
+            positions.push(parse_token(&token.to_lowercase())?);
+        }
+
+        let count: usize = positions.len();
+        if VALID_WORD_COUNTS.contains(&count) {
+            Ok(Self {
+                variants: vec![positions],
+            })
+        } else if VALID_WORD_COUNTS.contains(&(count + 1)) {
+            let variants: Vec<Vec<Vec<u16>>> = (0..=count)
+                .map(|index| {
+                    let mut variant: Vec<Vec<u16>> = positions.clone();
+                    variant.insert(index, (0..WORDLIST_SIZE).collect());
+                    variant
+                })
+                .collect();
+            Ok(Self { variants })
+        } else {
+            Err(Error::InvalidWordCount(count))
+        }
+    }
+}
+
+impl MnemonicTemplate {
+    /// Number of combinations, before the checksum filtering
+    pub fn combinations(&self) -> u128 {
+        self.variants
+            .iter()
+            .map(|variant| variant.iter().map(|p| p.len() as u128).product::<u128>())
+            .sum()
+    }
+
+    /// Get the combination at `index`: `None` if its checksum is invalid
+    fn candidate(&self, mut index: u64) -> Option<Mnemonic> {
+        for variant in self.variants.iter() {
+            let size: u64 = variant.iter().map(|p| p.len() as u64).product();
+            if index >= size {
+                index -= size;
+                continue;
+            }
+
+            let mut words: Vec<u16> = Vec::with_capacity(variant.len());
+            for position in variant.iter().rev() {
+                let len: u64 = position.len() as u64;
+                words.push(position[(index % len) as usize]);
+                index /= len;
+            }
+            words.reverse();
+            return from_word_indexes(&words);
+        }
+        None
+    }
+
+    /// Iterate over the checksum-valid candidates
+    pub fn candidates(&self) -> impl Iterator<Item = Mnemonic> + '_ {
+        let total: u64 = u64::try_from(self.combinations()).unwrap_or(u64::MAX);
+        (0..total).filter_map(move |index| self.candidate(index))
+    }
+}
+
+fn parse_token(token: &str) -> Result<Vec<u16>, Error> {
+    let language = Language::English;
+
+    if token == "?" {
+        return Ok((0..WORDLIST_SIZE).collect());
+    }
+
+    if token.contains('|') {
+        let mut candidates: Vec<u16> = Vec::new();
+        for word in token.split('|').filter(|w| !w.is_empty()) {
+            let index: u16 = language
+                .find_word(word)
+                .ok_or_else(|| Error::UnknownWord(word.to_string()))?;
+            if !candidates.contains(&index) {
+                candidates.push(index);
+            }
+        }
+        return Ok(candidates);
+    }
+
+    match language.find_word(token) {
+        Some(index) => Ok(vec![index]),
+        None => {
+            let candidates: Vec<u16> = language
+                .word_list()
+                .iter()
+                .enumerate()
+                .filter(|(_, word)| edit_distance(token, word) <= MAX_EDIT_DISTANCE)
+                .map(|(index, _)| index as u16)
+                .collect();
+            if candidates.is_empty() {
+                Err(Error::UnknownWord(token.to_string()))
+            } else {
+                Ok(candidates)
+            }
+        }
+    }
+}
+
+/// Levenshtein distance
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current: Vec<usize> = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution: usize = previous[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// Build the mnemonic if the checksum is valid
+fn from_word_indexes(words: &[u16]) -> Option<Mnemonic> {
+    let bits: usize = words.len() * 11;
+    let checksum_bits: usize = bits / 33;
+    let entropy_bits: usize = bits - checksum_bits;
+
+    let mut bytes: Vec<u8> = vec![0u8; (bits + 7) / 8];
+    for (i, word) in words.iter().enumerate() {
+        for bit in 0..11 {
+            if word & (1 << (10 - bit)) != 0 {
+                let position: usize = i * 11 + bit;
+                bytes[position / 8] |= 1 << (7 - position % 8);
+            }
+        }
+    }
+
+    let entropy: &[u8] = &bytes[..entropy_bits / 8];
+    let hash: [u8; 32] = sha256::Hash::hash(entropy).to_byte_array();
+    let expected: u8 = hash[0] >> (8 - checksum_bits);
+    let checksum: u8 = bytes[entropy_bits / 8] >> (8 - checksum_bits);
+    if expected == checksum {
+        Mnemonic::from_entropy(entropy).ok()
+    } else {
+        None
+    }
+}
+
+/// Search the candidate that matches `target`
+///
+/// Stops at the first match. `progress` gets the tested combinations and their total.
+pub fn recover<P>(
+    template: MnemonicTemplate,
+    passphrase: Option<String>,
+    target: Target,
+    network: Network,
+    threads: Option<usize>,
+    progress: P,
+) -> Result<Option<Mnemonic>, Error>
+where
+    P: FnMut(u64, u64),
+{
+    let total: u64 =
+        u64::try_from(template.combinations()).map_err(|_| Error::TooManyCombinations)?;
+    let secp: Arc<Secp256k1<All>> = Arc::new(Secp256k1::new());
+    super::search(
+        total,
+        threads,
+        move |index| match template.candidate(index) {
+            Some(mnemonic) => {
+                let seed = Seed::new(mnemonic.clone(), passphrase.clone());
+                if target.matches(&seed, network, &secp)? {
+                    Ok(Some(mnemonic))
+                } else {
+                    Ok(None)
+                }
+            }
+            None => Ok(None),
+        },
+        progress,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use bdk::bitcoin::bip32::Fingerprint;
+    use bdk::bitcoin::Address;
+
+    use super::*;
+    use crate::bips::bip43::Purpose;
+
+    const MNEMONIC: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+    #[test]
+    fn test_template() {
+        let template = MnemonicTemplate::from_str(MNEMONIC).unwrap();
+        assert_eq!(template.combinations(), 1);
+        assert_eq!(
+            template.candidates().collect::<Vec<Mnemonic>>(),
+            vec![Mnemonic::from_str(MNEMONIC).unwrap()]
+        );
+
+        let template = MnemonicTemplate::from_str("abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon ?").unwrap();
+        assert_eq!(template.combinations(), 2048);
+        // 4 bits checksum
+        assert_eq!(template.candidates().count(), 128);
+
+        // Missing word at unknown position
+        let template = MnemonicTemplate::from_str("abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon").unwrap();
+        assert_eq!(template.combinations(), 12 * 2048);
+
+        // Misspelled word
+        let template = MnemonicTemplate::from_str("abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abuot").unwrap();
+        assert!(template
+            .candidates()
+            .any(|m| m == Mnemonic::from_str(MNEMONIC).unwrap()));
+
+        assert!(matches!(
+            MnemonicTemplate::from_str("abandon|xyzxyz abandon"),
+            Err(Error::UnknownWord(_))
+        ));
+        assert!(matches!(
+            MnemonicTemplate::from_str("abandon abandon"),
+            Err(Error::InvalidWordCount(2))
+        ));
+    }
+
+    #[test]
+    fn test_recover() {
+        let template = MnemonicTemplate::from_str("abandon abandon abandon abandon|ability|able abandon abandon abandon abandon abandon abandon abandon about|above|absent").unwrap();
+        let target = Target::Fingerprint(Fingerprint::from_str("73c5da0a").unwrap());
+        let mnemonic = recover(
+            template.clone(),
+            None,
+            target,
+            Network::Bitcoin,
+            None,
+            |_, _| (),
+        )
+        .unwrap();
+        assert_eq!(mnemonic, Some(Mnemonic::from_str(MNEMONIC).unwrap()));
+
+        let address = Address::from_str("bc1qcr8te4kr609gcawutmrza0j4xv80jy8z306fyu")
+            .unwrap()
+            .assume_checked();
+        let target = Target::Address {
+            address,
+            purpose: Purpose::BIP84,
+            gap: 20,
+        };
+        let mnemonic =
+            recover(template, None, target, Network::Bitcoin, Some(2), |_, _| ()).unwrap();
+        assert_eq!(mnemonic, Some(Mnemonic::from_str(MNEMONIC).unwrap()));
+    }
+}
@@ -0,0 +1,336 @@
+// Copyright (c) 2022-2023 Yuki Kishimoto
+// Distributed under the MIT software license
+
+//! Recovery tools
+
+use core::fmt;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use bdk::bitcoin::secp256k1::{Secp256k1, Signing, Verification};
+use bdk::bitcoin::{Address, Network, ScriptBuf};
+
+use crate::bips::bip32::{self, Bip32, ChildNumber, ExtendedPrivKey, ExtendedPubKey, Fingerprint};
+use crate::bips::bip39;
+use crate::bips::bip43::Purpose;
+use crate::types::Seed;
+use crate::{descriptors, multisig};
+
+pub mod address;
+pub mod mnemonic;
+pub mod passphrase;
+
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Purposes of the account xpubs checked by [`Target::Xpub`]
+const XPUB_PURPOSES: [Purpose; 4] = [
+    Purpose::BIP44,
+    Purpose::BIP49,
+    Purpose::BIP84,
+    Purpose::BIP86,
+];
+
+#[derive(Debug)]
+pub enum Error {
+    BIP32(bip32::Error),
+    BIP39(bip39::Error),
+    Descriptors(descriptors::Error),
+    Multisig(multisig::Error),
+    /// Mnemonic with an invalid number of words
+    InvalidWordCount(usize),
+    /// Word not in the wordlist and without similar ones
+    UnknownWord(String),
+    /// Invalid passphrase mask
+    InvalidMask(String),
+    /// Search space too big
+    TooManyCombinations,
+    /// Address target with a multisig purpose
+    UnsupportedPurpose,
+    /// Address without a known script type
+    UnsupportedAddress,
+    /// A worker thread panicked
+    WorkerPanicked,
+}
+
+impl std::error::Error for Error {}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::BIP32(e) => write!(f, "BIP32: {e}"),
+            Self::BIP39(e) => write!(f, "BIP39: {e}"),
+            Self::Descriptors(e) => write!(f, "Descriptors: {e}"),
+            Self::Multisig(e) => write!(f, "Multisig: {e}"),
+            Self::InvalidWordCount(count) => write!(f, "Invalid word count: {count}"),
+            Self::UnknownWord(word) => write!(f, "Unknown word: {word}"),
+            Self::InvalidMask(reason) => write!(f, "Invalid mask: {reason}"),
+            Self::TooManyCombinations => write!(f, "Too many combinations"),
+            Self::UnsupportedPurpose => write!(f, "Unsupported purpose"),
+            Self::UnsupportedAddress => write!(f, "Unsupported address type"),
+            Self::WorkerPanicked => write!(f, "Worker thread panicked"),
+        }
+    }
+}
+
+impl From<bip32::Error> for Error {
+    fn from(e: bip32::Error) -> Self {
+        Self::BIP32(e)
+    }
+}
+
+impl From<bip39::Error> for Error {
+    fn from(e: bip39::Error) -> Self {
+        Self::BIP39(e)
+    }
+}
+
+impl From<descriptors::Error> for Error {
+    fn from(e: descriptors::Error) -> Self {
+        Self::Descriptors(e)
+    }
+}
+
+impl From<multisig::Error> for Error {
+    fn from(e: multisig::Error) -> Self {
+        Self::Multisig(e)
+    }
+}
+
+/// What the recovered seed must produce
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Target {
+    /// Root fingerprint
+    Fingerprint(Fingerprint),
+    /// Root xpub or first-level account xpub (BIP44, BIP49, BIP84 or BIP86)
+    Xpub(ExtendedPubKey),
+    /// One of the first `gap` receive addresses of the first account
+    Address {
+        address: Address,
+        purpose: Purpose,
+        gap: u32,
+    },
+}
+
+impl Target {
+    pub fn matches<C>(
+        &self,
+        seed: &Seed,
+        network: Network,
+        secp: &Secp256k1<C>,
+    ) -> Result<bool, Error>
+    where
+        C: Signing + Verification,
+    {
+        match self {
+            Self::Fingerprint(fingerprint) => Ok(seed.fingerprint(network, secp)? == *fingerprint),
+            Self::Xpub(xpub) => {
+                let root: ExtendedPrivKey = seed.to_bip32_root_key(xpub.network)?;
+                if xpub.depth == 0 {
+                    return Ok(ExtendedPubKey::from_priv(secp, &root) == *xpub);
+                }
+
+                if let (3, ChildNumber::Hardened { index: account }) =
+                    (xpub.depth, xpub.child_number)
+                {
+                    for purpose in XPUB_PURPOSES.iter() {
+                        let path = purpose.to_account_extended_path(xpub.network, Some(account))?;
+                        let derived: ExtendedPrivKey = root.derive_priv(secp, &path)?;
+                        if derived.private_key.public_key(secp) == xpub.public_key {
+                            return Ok(true);
+                        }
+                    }
+                }
+
+                Ok(false)
+            }
+            Self::Address {
+                address,
+                purpose,
+                gap,
+            } => {
+                let target: ScriptBuf = address.script_pubkey();
+                let root: ExtendedPrivKey = seed.to_bip32_root_key(network)?;
+                let path = purpose.to_account_extended_path(network, None)?;
+                let account: ExtendedPrivKey = root.derive_priv(secp, &path)?;
+                let external: ExtendedPubKey = ExtendedPubKey::from_priv(
+                    secp,
+                    &account.ckd_priv(secp, ChildNumber::Normal { index: 0 })?,
+                );
+                for index in 0..*gap {
+                    let child: ExtendedPubKey =
+                        external.ckd_pub(secp, ChildNumber::Normal { index })?;
+                    if script_pubkey(*purpose, &child, secp)? == target {
+                        return Ok(true);
+                    }
+                }
+                Ok(false)
+            }
+        }
+    }
+}
+
+fn script_pubkey<C>(
+    purpose: Purpose,
+    key: &ExtendedPubKey,
+    secp: &Secp256k1<C>,
+) -> Result<ScriptBuf, Error>
+where
+    C: Verification,
+{
+    let public_key = key.to_pub();
+    match purpose {
+        Purpose::BIP44 => Ok(ScriptBuf::new_p2pkh(&public_key.pubkey_hash())),
+        Purpose::BIP49 => {
+            let wpkh = public_key.wpubkey_hash().ok_or(Error::UnsupportedPurpose)?;
+            Ok(ScriptBuf::new_p2sh(
+                &ScriptBuf::new_v0_p2wpkh(&wpkh).script_hash(),
+            ))
+        }
+        Purpose::BIP84 => {
+            let wpkh = public_key.wpubkey_hash().ok_or(Error::UnsupportedPurpose)?;
+            Ok(ScriptBuf::new_v0_p2wpkh(&wpkh))
+        }
+        Purpose::BIP86 => Ok(ScriptBuf::new_v1_p2tr(secp, key.to_x_only_pub(), None)),
+        Purpose::BIP45 | Purpose::BIP48 { .. } | Purpose::Custom(..) => {
+            Err(Error::UnsupportedPurpose)
+        }
+    }
+}
+
+/// Test the candidates `0..total` in parallel, stopping at the first match
+///
+/// `progress` is called from the current thread with the number of tested candidates and the total.
+pub(crate) fn search<T, F, P>(
+    total: u64,
+    threads: Option<usize>,
+    test: F,
+    mut progress: P,
+) -> Result<Option<T>, Error>
+where
+    T: Send + 'static,
+    F: Fn(u64) -> Result<Option<T>, Error> + Send + Sync + 'static,
+    P: FnMut(u64, u64),
+{
+    let threads: u64 = match threads {
+        Some(threads) => threads.max(1) as u64,
+        None => thread::available_parallelism()
+            .map(|n| n.get() as u64)
+            .unwrap_or(1),
+    };
+
+    let test = Arc::new(test);
+    let tested = Arc::new(AtomicU64::new(0));
+    let stop = Arc::new(AtomicBool::new(false));
+    let (sender, receiver) = mpsc::channel::<Result<T, Error>>();
+
+    let mut handles: Vec<JoinHandle<()>> = Vec::new();
+    for offset in 0..threads.min(total.max(1)) {
+        let test = test.clone();
+        let tested = tested.clone();
+        let stop = stop.clone();
+        let sender = sender.clone();
+        handles.push(thread::spawn(move || {
+            let mut index: u64 = offset;
+            while index < total && !stop.load(Ordering::Relaxed) {
+                match test(index) {
+                    Ok(Some(found)) => {
+                        stop.store(true, Ordering::Relaxed);
+                        let _ = sender.send(Ok(found));
+                    }
+                    Ok(None) => (),
+                    Err(e) => {
+                        stop.store(true, Ordering::Relaxed);
+                        let _ = sender.send(Err(e));
+                    }
+                }
+                tested.fetch_add(1, Ordering::Relaxed);
+                index += threads;
+            }
+        }));
+    }
+    drop(sender);
+
+    let result: Option<Result<T, Error>> = loop {
+        match receiver.recv_timeout(PROGRESS_INTERVAL) {
+            Ok(result) => break Some(result),
+            Err(RecvTimeoutError::Timeout) => progress(tested.load(Ordering::Relaxed), total),
+            Err(RecvTimeoutError::Disconnected) => break None,
+        }
+    };
+
+    stop.store(true, Ordering::Relaxed);
+    let mut panicked: bool = false;
+    for handle in handles.into_iter() {
+        panicked |= handle.join().is_err();
+    }
+    progress(tested.load(Ordering::Relaxed), total);
+
+    match result {
+        Some(result) => result.map(Some),
+        None if panicked => Err(Error::WorkerPanicked),
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::str::FromStr;
+
+    use super::*;
+    use crate::bips::bip39::Mnemonic;
+
+    #[test]
+    fn test_target() {
+        let secp = Secp256k1::new();
+        let mnemonic = Mnemonic::from_str("abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about").unwrap();
+        let seed = Seed::new(mnemonic, None::<String>);
+        let other = Seed::new(
+            Mnemonic::from_str(
+                "legal winner thank year wave sausage worth useful legal winner thank yellow",
+            )
+            .unwrap(),
+            None::<String>,
+        );
+
+        let root: ExtendedPrivKey = seed.to_bip32_root_key(Network::Bitcoin).unwrap();
+        let path = Purpose::BIP84
+            .to_account_extended_path(Network::Bitcoin, Some(1))
+            .unwrap();
+        let account = ExtendedPubKey::from_priv(&secp, &root.derive_priv(&secp, &path).unwrap());
+
+        for target in [
+            Target::Fingerprint(Fingerprint::from_str("73c5da0a").unwrap()),
+            Target::Xpub(ExtendedPubKey::from_priv(&secp, &root)),
+            Target::Xpub(account),
+        ] {
+            assert!(target.matches(&seed, Network::Bitcoin, &secp).unwrap());
+            assert!(!target.matches(&other, Network::Bitcoin, &secp).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_search() {
+        let found = search(
+            10_000,
+            Some(4),
+            |i| Ok(if i == 7_777 { Some(i) } else { None }),
+            |_, _| (),
+        )
+        .unwrap();
+        assert_eq!(found, Some(7_777));
+
+        let mut last: (u64, u64) = (0, 0);
+        let found: Option<u64> = search(
+            1_000,
+            None,
+            |_| Ok(None),
+            |tested, total| last = (tested, total),
+        )
+        .unwrap();
+        assert_eq!(found, None);
+        assert_eq!(last, (1_000, 1_000));
+    }
+}
@@ -0,0 +1,339 @@
+// Copyright (c) 2022-2023 Yuki Kishimoto
+// Distributed under the MIT software license
+
+//! BIP39 passphrase recovery
+//!
+//! Mask syntax (like hashcat):
+//! * `?l`: lowercase letter
+//! * `?u`: uppercase letter
+//! * `?d`: digit
+//! * `?s`: symbol (or space)
+//! * `?a`: any of the above
+//! * `??`: literal `?`
+//!
+//! Every other character is literal.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use bdk::bitcoin::secp256k1::{All, Secp256k1};
+use bdk::bitcoin::Network;
+
+use super::{Error, Target};
+use crate::types::{Seed, SeedKind};
+
+const LOWERCASE: &str = "abcdefghijklmnopqrstuvwxyz";
+const UPPERCASE: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+const DIGITS: &str = "0123456789";
+const SYMBOLS: &str = " !\"#$%&'()*+,-./:;<=>?@[\\]^_`{|}~";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Source {
+    Wordlist(Vec<String>),
+    /// Charset for every position
+    Mask(Vec<Vec<char>>),
+}
+
+/// Passphrase candidates generator
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PassphraseGenerator {
+    source: Source,
+    case: bool,
+    typos: bool,
+}
+
+impl PassphraseGenerator {
+    /// Candidates from a wordlist
+    pub fn wordlist<I, S>(words: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            source: Source::Wordlist(words.into_iter().map(|w| w.into()).collect()),
+            case: false,
+            typos: false,
+        }
+    }
+
+    /// Candidates from a mask
+    pub fn mask<S>(mask: S) -> Result<Self, Error>
+    where
+        S: AsRef<str>,
+    {
+        let mut positions: Vec<Vec<char>> = Vec::new();
+        let mut chars = mask.as_ref().chars();
+        while let Some(c) = chars.next() {
+            if c == '?' {
+                let charset: String = match chars.next() {
+                    Some('l') => LOWERCASE.to_string(),
+                    Some('u') => UPPERCASE.to_string(),
+                    Some('d') => DIGITS.to_string(),
+                    Some('s') => SYMBOLS.to_string(),
+                    Some('a') => format!("{LOWERCASE}{UPPERCASE}{DIGITS}{SYMBOLS}"),
+                    Some('?') => String::from("?"),
+                    Some(c) => return Err(Error::InvalidMask(format!("unknown charset ?{c}"))),
+                    None => return Err(Error::InvalidMask(String::from("trailing ?"))),
+                };
+                positions.push(charset.chars().collect());
+            } else {
+                positions.push(vec![c]);
+            }
+        }
+
+        if positions.is_empty() {
+            return Err(Error::InvalidMask(String::from("empty")));
+        }
+
+        Ok(Self {
+            source: Source::Mask(positions),
+            case: false,
+            typos: false,
+        })
+    }
+
+    /// Also try the case mutations (lowercase, uppercase, capitalized, single letters toggled, ...)
+    pub fn case_mutations(self, enable: bool) -> Self {
+        Self {
+            case: enable,
+            ..self
+        }
+    }
+
+    /// Also try the typos (one character deleted, swapped, replaced or added)
+    pub fn typo_mutations(self, enable: bool) -> Self {
+        Self {
+            typos: enable,
+            ..self
+        }
+    }
+
+    /// Number of base candidates (before the mutations)
+    pub fn len(&self) -> u128 {
+        match &self.source {
+            Source::Wordlist(words) => words.len() as u128,
+            Source::Mask(positions) => positions.iter().map(|p| p.len() as u128).product(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn base(&self, mut index: u64) -> String {
+        match &self.source {
+            Source::Wordlist(words) => words[index as usize].clone(),
+            Source::Mask(positions) => {
+                let mut chars: Vec<char> = Vec::with_capacity(positions.len());
+                for charset in positions.iter().rev() {
+                    let len: u64 = charset.len() as u64;
+                    chars.push(charset[(index % len) as usize]);
+                    index /= len;
+                }
+                chars.into_iter().rev().collect()
+            }
+        }
+    }
+
+    /// Base candidate at `index` followed by its mutations
+    pub fn candidates(&self, index: u64) -> Vec<String> {
+        let base: String = self.base(index);
+        let mut candidates: Vec<String> = vec![base.clone()];
+        if self.case {
+            candidates.extend(case_variants(&base));
+        }
+        if self.typos {
+            candidates.extend(typo_variants(&base));
+        }
+
+        let mut seen: HashSet<String> = HashSet::new();
+        candidates.retain(|c| seen.insert(c.clone()));
+        candidates
+    }
+}
+
+fn case_variants(base: &str) -> Vec<String> {
+    let chars: Vec<char> = base.chars().collect();
+    let toggle = |c: char| -> String {
+        if c.is_uppercase() {
+            c.to_lowercase().collect()
+        } else {
+            c.to_uppercase().collect()
+        }
+    };
+
+    let mut variants: Vec<String> = vec![
+        base.to_lowercase(),
+        base.to_uppercase(),
+        chars.iter().map(|c| toggle(*c)).collect(),
+    ];
+
+    // Capitalized
+    let lowercase: String = base.to_lowercase();
+    let mut rest = lowercase.chars();
+    if let Some(first) = rest.next() {
+        variants.push(first.to_uppercase().chain(rest).collect());
+    }
+
+    // Single character toggled
+    for index in 0..chars.len() {
+        let mut variant: String = String::with_capacity(base.len());
+        for (i, c) in chars.iter().enumerate() {
+            if i == index {
+                variant.push_str(&toggle(*c));
+            } else {
+                variant.push(*c);
+            }
+        }
+        variants.push(variant);
+    }
+
+    variants
+}
+
+/// Candidates at edit distance 1 (printable ASCII only)
+fn typo_variants(base: &str) -> Vec<String> {
+    let chars: Vec<char> = base.chars().collect();
+    let printable: Vec<char> = (0x20u8..0x7f).map(char::from).collect();
+    let mut variants: Vec<String> = Vec::new();
+
+    // Deletions
+    for index in 0..chars.len() {
+        let mut variant: Vec<char> = chars.clone();
+        variant.remove(index);
+        variants.push(variant.into_iter().collect());
+    }
+
+    // Adjacent transpositions
+    for index in 1..chars.len() {
+        let mut variant: Vec<char> = chars.clone();
+        variant.swap(index - 1, index);
+        variants.push(variant.into_iter().collect());
+    }
+
+    // Substitutions
+    for index in 0..chars.len() {
+        for c in printable.iter() {
+            let mut variant: Vec<char> = chars.clone();
+            variant[index] = *c;
+            variants.push(variant.into_iter().collect());
+        }
+    }
+
+    // Insertions (trailing character included)
+    for index in 0..=chars.len() {
+        for c in printable.iter() {
+            let mut variant: Vec<char> = chars.clone();
+            variant.insert(index, *c);
+            variants.push(variant.into_iter().collect());
+        }
+    }
+
+    variants
+}
+
+/// Search the passphrase that, with the seed `kind`, matches `target`
+///
+/// Stops at the first match. `progress` gets the tested base candidates and their total.
+pub fn recover<P>(
+    kind: SeedKind,
+    generator: PassphraseGenerator,
+    target: Target,
+    network: Network,
+    threads: Option<usize>,
+    progress: P,
+) -> Result<Option<String>, Error>
+where
+    P: FnMut(u64, u64),
+{
+    let total: u64 = u64::try_from(generator.len()).map_err(|_| Error::TooManyCombinations)?;
+    let secp: Arc<Secp256k1<All>> = Arc::new(Secp256k1::new());
+    super::search(
+        total,
+        threads,
+        move |index| {
+            for candidate in generator.candidates(index).into_iter() {
+                let seed = Seed::with_kind(kind.clone(), Some(candidate.clone()));
+                if target.matches(&seed, network, &secp)? {
+                    return Ok(Some(candidate));
+                }
+            }
+            Ok(None)
+        },
+        progress,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use core::str::FromStr;
+
+    use super::*;
+    use crate::bips::bip32::Bip32;
+    use crate::bips::bip39::Mnemonic;
+
+    const MNEMONIC: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+    #[test]
+    fn test_mask() {
+        let generator = PassphraseGenerator::mask("ab?d??").unwrap();
+        assert_eq!(generator.len(), 10);
+        assert_eq!(generator.candidates(0), vec![String::from("ab0?")]);
+        assert_eq!(generator.candidates(9), vec![String::from("ab9?")]);
+
+        assert_eq!(
+            PassphraseGenerator::mask("?l?u?d?s?a").unwrap().len(),
+            26 * 26 * 10 * 33 * 95
+        );
+        assert!(PassphraseGenerator::mask("abc?x").is_err());
+        assert!(PassphraseGenerator::mask("abc?").is_err());
+    }
+
+    #[test]
+    fn test_mutations() {
+        let generator = PassphraseGenerator::wordlist(["Satoshi"])
+            .case_mutations(true)
+            .typo_mutations(true);
+        let candidates: Vec<String> = generator.candidates(0);
+        assert_eq!(candidates[0], "Satoshi");
+        for expected in [
+            "satoshi", "SATOSHI", "sATOSHI", "SatOshi", "Satosi", "Staoshi", "Satoshi!",
+        ] {
+            assert!(candidates.contains(&expected.to_string()), "{expected}");
+        }
+    }
+
+    #[test]
+    fn test_recover() {
+        let secp = Secp256k1::new();
+        let kind = SeedKind::BIP39(Mnemonic::from_str(MNEMONIC).unwrap());
+        let seed = Seed::with_kind(kind.clone(), Some("TREZOR!"));
+        let target = Target::Fingerprint(seed.fingerprint(Network::Bitcoin, &secp).unwrap());
+
+        let generator = PassphraseGenerator::wordlist(["hello", "trezor", "world"])
+            .case_mutations(true)
+            .typo_mutations(false);
+        let found = recover(
+            kind.clone(),
+            generator,
+            target.clone(),
+            Network::Bitcoin,
+            None,
+            |_, _| (),
+        )
+        .unwrap();
+        assert_eq!(found, None);
+
+        let generator = PassphraseGenerator::mask("TREZOR?s").unwrap();
+        let found = recover(
+            kind,
+            generator,
+            target,
+            Network::Bitcoin,
+            Some(2),
+            |_, _| (),
+        )
+        .unwrap();
+        assert_eq!(found, Some(String::from("TREZOR!")));
+    }
+}
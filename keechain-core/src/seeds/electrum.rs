@@ -0,0 +1,313 @@
+// Copyright (c) 2022-2023 Yuki Kishimoto
+// Distributed under the MIT software license
+
+//! Electrum seed
+//!
+//! <https://electrum.readthedocs.io/en/latest/seedphrase.html>
+
+use core::fmt;
+use std::str::FromStr;
+
+use bdk::bitcoin::hashes::hmac::{Hmac, HmacEngine};
+use bdk::bitcoin::hashes::{sha512, Hash, HashEngine};
+use serde::de::Deserializer;
+use serde::{Deserialize, Serialize};
+use unicode_normalization::char::canonical_combining_class;
+use unicode_normalization::UnicodeNormalization;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+use crate::bips::bip32::{ChildNumber, DerivationPath};
+use crate::bips::bip43::Purpose;
+use crate::crypto::hash;
+use crate::util::hex;
+
+const SEED_VERSION_HMAC_KEY: &[u8] = b"Seed version";
+const SEED_SALT_PREFIX: &str = "electrum";
+const PBKDF2_ROUNDS: u32 = 2048;
+
+/// Unicode blocks where Electrum removes the whitespaces between characters
+const CJK_INTERVALS: [(u32, u32); 29] = [
+    (0x4E00, 0x9FFF),
+    (0x3400, 0x4DBF),
+    (0x20000, 0x2A6DF),
+    (0x2A700, 0x2B73F),
+    (0x2B740, 0x2B81F),
+    (0xF900, 0xFAFF),
+    (0x2F800, 0x2FA1D),
+    (0x3190, 0x319F),
+    (0x2E80, 0x2EFF),
+    (0x2F00, 0x2FDF),
+    (0x31C0, 0x31EF),
+    (0x2FF0, 0x2FFF),
+    (0xE0100, 0xE01EF),
+    (0x3100, 0x312F),
+    (0x31A0, 0x31BF),
+    (0xFF00, 0xFFEF),
+    (0x3040, 0x309F),
+    (0x30A0, 0x30FF),
+    (0x31F0, 0x31FF),
+    (0x1B000, 0x1B0FF),
+    (0xAC00, 0xD7AF),
+    (0x1100, 0x11FF),
+    (0xA960, 0xA97F),
+    (0xD7B0, 0xD7FF),
+    (0x3130, 0x318F),
+    (0xA4D0, 0xA4FF),
+    (0x16F00, 0x16F9F),
+    (0xA000, 0xA48F),
+    (0xA490, 0xA4CF),
+];
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Error {
+    /// Empty mnemonic
+    Empty,
+    /// 2FA seed (TrustedCoin)
+    TwoFactorAuthentication,
+    /// Not a supported Electrum seed
+    UnknownVersion,
+}
+
+impl std::error::Error for Error {}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "Empty mnemonic"),
+            Self::TwoFactorAuthentication => write!(
+                f,
+                "Electrum 2FA seeds are not supported: one of the keys is held by TrustedCoin. Restore it in Electrum and disable 2FA first"
+            ),
+            Self::UnknownVersion => write!(f, "Not a standard or segwit Electrum seed"),
+        }
+    }
+}
+
+/// Electrum seed version
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ElectrumSeedVersion {
+    /// P2PKH (root: `m/`)
+    Standard,
+    /// P2WPKH (root: `m/0'`)
+    Segwit,
+}
+
+impl fmt::Display for ElectrumSeedVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Standard => write!(f, "standard"),
+            Self::Segwit => write!(f, "segwit"),
+        }
+    }
+}
+
+impl ElectrumSeedVersion {
+    fn from_mnemonic(mnemonic: &str) -> Result<Self, Error> {
+        let mut engine: HmacEngine<sha512::Hash> = HmacEngine::new(SEED_VERSION_HMAC_KEY);
+        engine.input(mnemonic.as_bytes());
+        let hash: [u8; 64] = Hmac::from_engine(engine).to_byte_array();
+        let version: String = hex::encode(&hash[..2]);
+
+        if version.starts_with("01") {
+            Ok(Self::Standard)
+        } else if version.starts_with("100") {
+            Ok(Self::Segwit)
+        } else if version.starts_with("101") || version.starts_with("102") {
+            Err(Error::TwoFactorAuthentication)
+        } else {
+            Err(Error::UnknownVersion)
+        }
+    }
+
+    /// Path of the account key used by Electrum
+    pub fn account_extended_path(&self) -> DerivationPath {
+        match self {
+            Self::Standard => DerivationPath::master(),
+            Self::Segwit => DerivationPath::from(vec![ChildNumber::Hardened { index: 0 }]),
+        }
+    }
+
+    /// Purpose with the same script type (used for descriptors and SLIP132 versions)
+    pub fn purpose(&self) -> Purpose {
+        match self {
+            Self::Standard => Purpose::BIP44,
+            Self::Segwit => Purpose::BIP84,
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Zeroize, ZeroizeOnDrop)]
+pub struct ElectrumMnemonic {
+    words: String,
+    #[zeroize(skip)]
+    version: ElectrumSeedVersion,
+}
+
+impl fmt::Debug for ElectrumMnemonic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<sensitive>")
+    }
+}
+
+impl fmt::Display for ElectrumMnemonic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.words)
+    }
+}
+
+impl FromStr for ElectrumMnemonic {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let words: String = normalize(s);
+        if words.is_empty() {
+            return Err(Error::Empty);
+        }
+        let version = ElectrumSeedVersion::from_mnemonic(&words)?;
+        Ok(Self { words, version })
+    }
+}
+
+impl Serialize for ElectrumMnemonic {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.words)
+    }
+}
+
+impl<'de> Deserialize<'de> for ElectrumMnemonic {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let words: String = String::deserialize(deserializer)?;
+        Self::from_str(&words).map_err(serde::de::Error::custom)
+    }
+}
+
+impl ElectrumMnemonic {
+    pub fn version(&self) -> ElectrumSeedVersion {
+        self.version
+    }
+
+    pub fn word_count(&self) -> usize {
+        self.words.split_whitespace().count()
+    }
+
+    pub fn to_seed<S>(&self, passphrase: S) -> [u8; 64]
+    where
+        S: AsRef<str>,
+    {
+        let salt: String = format!("{SEED_SALT_PREFIX}{}", normalize(passphrase.as_ref()));
+        hash::pbkdf2_hmac_sha512(&self.words, salt, PBKDF2_ROUNDS)
+    }
+}
+
+fn is_cjk(c: char) -> bool {
+    let c: u32 = c as u32;
+    CJK_INTERVALS
+        .iter()
+        .any(|(start, end)| c >= *start && c <= *end)
+}
+
+/// Normalize text like Electrum does (NFKD, lowercase, no accents, single spaces and no spaces between CJK chars)
+fn normalize(text: &str) -> String {
+    let text: String = text
+        .nfkd()
+        .collect::<String>()
+        .to_lowercase()
+        .chars()
+        .filter(|c| canonical_combining_class(*c) == 0)
+        .collect();
+    let chars: Vec<char> = text
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .chars()
+        .collect();
+    chars
+        .iter()
+        .enumerate()
+        .filter(|(i, c)| {
+            !(c.is_whitespace()
+                && *i > 0
+                && is_cjk(chars[i - 1])
+                && chars.get(i + 1).map(|c| is_cjk(*c)).unwrap_or(false))
+        })
+        .map(|(_, c)| *c)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use bdk::bitcoin::Network;
+
+    use super::*;
+    use crate::bips::bip32::ExtendedPrivKey;
+
+    #[test]
+    fn test_electrum_seed_version() {
+        let mnemonic = ElectrumMnemonic::from_str(
+            "cycle rocket west magnet parrot shuffle foot correct salt library feed song",
+        )
+        .unwrap();
+        assert_eq!(mnemonic.version(), ElectrumSeedVersion::Standard);
+
+        let mnemonic = ElectrumMnemonic::from_str(
+            "  Bitter grass shiver impose  acquire brush forget axis eager alone wine silver",
+        )
+        .unwrap();
+        assert_eq!(mnemonic.version(), ElectrumSeedVersion::Segwit);
+        assert_eq!(
+            mnemonic.to_string(),
+            "bitter grass shiver impose acquire brush forget axis eager alone wine silver"
+        );
+
+        // BIP39 mnemonic
+        assert_eq!(
+            ElectrumMnemonic::from_str("easy uncover favorite crystal bless differ energy seat ecology match carry group refuse together chat observe hidden glad brave month diesel sustain depth salt").unwrap_err(),
+            Error::UnknownVersion
+        );
+    }
+
+    #[test]
+    fn test_electrum_2fa_seed() {
+        assert_eq!(
+            ElectrumMnemonic::from_str(
+                "abandon science wild father tree among universe such mobile favorite target dynamic"
+            )
+            .unwrap_err(),
+            Error::TwoFactorAuthentication
+        );
+        assert_eq!(
+            ElectrumMnemonic::from_str(
+                "above achieve wild father tree among universe such mobile favorite target dynamic"
+            )
+            .unwrap_err(),
+            Error::TwoFactorAuthentication
+        );
+    }
+
+    #[test]
+    fn test_electrum_to_seed() {
+        let mnemonic = ElectrumMnemonic::from_str(
+            "cycle rocket west magnet parrot shuffle foot correct salt library feed song",
+        )
+        .unwrap();
+        let root = ExtendedPrivKey::new_master(Network::Bitcoin, &mnemonic.to_seed("")).unwrap();
+        assert_eq!(root.to_string(), "xprv9s21ZrQH143K32jECVM729vWgGq4mUDJCk1ozqAStTphzQtCTuoFmFafNoG1g55iCnBTXUzz3zWnDb5CVLGiFvmaZjuazHDL8a81cPQ8KL6".to_string());
+
+        // Electrum passphrases are case-insensitive
+        assert_eq!(
+            mnemonic.to_seed("Passphrase"),
+            mnemonic.to_seed("passphrase")
+        );
+    }
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize("  Héllo   WORLD  "), "hello world");
+        assert_eq!(normalize("あ い う abc  漢 字"), "あいう abc 漢字");
+    }
+}
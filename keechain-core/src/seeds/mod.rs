@@ -0,0 +1,8 @@
+// Copyright (c) 2022-2023 Yuki Kishimoto
+// Distributed under the MIT software license
+
+//! Seed formats and backup schemes
+
+pub mod aezeed;
+pub mod electrum;
+pub mod xor;
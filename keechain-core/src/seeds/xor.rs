@@ -0,0 +1,143 @@
+// Copyright (c) 2022-2023 Yuki Kishimoto
+// Distributed under the MIT software license
+
+//! Seed XOR
+//!
+//! <https://seedxor.com>
+//!
+//! Coldcard-compatible: 24-word mnemonics are split into 24-word parts, each a valid BIP39 mnemonic.
+
+use core::fmt;
+
+use bdk::bitcoin::secp256k1::rand::rngs::OsRng;
+use bdk::bitcoin::secp256k1::rand::RngCore;
+use zeroize::Zeroizing;
+
+use crate::bips::bip39::{self, Mnemonic};
+
+pub const MIN_PARTS: usize = 2;
+/// Word count of the mnemonics to split (and of the parts)
+pub const WORD_COUNT: usize = 24;
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum Error {
+    BIP39(bip39::Error),
+    /// Less than [`MIN_PARTS`] parts
+    NotEnoughParts,
+    /// Parts with different word count
+    WordCountMismatch,
+    /// Only [`WORD_COUNT`] words mnemonics can be split
+    UnsupportedWordCount(usize),
+}
+
+impl std::error::Error for Error {}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::BIP39(e) => write!(f, "BIP39: {e}"),
+            Self::NotEnoughParts => write!(f, "at least {MIN_PARTS} parts are required"),
+            Self::WordCountMismatch => write!(f, "all parts must have the same word count"),
+            Self::UnsupportedWordCount(count) => write!(
+                f,
+                "only {WORD_COUNT} words mnemonics can be split, found {count} words"
+            ),
+        }
+    }
+}
+
+impl From<bip39::Error> for Error {
+    fn from(e: bip39::Error) -> Self {
+        Self::BIP39(e)
+    }
+}
+
+/// Split a 24-word mnemonic into `parts` 24-word mnemonics whose XOR is the original one
+pub fn split(mnemonic: &Mnemonic, parts: usize) -> Result<Vec<Mnemonic>, Error> {
+    if parts < MIN_PARTS {
+        return Err(Error::NotEnoughParts);
+    }
+    if mnemonic.word_count() != WORD_COUNT {
+        return Err(Error::UnsupportedWordCount(mnemonic.word_count()));
+    }
+
+    let mut last: Zeroizing<Vec<u8>> = Zeroizing::new(mnemonic.to_entropy());
+    let mut mnemonics: Vec<Mnemonic> = Vec::with_capacity(parts);
+    for _ in 1..parts {
+        let mut entropy: Zeroizing<Vec<u8>> = Zeroizing::new(vec![0u8; last.len()]);
+        OsRng.fill_bytes(&mut entropy);
+        for (l, e) in last.iter_mut().zip(entropy.iter()) {
+            *l ^= e;
+        }
+        mnemonics.push(Mnemonic::from_entropy(&entropy)?);
+    }
+    mnemonics.push(Mnemonic::from_entropy(&last)?);
+
+    Ok(mnemonics)
+}
+
+/// Combine the parts back into the original mnemonic (the order doesn't matter)
+pub fn combine(parts: &[Mnemonic]) -> Result<Mnemonic, Error> {
+    if parts.len() < MIN_PARTS {
+        return Err(Error::NotEnoughParts);
+    }
+
+    let mut entropy: Zeroizing<Vec<u8>> = Zeroizing::new(parts[0].to_entropy());
+    for part in parts.iter().skip(1) {
+        let part: Zeroizing<Vec<u8>> = Zeroizing::new(part.to_entropy());
+        if part.len() != entropy.len() {
+            return Err(Error::WordCountMismatch);
+        }
+        for (e, p) in entropy.iter_mut().zip(part.iter()) {
+            *e ^= p;
+        }
+    }
+
+    Ok(Mnemonic::from_entropy(&entropy)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use core::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn test_combine() {
+        // Coldcard test vector
+        let parts = vec![
+            Mnemonic::from_str("romance wink lottery autumn shop bring dawn tongue range crater truth ability miss spice fitness easy legal release recall obey exchange recycle dragon room").unwrap(),
+            Mnemonic::from_str("lion misery divide hurry latin fluid camp advance illegal lab pyramid unaware eager fringe sick camera series noodle toy crowd jeans select depth lounge").unwrap(),
+            Mnemonic::from_str("vault nominee cradle silk own frown throw leg cactus recall talent worry gadget surface shy planet purpose coffee drip few seven term squeeze educate").unwrap(),
+        ];
+        assert_eq!(
+            combine(&parts).unwrap(),
+            Mnemonic::from_str("silent toe meat possible chair blossom wait occur this worth option bag nurse find fish scene bench asthma bike wage world quit primary indoor").unwrap()
+        );
+        assert_eq!(combine(&parts[..1]).unwrap_err(), Error::NotEnoughParts);
+    }
+
+    #[test]
+    fn test_split() {
+        let mnemonic = Mnemonic::from_str("silent toe meat possible chair blossom wait occur this worth option bag nurse find fish scene bench asthma bike wage world quit primary indoor").unwrap();
+        for count in MIN_PARTS..5 {
+            let parts: Vec<Mnemonic> = split(&mnemonic, count).unwrap();
+            assert_eq!(parts.len(), count);
+            assert!(parts.iter().all(|p| p.word_count() == 24));
+            assert_eq!(combine(&parts).unwrap(), mnemonic);
+        }
+        assert_eq!(split(&mnemonic, 1).unwrap_err(), Error::NotEnoughParts);
+
+        let short = Mnemonic::from_str(
+            "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+        )
+        .unwrap();
+        assert_eq!(
+            split(&short, 2).unwrap_err(),
+            Error::UnsupportedWordCount(12)
+        );
+        let mut parts: Vec<Mnemonic> = split(&mnemonic, 2).unwrap();
+        parts.push(short);
+        assert_eq!(combine(&parts).unwrap_err(), Error::WordCountMismatch);
+    }
+}
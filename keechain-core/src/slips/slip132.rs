@@ -1,18 +1,25 @@
 // Copyright (c) 2022-2023 Yuki Kishimoto
 // Distributed under the MIT software license
 
+//! SLIP132
+//!
+//! <https://github.com/satoshilabs/slips/blob/master/slip-0132.md>
+
 use core::fmt;
 
 use bdk::bitcoin::base58;
-use bdk::bitcoin::bip32::{ChildNumber, DerivationPath, ExtendedPubKey};
+use bdk::bitcoin::bip32::{self, ChildNumber, DerivationPath, ExtendedPubKey};
 
-use crate::util::hex;
+use crate::bips::bip43::Purpose;
+use crate::bips::bip48;
 
 #[derive(Debug, PartialEq, Eq)]
 pub enum Error {
     Base58(base58::Error),
-    Hex(hex::Error),
+    BIP32(bip32::Error),
     UnsupportedDerivationPath,
+    UnknownVersion,
+    InvalidLength(usize),
 }
 
 impl std::error::Error for Error {}
@@ -21,8 +28,15 @@ impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::Base58(e) => write!(f, "Base58: {e}"),
-            Self::Hex(e) => write!(f, "Hex: {e}"),
+            Self::BIP32(e) => write!(f, "BIP32: {e}"),
             Self::UnsupportedDerivationPath => write!(f, "Unsupported derivation path"),
+            Self::UnknownVersion => write!(f, "Unknown SLIP132 version"),
+            Self::InvalidLength(len) => {
+                write!(
+                    f,
+                    "Invalid extended key length: expected 78 bytes, got {len}"
+                )
+            }
         }
     }
 }
@@ -33,9 +47,123 @@ impl From<base58::Error> for Error {
     }
 }
 
-impl From<hex::Error> for Error {
-    fn from(e: hex::Error) -> Self {
-        Self::Hex(e)
+impl From<bip32::Error> for Error {
+    fn from(e: bip32::Error) -> Self {
+        Self::BIP32(e)
+    }
+}
+
+/// Script type of a SLIP132 version
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ScriptType {
+    /// P2PKH or P2SH (`xpub`, `tpub`), also used by the scripts without a dedicated version
+    P2PKH,
+    /// P2WPKH nested in P2SH (`ypub`, `upub`)
+    P2SHWPKH,
+    /// P2WPKH (`zpub`, `vpub`)
+    P2WPKH,
+    /// Multisig P2WSH nested in P2SH (`Ypub`, `Upub`)
+    P2SHWSH,
+    /// Multisig P2WSH (`Zpub`, `Vpub`)
+    P2WSH,
+}
+
+impl fmt::Display for ScriptType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::P2PKH => write!(f, "P2PKH"),
+            Self::P2SHWPKH => write!(f, "P2SH-P2WPKH"),
+            Self::P2WPKH => write!(f, "P2WPKH"),
+            Self::P2SHWSH => write!(f, "P2SH-P2WSH (multisig)"),
+            Self::P2WSH => write!(f, "P2WSH (multisig)"),
+        }
+    }
+}
+
+impl ScriptType {
+    const ALL: [Self; 5] = [
+        Self::P2PKH,
+        Self::P2SHWPKH,
+        Self::P2WPKH,
+        Self::P2SHWSH,
+        Self::P2WSH,
+    ];
+
+    /// Script type of a derivation path purpose
+    pub fn from_purpose(purpose: Purpose) -> Result<Self, Error> {
+        match purpose {
+            Purpose::BIP44 => Ok(Self::P2PKH),
+            Purpose::BIP49 => Ok(Self::P2SHWPKH),
+            Purpose::BIP84 => Ok(Self::P2WPKH),
+            Purpose::BIP48 {
+                script: bip48::ScriptType::P2SHWSH,
+            } => Ok(Self::P2SHWSH),
+            Purpose::BIP48 {
+                script: bip48::ScriptType::P2WSH,
+            } => Ok(Self::P2WSH),
+            _ => Err(Error::UnsupportedDerivationPath),
+        }
+    }
+
+    /// Version bytes
+    pub fn version(&self, is_mainnet: bool) -> [u8; 4] {
+        match (self, is_mainnet) {
+            (Self::P2PKH, true) => [0x04, 0x88, 0xb2, 0x1e],
+            (Self::P2PKH, false) => [0x04, 0x35, 0x87, 0xcf],
+            (Self::P2SHWPKH, true) => [0x04, 0x9d, 0x7c, 0xb2],
+            (Self::P2SHWPKH, false) => [0x04, 0x4a, 0x52, 0x62],
+            (Self::P2WPKH, true) => [0x04, 0xb2, 0x47, 0x46],
+            (Self::P2WPKH, false) => [0x04, 0x5f, 0x1c, 0xf6],
+            (Self::P2SHWSH, true) => [0x02, 0x95, 0xb4, 0x3f],
+            (Self::P2SHWSH, false) => [0x02, 0x42, 0x89, 0xef],
+            (Self::P2WSH, true) => [0x02, 0xaa, 0x7e, 0xd3],
+            (Self::P2WSH, false) => [0x02, 0x57, 0x54, 0x83],
+        }
+    }
+
+    /// Script type and network (`true` for mainnet) of version bytes
+    pub fn from_version(version: [u8; 4]) -> Option<(Self, bool)> {
+        Self::ALL.into_iter().find_map(|script| {
+            if script.version(true) == version {
+                Some((script, true))
+            } else if script.version(false) == version {
+                Some((script, false))
+            } else {
+                None
+            }
+        })
+    }
+
+    /// BIP48 script type of the multisig versions
+    pub fn multisig(&self) -> Option<bip48::ScriptType> {
+        match self {
+            Self::P2SHWSH => Some(bip48::ScriptType::P2SHWSH),
+            Self::P2WSH => Some(bip48::ScriptType::P2WSH),
+            _ => None,
+        }
+    }
+
+    /// Check that a key origin path doesn't belong to another script type
+    ///
+    /// Non-standard paths are accepted.
+    pub fn matches_path(&self, path: &DerivationPath) -> bool {
+        let purpose: u32 = match path.into_iter().next() {
+            Some(ChildNumber::Hardened { index }) => *index,
+            _ => return true,
+        };
+        match (self, purpose) {
+            (Self::P2PKH, _) => true,
+            (Self::P2SHWPKH, 49) | (Self::P2WPKH, 84) => true,
+            (Self::P2SHWSH | Self::P2WSH, 48) => match path.into_iter().nth(3) {
+                Some(ChildNumber::Hardened { index }) => self
+                    .multisig()
+                    .map(|script| script.as_u32() == *index)
+                    .unwrap_or_default(),
+                _ => true,
+            },
+            (_, 44 | 45 | 48 | 49 | 84 | 86) => false,
+            _ => true,
+        }
     }
 }
 
@@ -47,8 +175,6 @@ pub trait ToSlip132 {
 impl ToSlip132 for ExtendedPubKey {
     type Err = Error;
     fn to_slip132(&self, path: &DerivationPath) -> Result<String, Self::Err> {
-        let data: Vec<u8> = base58::decode_check(&self.to_string())?;
-
         let mut iter = path.into_iter();
         let purpose: Option<&ChildNumber> = iter.next();
         let is_mainnet: bool = match iter.next() {
@@ -57,34 +183,100 @@ impl ToSlip132 for ExtendedPubKey {
             _ => return Err(Error::UnsupportedDerivationPath),
         };
 
-        let hex: &str = match purpose {
-            Some(ChildNumber::Hardened { index: 44 }) => {
-                if is_mainnet {
-                    "0488b21e"
-                } else {
-                    "043587cf"
-                }
-            }
-            Some(ChildNumber::Hardened { index: 49 }) => {
-                if is_mainnet {
-                    "049d7cb2"
-                } else {
-                    "044a5262"
-                }
+        match purpose {
+            // Path: m/48'/<coin_type>'/<account>'/<script_type>'
+            Some(ChildNumber::Hardened { index: 48 }) => {
+                let script: ScriptType = match iter.nth(1) {
+                    Some(ChildNumber::Hardened { index: 1 }) => ScriptType::P2SHWSH,
+                    Some(ChildNumber::Hardened { index: 2 }) => ScriptType::P2WSH,
+                    _ => return Err(Error::UnsupportedDerivationPath),
+                };
+                Ok(encode_script(self, script, is_mainnet))
             }
-            Some(ChildNumber::Hardened { index: 84 }) => {
-                if is_mainnet {
-                    "04b24746"
-                } else {
-                    "045f1cf6"
+            Some(ChildNumber::Hardened { index }) => encode(self, *index, is_mainnet),
+            _ => Err(Error::UnsupportedDerivationPath),
+        }
+    }
+}
+
+pub trait FromSlip132: Sized {
+    type Err;
+    /// Decode a SLIP132 extended key, with the script type inferred from its version
+    fn from_slip132(s: &str) -> Result<(Self, ScriptType), Self::Err>;
+}
+
+impl FromSlip132 for ExtendedPubKey {
+    type Err = Error;
+    fn from_slip132(s: &str) -> Result<(Self, ScriptType), Self::Err> {
+        let mut data: Vec<u8> = base58::decode_check(s.trim())?;
+        if data.len() != 78 {
+            return Err(Error::InvalidLength(data.len()));
+        }
+
+        let mut version: [u8; 4] = [0; 4];
+        version.copy_from_slice(&data[..4]);
+        let (script, is_mainnet) =
+            ScriptType::from_version(version).ok_or(Error::UnknownVersion)?;
+
+        // Back to the BIP32 version
+        data[..4].copy_from_slice(&ScriptType::P2PKH.version(is_mainnet));
+        Ok((ExtendedPubKey::decode(&data)?, script))
+    }
+}
+
+/// Encode extended public key with the SLIP132 version of `purpose`
+pub fn encode(pubkey: &ExtendedPubKey, purpose: u32, is_mainnet: bool) -> Result<String, Error> {
+    let script: ScriptType = match purpose {
+        44 => ScriptType::P2PKH,
+        49 => ScriptType::P2SHWPKH,
+        84 => ScriptType::P2WPKH,
+        _ => return Err(Error::UnsupportedDerivationPath),
+    };
+    Ok(encode_script(pubkey, script, is_mainnet))
+}
+
+/// Encode extended public key with the SLIP132 version of `script`
+pub fn encode_script(pubkey: &ExtendedPubKey, script: ScriptType, is_mainnet: bool) -> String {
+    let mut data: [u8; 78] = pubkey.encode();
+    data[..4].copy_from_slice(&script.version(is_mainnet));
+    base58::encode_check(&data)
+}
+
+/// Replace the SLIP132 extended keys (ex. `zpub`, `Zpub`) of a key expression or descriptor with
+/// their BIP32 encoding (`xpub`, `tpub`)
+///
+/// Returns the decoded keys, with their script type. Descriptor checksums are not updated.
+pub fn replace_keys(s: &str) -> (String, Vec<(ExtendedPubKey, ScriptType)>) {
+    let mut output: String = String::with_capacity(s.len());
+    let mut decoded: Vec<(ExtendedPubKey, ScriptType)> = Vec::new();
+    let mut token: String = String::new();
+
+    let mut flush = |token: &mut String, output: &mut String| {
+        if token.get(1..4) == Some("pub") {
+            if let Ok((xpub, script)) = ExtendedPubKey::from_slip132(token) {
+                if script != ScriptType::P2PKH {
+                    output.push_str(&xpub.to_string());
+                    decoded.push((xpub, script));
+                    token.clear();
+                    return;
                 }
             }
-            _ => return Err(Error::UnsupportedDerivationPath),
-        };
+        }
+        output.push_str(token);
+        token.clear();
+    };
 
-        let data: Vec<u8> = [hex::decode(hex)?, data[4..].to_vec()].concat();
-        Ok(base58::encode_check(&data))
+    for c in s.chars() {
+        if c.is_ascii_alphanumeric() && !matches!(c, '0' | 'O' | 'I' | 'l') {
+            token.push(c);
+        } else {
+            flush(&mut token, &mut output);
+            output.push(c);
+        }
     }
+    flush(&mut token, &mut output);
+
+    (output, decoded)
 }
 
 #[cfg(test)]
@@ -106,7 +298,8 @@ mod tests {
         let passphrase: Option<&str> = Some("mypassphrase");
         let seed = Seed::new(mnemonic, passphrase);
 
-        let root = ExtendedPrivKey::new_master(Network::Bitcoin, &seed.to_bytes()).unwrap();
+        let root =
+            ExtendedPrivKey::new_master(Network::Bitcoin, &seed.to_bytes().unwrap()).unwrap();
 
         let path = DerivationPath::from_str("m/44'/0'/0'").unwrap();
         let pubkey: ExtendedPubKey =
@@ -129,5 +322,57 @@ mod tests {
                 .unwrap_err(),
             Error::UnsupportedDerivationPath
         );
+
+        let path = DerivationPath::from_str("m/48'/0'/0'/2'").unwrap();
+        let pubkey: ExtendedPubKey =
+            ExtendedPubKey::from_priv(&secp, &root.derive_priv(&secp, &path).unwrap());
+        let zpub: String = pubkey.to_slip132(&path).unwrap();
+        assert!(zpub.starts_with("Zpub"));
+        assert_eq!(
+            ExtendedPubKey::from_slip132(&zpub).unwrap(),
+            (pubkey, ScriptType::P2WSH)
+        );
+
+        let path = DerivationPath::from_str("m/48'/1'/0'/1'").unwrap();
+        let pubkey: ExtendedPubKey =
+            ExtendedPubKey::from_priv(&secp, &root.derive_priv(&secp, &path).unwrap());
+        assert!(pubkey.to_slip132(&path).unwrap().starts_with("Upub"));
+
+        let path = DerivationPath::from_str("m/48'/0'/0'/3'").unwrap();
+        assert_eq!(
+            pubkey.to_slip132(&path).unwrap_err(),
+            Error::UnsupportedDerivationPath
+        );
+    }
+
+    #[test]
+    fn test_from_slip132() {
+        let (xpub, script) = ExtendedPubKey::from_slip132("zpub6qR4RRKqYzgY9psfVvZFQchEZfH6upEMWJRJSLWAXeYk4KXNKoLuBzC7977uUKMFiVYNMqMrrjNgJ871YQeJEbgzQ6hZevYE8uB6NipiLLj").unwrap();
+        assert_eq!(xpub.to_string(), "xpub6BkXp5z1FdbaTEVRqCyzzSWEDizD2aFMg5NrsYiPmdnyx7tupV1mwrsq6hCjUW3QuDJkrtAjwQfaXYst71pGe8KnfRJiV6uFbT3obbGiNAM");
+        assert_eq!(script, ScriptType::P2WPKH);
+
+        let (zpub, script) = ExtendedPubKey::from_slip132("Zpub72K9Yf4H7xEuaQ33Sb2EEh33HTKN8Auwpa4yMbmhuRP9gW6H6CjBH742wp5P2ka9wxcMERMyjwmBRHimqdoFP5ofEZoy4L1E2dSxxev1WaM").unwrap();
+        assert_eq!(zpub, xpub);
+        assert_eq!(script, ScriptType::P2WSH);
+
+        let (_, script) = ExtendedPubKey::from_slip132(&xpub.to_string()).unwrap();
+        assert_eq!(script, ScriptType::P2PKH);
+
+        assert!(ExtendedPubKey::from_slip132("zpub6qR4RRKqYzgY9psfVvZFQchEZ").is_err());
+    }
+
+    #[test]
+    fn test_replace_keys() {
+        let (desc, decoded) = replace_keys("wsh(sortedmulti(2,[0f056943/48'/0'/0'/2']Zpub72K9Yf4H7xEuaQ33Sb2EEh33HTKN8Auwpa4yMbmhuRP9gW6H6CjBH742wp5P2ka9wxcMERMyjwmBRHimqdoFP5ofEZoy4L1E2dSxxev1WaM/0/*,[91ef223d/48'/0'/0'/2']xpub6BkXp5z1FdbaTEVRqCyzzSWEDizD2aFMg5NrsYiPmdnyx7tupV1mwrsq6hCjUW3QuDJkrtAjwQfaXYst71pGe8KnfRJiV6uFbT3obbGiNAM/0/*))");
+        assert_eq!(desc, "wsh(sortedmulti(2,[0f056943/48'/0'/0'/2']xpub6BkXp5z1FdbaTEVRqCyzzSWEDizD2aFMg5NrsYiPmdnyx7tupV1mwrsq6hCjUW3QuDJkrtAjwQfaXYst71pGe8KnfRJiV6uFbT3obbGiNAM/0/*,[91ef223d/48'/0'/0'/2']xpub6BkXp5z1FdbaTEVRqCyzzSWEDizD2aFMg5NrsYiPmdnyx7tupV1mwrsq6hCjUW3QuDJkrtAjwQfaXYst71pGe8KnfRJiV6uFbT3obbGiNAM/0/*))");
+        assert_eq!(decoded.len(), 1);
+        assert_eq!(decoded[0].1, ScriptType::P2WSH);
+
+        let path = DerivationPath::from_str("m/48'/0'/0'/2'").unwrap();
+        assert!(ScriptType::P2WSH.matches_path(&path));
+        assert!(!ScriptType::P2SHWSH.matches_path(&path));
+        assert!(!ScriptType::P2WPKH.matches_path(&path));
+        assert!(ScriptType::P2WPKH.matches_path(&DerivationPath::from_str("m/84'/0'/0'").unwrap()));
+        assert!(ScriptType::P2WPKH.matches_path(&DerivationPath::from_str("m/1'/0'/0'").unwrap()));
     }
 }
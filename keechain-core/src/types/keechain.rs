@@ -6,26 +6,40 @@ use core::ops::{Deref, DerefMut};
 use std::fs::{self, File};
 use std::io::{Read, Write};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
 
 use bdk::bitcoin::hashes::sha256::Hash as Sha256Hash;
 use bdk::bitcoin::hashes::Hash;
 use bdk::bitcoin::psbt::PartiallySignedTransaction;
 use bdk::bitcoin::secp256k1::{Secp256k1, Signing};
-use bdk::bitcoin::{Network, PrivateKey};
+use bdk::bitcoin::{Address, Network, PrivateKey};
 use bdk::miniscript::Descriptor;
 use bdk::signer::SignerWrapper;
 use serde::{Deserialize, Serialize};
 
-use super::keychain::{self, EncryptedKeychain, Keychain};
-use super::Index;
-use crate::bips::bip32::{self, Bip32, Fingerprint};
-use crate::bips::bip39::{self, Mnemonic};
+use super::keychain::{self, Bip85Origin, EncryptedKeychain, Keychain, Passphrase};
+use super::{Index, SeedKind};
+use crate::bips::bip129::{self, DescriptorRecord, KeyRecord, Token};
+use crate::bips::bip32::{self, Bip32, DerivationPath, Fingerprint};
+use crate::bips::bip39::{self, Language, Mnemonic};
+use crate::bips::bip43::Purpose;
+use crate::bips::bip48::{self, ScriptType};
+use crate::bips::bip85::Application;
+use crate::blinded::BlindedKey;
 use crate::crypto::aes;
 use crate::crypto::{self, hash, MultiEncryption};
-use crate::psbt::{self, PsbtUtility};
+use crate::descriptors::DerivedAddress;
+use crate::multisig::{self, Cosigner, MultisigWallet};
+use crate::policy::{self, CompiledPolicy};
+use crate::psbt::{self, MultisigReview, PsbtUtility, SigningDescriptor};
+use crate::recovery::address::{self as address_recovery, AddressOrigin, Bounds};
+use crate::recovery::passphrase::{self as passphrase_recovery, PassphraseGenerator};
+use crate::recovery::{self, Target};
 use crate::types::WordCount;
 use crate::util::dir::{self, KEECHAIN_DOT_EXTENSION, KEECHAIN_EXTENSION};
 use crate::util::{self, base64};
+use crate::vanity::{self, Pattern, Progress as VanityProgress};
 use crate::{Result, Seed};
 
 const KEECHAIN_FILE_VERSION: u8 = 2;
@@ -40,8 +54,13 @@ pub enum Error {
     Base64(base64::DecodeError),
     BIP32(bip32::Error),
     BIP39(bip39::Error),
+    BIP129(bip129::Error),
     Keychain(keychain::Error),
+    Multisig(multisig::Error),
+    Policy(policy::Error),
     Psbt(psbt::Error),
+    Recovery(recovery::Error),
+    Vanity(vanity::Error),
     Generic(String),
     InvalidName,
     FileNotFound,
@@ -50,6 +69,7 @@ pub enum Error {
     PasswordNotMatch,
     CurrentPasswordNotMatch,
     UnknownVersion(u8),
+    WatchOnly,
 }
 
 impl std::error::Error for Error {}
@@ -65,8 +85,13 @@ impl fmt::Display for Error {
             Self::Base64(e) => write!(f, "Base64: {e}"),
             Self::BIP32(e) => write!(f, "BIP32: {e}"),
             Self::BIP39(e) => write!(f, "BIP39: {e}"),
+            Self::BIP129(e) => write!(f, "BIP129: {e}"),
             Self::Keychain(e) => write!(f, "Keychain: {e}"),
+            Self::Multisig(e) => write!(f, "Multisig: {e}"),
+            Self::Policy(e) => write!(f, "Policy: {e}"),
             Self::Psbt(e) => write!(f, "Psbt: {e}"),
+            Self::Recovery(e) => write!(f, "Recovery: {e}"),
+            Self::Vanity(e) => write!(f, "Vanity: {e}"),
             Self::Generic(e) => write!(f, "Generic: {e}"),
             Self::InvalidName => write!(f, "Invalid name"),
             Self::FileNotFound => write!(f, "File not found"),
@@ -78,6 +103,10 @@ impl fmt::Display for Error {
             Self::PasswordNotMatch => write!(f, "Password not match"),
             Self::CurrentPasswordNotMatch => write!(f, "Current password not match"),
             Self::UnknownVersion(v) => write!(f, "Unknown keechain file version: {v}"),
+            Self::WatchOnly => write!(
+                f,
+                "This is a watch-only keychain: secrets and signing are not available"
+            ),
         }
     }
 }
@@ -130,12 +159,42 @@ impl From<bip39::Error> for Error {
     }
 }
 
+impl From<bip129::Error> for Error {
+    fn from(e: bip129::Error) -> Self {
+        Self::BIP129(e)
+    }
+}
+
 impl From<keychain::Error> for Error {
     fn from(e: keychain::Error) -> Self {
         Self::Keychain(e)
     }
 }
 
+impl From<multisig::Error> for Error {
+    fn from(e: multisig::Error) -> Self {
+        Self::Multisig(e)
+    }
+}
+
+impl From<policy::Error> for Error {
+    fn from(e: policy::Error) -> Self {
+        Self::Policy(e)
+    }
+}
+
+impl From<recovery::Error> for Error {
+    fn from(e: recovery::Error) -> Self {
+        Self::Recovery(e)
+    }
+}
+
+impl From<vanity::Error> for Error {
+    fn from(e: vanity::Error) -> Self {
+        Self::Vanity(e)
+    }
+}
+
 impl From<psbt::Error> for Error {
     fn from(e: psbt::Error) -> Self {
         Self::Psbt(e)
@@ -232,6 +291,10 @@ impl KeeChain {
             return Err(Error::InvalidName);
         }
 
+        if dir::is_watch_only(base_path.as_ref(), name.clone())? {
+            return Err(Error::WatchOnly);
+        }
+
         let keychain_file: PathBuf = dir::get_keychain_file(base_path, name)?;
         if !keychain_file.exists() {
             return Err(Error::FileNotFound);
@@ -340,7 +403,7 @@ impl KeeChain {
         Ok(keechain)
     }
 
-    pub fn restore<P, S, PSW, CPSW, M, C>(
+    pub fn restore<P, S, PSW, CPSW, M, K, C>(
         base_path: P,
         name: S,
         get_password: PSW,
@@ -354,7 +417,8 @@ impl KeeChain {
         PSW: FnOnce() -> Result<String>,
         CPSW: FnOnce() -> Result<String>,
         S: Into<String>,
-        M: FnOnce() -> Result<Mnemonic>,
+        M: FnOnce() -> Result<K>,
+        K: Into<SeedKind>,
         C: Signing,
     {
         let name: String = name.into();
@@ -382,8 +446,10 @@ impl KeeChain {
             return Err(Error::PasswordNotMatch);
         }
 
-        let mnemonic: Mnemonic = get_mnemonic().map_err(|e| Error::Generic(e.to_string()))?;
-        let keychain = Keychain::new(mnemonic, Vec::new());
+        let kind: SeedKind = get_mnemonic()
+            .map_err(|e| Error::Generic(e.to_string()))?
+            .into();
+        let keychain = Keychain::new(kind, Vec::new());
 
         let keechain = Self::new(
             keychain_file,
@@ -439,41 +505,280 @@ impl KeeChain {
         self.encrypted_keychain.passphrase()
     }
 
-    pub fn passphrases<T>(&self, password: T) -> Result<Vec<String>, Error>
+    pub fn passphrases<T>(&self, password: T) -> Result<Vec<Passphrase>, Error>
     where
         T: AsRef<[u8]>,
     {
         Ok(self.keychain(password)?.passphrases())
     }
 
-    pub fn add_passphrase<T, S>(&mut self, password: T, passphrase: S) -> Result<(), Error>
+    pub fn add_passphrase<T, L, S, C>(
+        &mut self,
+        password: T,
+        label: L,
+        passphrase: S,
+        secp: &Secp256k1<C>,
+    ) -> Result<Passphrase, Error>
     where
         T: AsRef<[u8]>,
+        L: Into<String>,
         S: Into<String>,
+        C: Signing,
     {
-        self.encrypted_keychain
-            .add_passphrase(password, passphrase)?;
+        let passphrase: Passphrase = self
+            .encrypted_keychain
+            .add_passphrase(password, label, passphrase, secp)?;
+        self.save()?;
+        Ok(passphrase)
+    }
+
+    /// Remove saved passphrase by label
+    pub fn remove_passphrase<T, S>(&mut self, password: T, label: S) -> Result<(), Error>
+    where
+        T: AsRef<[u8]>,
+        S: Into<String>,
+    {
+        self.encrypted_keychain.remove_passphrase(password, label)?;
         self.save()?;
         Ok(())
     }
 
-    pub fn remove_passphrase<T, S>(&mut self, password: T, passphrase: S) -> Result<(), Error>
+    /// Assemble a multisig wallet from our BIP48 key and the cosigners and save it in the keychain
+    pub fn create_multisig_wallet<T, S, C>(
+        &mut self,
+        password: T,
+        name: S,
+        script: ScriptType,
+        account: Option<u32>,
+        threshold: usize,
+        cosigners: &[Cosigner],
+        secp: &Secp256k1<C>,
+    ) -> Result<MultisigWallet, Error>
     where
         T: AsRef<[u8]>,
         S: Into<String>,
+        C: Signing,
     {
+        let key = self
+            .keychain(&password)?
+            .multisig_key(self.network, account, script, secp)?;
+        let wallet: MultisigWallet =
+            MultisigWallet::from_cosigners(name, script, threshold, key, cosigners, self.network)?;
         self.encrypted_keychain
-            .remove_passphrase(password, passphrase)?;
+            .add_multisig_wallet(password, wallet.clone())?;
+        self.save()?;
+        Ok(wallet)
+    }
+
+    pub fn multisig_wallets<T>(&self, password: T) -> Result<Vec<MultisigWallet>, Error>
+    where
+        T: AsRef<[u8]>,
+    {
+        Ok(self.keychain(password)?.multisig_wallets())
+    }
+
+    /// Compile a miniscript policy
+    ///
+    /// `@me` is our BIP48 key of `account` (or the key at `path`), the other aliases are the cosigners.
+    pub fn compile_policy<T, S, C>(
+        &self,
+        password: T,
+        policy: S,
+        script: ScriptType,
+        account: Option<u32>,
+        path: Option<DerivationPath>,
+        cosigners: &[(String, Cosigner)],
+        secp: &Secp256k1<C>,
+    ) -> Result<CompiledPolicy, Error>
+    where
+        T: AsRef<[u8]>,
+        S: Into<String>,
+        C: Signing,
+    {
+        let keychain: Keychain = self.keychain(password)?;
+        let me = match path {
+            Some(path) => keychain.extended_public_key(self.network, &path, secp)?,
+            None => keychain.multisig_key(self.network, account, script, secp)?,
+        };
+        let mut keys = vec![(String::from("me"), me)];
+        for (alias, cosigner) in cosigners.iter() {
+            keys.push((alias.clone(), cosigner.key(script)?));
+        }
+        Ok(policy::compile(policy, script, &keys)?)
+    }
+
+    /// Save a compiled policy as a wallet in the keychain
+    pub fn save_policy_wallet<T, S>(
+        &mut self,
+        password: T,
+        name: S,
+        compiled: &CompiledPolicy,
+    ) -> Result<MultisigWallet, Error>
+    where
+        T: AsRef<[u8]>,
+        S: Into<String>,
+    {
+        let wallet: MultisigWallet = MultisigWallet::from_policy(name, compiled, self.network)?;
+        self.encrypted_keychain
+            .add_multisig_wallet(password, wallet.clone())?;
+        self.save()?;
+        Ok(wallet)
+    }
+
+    /// Blinded BIP48 key labeled `label`: blinded with a random path and saved in the keychain the first time
+    pub fn blinded_key<T, S, C>(
+        &mut self,
+        password: T,
+        label: S,
+        script: ScriptType,
+        account: Option<u32>,
+        secp: &Secp256k1<C>,
+    ) -> Result<BlindedKey, Error>
+    where
+        T: AsRef<[u8]>,
+        S: Into<String>,
+        C: Signing,
+    {
+        let label: String = label.into();
+        let keychain: Keychain = self.keychain(&password)?;
+        if let Some(key) = keychain.get_blinded_key(&label) {
+            let path: DerivationPath = bip48::account_extended_path(self.network, account, script)?;
+            return if key.account_path() == path {
+                Ok(key)
+            } else {
+                Err(keychain::Error::DuplicateBlindedKey(label).into())
+            };
+        }
+        let key: BlindedKey =
+            keychain.blind_multisig_key(self.network, account, script, label, secp)?;
+        self.encrypted_keychain
+            .add_blinded_key(password, key.clone())?;
+        self.save()?;
+        Ok(key)
+    }
+
+    pub fn blinded_keys<T>(&self, password: T) -> Result<Vec<BlindedKey>, Error>
+    where
+        T: AsRef<[u8]>,
+    {
+        Ok(self.keychain(password)?.blinded_keys())
+    }
+
+    /// BIP129 round 1: key record of our BIP48 key, to send to the coordinator
+    pub fn bsms_key_record<T, S, C>(
+        &self,
+        password: T,
+        script: ScriptType,
+        account: Option<u32>,
+        token: Token,
+        description: S,
+        secp: &Secp256k1<C>,
+    ) -> Result<KeyRecord, Error>
+    where
+        T: AsRef<[u8]>,
+        S: Into<String>,
+        C: Signing,
+    {
+        Ok(self.keychain(password)?.bsms_key_record(
+            self.network,
+            account,
+            script,
+            token,
+            description,
+            secp,
+        )?)
+    }
+
+    /// BIP129 round 2: save the wallet of a verified descriptor record in the keychain
+    pub fn bsms_register<T, S>(
+        &mut self,
+        password: T,
+        name: S,
+        record: &DescriptorRecord,
+    ) -> Result<MultisigWallet, Error>
+    where
+        T: AsRef<[u8]>,
+        S: Into<String>,
+    {
+        let wallet: MultisigWallet =
+            record.to_wallet(name, self.encrypted_keychain.fingerprint(), self.network)?;
+        self.encrypted_keychain
+            .add_multisig_wallet(password, wallet.clone())?;
+        self.save()?;
+        Ok(wallet)
+    }
+
+    pub fn remove_multisig_wallet<T, S>(&mut self, password: T, name: S) -> Result<(), Error>
+    where
+        T: AsRef<[u8]>,
+        S: Into<String>,
+    {
+        self.encrypted_keychain
+            .remove_multisig_wallet(password, name)?;
         self.save()?;
         Ok(())
     }
 
+    /// Derive the addresses of a single-sig account
+    pub fn addresses<T, C>(
+        &self,
+        password: T,
+        purpose: Purpose,
+        account: Option<u32>,
+        change: bool,
+        from: u32,
+        count: u32,
+        secp: &Secp256k1<C>,
+    ) -> Result<Vec<DerivedAddress>, Error>
+    where
+        T: AsRef<[u8]>,
+        C: Signing,
+    {
+        Ok(self.keychain(password)?.addresses(
+            self.network,
+            purpose,
+            account,
+            change,
+            from,
+            count,
+            secp,
+        )?)
+    }
+
+    /// Derive the addresses of a saved multisig wallet
+    pub fn multisig_addresses<T, S, C>(
+        &self,
+        password: T,
+        wallet: S,
+        change: bool,
+        from: u32,
+        count: u32,
+        secp: &Secp256k1<C>,
+    ) -> Result<Vec<DerivedAddress>, Error>
+    where
+        T: AsRef<[u8]>,
+        S: Into<String>,
+        C: Signing,
+    {
+        Ok(self.keychain(password)?.multisig_addresses(
+            self.network,
+            wallet,
+            change,
+            from,
+            count,
+            secp,
+        )?)
+    }
+
+    /// Apply passphrase
+    ///
+    /// Return the saved passphrase with the same fingerprint: `None` means it's unknown (typo?).
     pub fn apply_passphrase<T, S, C>(
         &mut self,
         password: T,
         passphrase: Option<S>,
         secp: &Secp256k1<C>,
-    ) -> Result<(), Error>
+    ) -> Result<Option<Passphrase>, Error>
     where
         T: AsRef<[u8]>,
         S: Into<String>,
@@ -484,6 +789,91 @@ impl KeeChain {
             .apply_passphrase(password, passphrase, secp)?)
     }
 
+    /// Search the account, chain and index (or multisig wallet) that produced `address`
+    pub fn verify_address<T, P, C>(
+        &self,
+        password: T,
+        address: &Address,
+        bounds: Bounds,
+        threads: Option<usize>,
+        progress: P,
+        secp: &Secp256k1<C>,
+    ) -> Result<Option<AddressOrigin>, Error>
+    where
+        T: AsRef<[u8]>,
+        P: FnMut(u64, u64),
+        C: Signing,
+    {
+        let keychain: Keychain = self.keychain(password)?;
+        Ok(address_recovery::search(
+            &keychain.seed(),
+            &keychain.multisig_wallets(),
+            address,
+            self.network,
+            bounds,
+            threads,
+            progress,
+            secp,
+        )?)
+    }
+
+    /// Search a vanity address in the `purpose`/`account`/`change` chain
+    pub fn vanity_address<T, P, C>(
+        &self,
+        password: T,
+        purpose: Purpose,
+        account: Option<u32>,
+        change: bool,
+        pattern: Pattern,
+        threads: Option<usize>,
+        stop: Arc<AtomicBool>,
+        progress: P,
+        secp: &Secp256k1<C>,
+    ) -> Result<Option<DerivedAddress>, Error>
+    where
+        T: AsRef<[u8]>,
+        P: FnMut(VanityProgress),
+        C: Signing,
+    {
+        let seed: Seed = self.seed(password)?;
+        Ok(vanity::search(
+            &seed,
+            purpose,
+            account,
+            change,
+            pattern,
+            self.network,
+            threads,
+            stop,
+            progress,
+            secp,
+        )?)
+    }
+
+    /// Search the forgotten passphrase that produces `target` and apply it
+    pub fn search_passphrase<T, P, C>(
+        &mut self,
+        password: T,
+        generator: PassphraseGenerator,
+        target: Target,
+        threads: Option<usize>,
+        progress: P,
+        secp: &Secp256k1<C>,
+    ) -> Result<Option<String>, Error>
+    where
+        T: AsRef<[u8]>,
+        P: FnMut(u64, u64),
+        C: Signing,
+    {
+        let kind: SeedKind = self.keychain(&password)?.kind();
+        let found: Option<String> =
+            passphrase_recovery::recover(kind, generator, target, self.network, threads, progress)?;
+        if let Some(passphrase) = &found {
+            self.apply_passphrase(password, Some(passphrase.clone()), secp)?;
+        }
+        Ok(found)
+    }
+
     pub fn clear_passphrase(&mut self) {
         self.encrypted_keychain.passphrase = None;
         self.encrypted_keychain.current_bip32_root_pubkey =
@@ -493,6 +883,7 @@ impl KeeChain {
     pub fn deterministic_entropy<T, C>(
         &self,
         password: T,
+        language: Language,
         word_count: WordCount,
         index: Index,
         secp: &Secp256k1<C>,
@@ -503,7 +894,84 @@ impl KeeChain {
     {
         Ok(self
             .keychain(password)?
-            .deterministic_entropy(word_count, index, secp)?)
+            .deterministic_entropy(language, word_count, index, secp)?)
+    }
+
+    /// Derive a BIP85 child mnemonic and save it as a new keychain
+    ///
+    /// The child is saved next to this keychain and records the parent fingerprint and the index.
+    pub fn derive_child_keychain<T, S, P, C>(
+        &self,
+        password: T,
+        language: Language,
+        word_count: WordCount,
+        index: Index,
+        new_name: S,
+        new_password: P,
+        secp: &Secp256k1<C>,
+    ) -> Result<Self, Error>
+    where
+        T: AsRef<[u8]>,
+        S: Into<String>,
+        P: Into<String>,
+        C: Signing,
+    {
+        let name: String = new_name.into();
+        if name.is_empty() {
+            return Err(Error::InvalidName);
+        }
+
+        let new_password: String = new_password.into();
+        if new_password.is_empty() {
+            return Err(Error::InvalidPassword);
+        }
+
+        let base_path: &Path = self.file.parent().ok_or(Error::FileNotFound)?;
+        let keychain_file: PathBuf = dir::get_keychain_file(base_path, name)?;
+        if keychain_file.exists() {
+            return Err(Error::FileAlreadyExists);
+        }
+
+        let parent: Keychain = self.keychain(password)?;
+        let mnemonic: Mnemonic = parent.deterministic_entropy(language, word_count, index, secp)?;
+        let origin = Bip85Origin::new(parent.seed.fingerprint(Network::Bitcoin, secp)?, index);
+        let keychain = Keychain::new(mnemonic, Vec::new()).with_origin(origin);
+
+        let keechain = Self::new(
+            keychain_file,
+            new_password,
+            KEECHAIN_FILE_VERSION,
+            EncryptionKeyType::Password,
+            keychain,
+            self.network,
+            secp,
+        )?;
+
+        keechain.save()?;
+
+        Ok(keechain)
+    }
+
+    /// Derive BIP85 application, formatted for display
+    pub fn bip85<T, C>(
+        &self,
+        password: T,
+        application: Application,
+        index: Index,
+        secp: &Secp256k1<C>,
+    ) -> Result<String, Error>
+    where
+        T: AsRef<[u8]>,
+        C: Signing,
+    {
+        Ok(self.keychain(password)?.bip85(application, index, secp)?)
+    }
+
+    pub fn seed_xor<T>(&self, password: T, parts: usize) -> Result<Vec<Mnemonic>, Error>
+    where
+        T: AsRef<[u8]>,
+    {
+        Ok(self.keychain(password)?.seed_xor(parts)?)
     }
 
     pub fn network(&self) -> Network {
@@ -534,11 +1002,30 @@ impl KeeChain {
         self.password_hash == Sha256Hash::hash(password)
     }
 
+    /// Registered multisig wallet the PSBT spends from and its change outputs, to show before signing
+    ///
+    /// `None` if no input spends a script (see [`psbt::verify_multisig`]).
+    pub fn review_multisig<T>(
+        &self,
+        password: T,
+        psbt: &PartiallySignedTransaction,
+    ) -> Result<Option<MultisigReview>, Error>
+    where
+        T: AsRef<[u8]>,
+    {
+        let keychain: Keychain = self.keychain(password)?;
+        Ok(psbt::verify_multisig(psbt, &keychain.multisig_wallets())?)
+    }
+
+    /// Sign PSBT
+    ///
+    /// PSBTs spending a script must spend from a registered wallet (see [`psbt::verify_multisig`]):
+    /// the registered descriptor is used instead of `descriptor`.
     pub fn sign_psbt<T, C>(
         &self,
         password: T,
         psbt: &mut PartiallySignedTransaction,
-        descriptor: Option<Descriptor<String>>,
+        descriptor: Option<SigningDescriptor>,
         custom_signers: Vec<SignerWrapper<PrivateKey>>,
         secp: &Secp256k1<C>,
     ) -> Result<bool, Error>
@@ -546,8 +1033,33 @@ impl KeeChain {
         T: AsRef<[u8]>,
         C: Signing,
     {
-        let seed: Seed = self.seed(password)?;
-        Ok(psbt.sign_custom(&seed, descriptor, custom_signers, self.network, secp)?)
+        let keychain: Keychain = self.keychain(password)?;
+        let descriptor: Option<Descriptor<String>> =
+            match psbt::verify_multisig(psbt, &keychain.multisig_wallets())? {
+                Some(review) => Some(review.wallet.signing_descriptor()?),
+                None => match descriptor {
+                    Some(descriptor) => {
+                        Some(descriptor.to_descriptor().map_err(psbt::Error::from)?)
+                    }
+                    None => None,
+                },
+            };
+        // Keys of the blinded xpubs, if the PSBT doesn't carry their blinded origin
+        let mut custom_signers: Vec<SignerWrapper<PrivateKey>> = custom_signers;
+        custom_signers.extend(psbt::blinded_signers(
+            psbt,
+            &keychain.seed(),
+            &keychain.blinded_keys(),
+            self.network,
+            secp,
+        )?);
+        Ok(psbt.sign_custom(
+            &keychain.seed(),
+            descriptor,
+            custom_signers,
+            self.network,
+            secp,
+        )?)
     }
 
     pub fn rename<S>(&mut self, new_name: S) -> Result<(), Error>
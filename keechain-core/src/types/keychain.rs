@@ -4,25 +4,55 @@
 use core::fmt;
 use core::ops::Deref;
 
-use bdk::bitcoin::secp256k1::{Secp256k1, Signing};
+use bdk::bitcoin::secp256k1::{Secp256k1, SignOnly, Signing};
 use bdk::bitcoin::Network;
+use bdk::miniscript::DescriptorPublicKey;
 use serde::de::Deserializer;
 use serde::{Deserialize, Serialize};
 use zeroize::{Zeroize, ZeroizeOnDrop};
 
-use crate::bips::bip32::{self, Bip32, ExtendedPubKey, Fingerprint};
-use crate::bips::bip39::Mnemonic;
-use crate::bips::bip85::{self, Bip85};
+use crate::bips::bip129::{self, KeyRecord, Token};
+use crate::bips::bip32::{
+    self, Bip32, DerivationPath, ExtendedPrivKey, ExtendedPubKey, Fingerprint,
+};
+use crate::bips::bip39::{Language, Mnemonic};
+use crate::bips::bip43::Purpose;
+use crate::bips::bip48::{self, ScriptType};
+use crate::bips::bip85::{self, Application, Bip85};
+use crate::blinded::{self, BlindedKey};
 use crate::crypto::{self, MultiEncryption};
-use crate::types::{Index, Secrets, Seed, WordCount};
-use crate::{descriptors, Descriptors, Result};
+use crate::descriptors::{self, DerivedAddress};
+use crate::multisig::{self, MultisigWallet};
+use crate::seeds::xor;
+use crate::types::{Index, Secrets, Seed, SeedKind, WordCount};
+use crate::{Descriptors, Result};
 
 #[derive(Debug)]
 pub enum Error {
     BIP32(bip32::Error),
     BIP85(bip85::Error),
+    BIP129(bip129::Error),
+    Blinded(blinded::Error),
     Crypto(crypto::Error),
     Descriptors(descriptors::Error),
+    Multisig(multisig::Error),
+    SeedXor(xor::Error),
+    /// Operation available only for BIP39 mnemonics
+    NotBIP39,
+    /// Operation not available for keychains with only a root xprv
+    XprvOnly,
+    /// Passphrases apply only to BIP39 and Electrum mnemonics
+    PassphraseNotSupported,
+    /// Passphrase label can't be empty
+    EmptyLabel,
+    /// Another saved passphrase already uses this label
+    DuplicateLabel(String),
+    /// Another multisig wallet already uses this name
+    DuplicateWallet(String),
+    /// No multisig wallet with this name
+    WalletNotFound(String),
+    /// Another blinded key already uses this label
+    DuplicateBlindedKey(String),
 }
 
 impl std::error::Error for Error {}
@@ -32,8 +62,26 @@ impl fmt::Display for Error {
         match self {
             Self::BIP32(e) => write!(f, "BIP32: {e}"),
             Self::BIP85(e) => write!(f, "BIP85: {e}"),
+            Self::BIP129(e) => write!(f, "BIP129: {e}"),
+            Self::Blinded(e) => write!(f, "Blinded: {e}"),
             Self::Crypto(e) => write!(f, "Crypto: {e}"),
             Self::Descriptors(e) => write!(f, "Descriptors: {e}"),
+            Self::Multisig(e) => write!(f, "Multisig: {e}"),
+            Self::SeedXor(e) => write!(f, "Seed XOR: {e}"),
+            Self::NotBIP39 => write!(f, "Not a BIP39 mnemonic"),
+            Self::XprvOnly => write!(f, "Not available for xprv-only keychains"),
+            Self::PassphraseNotSupported => write!(f, "Passphrase not supported by this seed"),
+            Self::EmptyLabel => write!(f, "Empty passphrase label"),
+            Self::DuplicateLabel(label) => {
+                write!(f, "A passphrase labeled '{label}' already exists")
+            }
+            Self::DuplicateWallet(name) => {
+                write!(f, "A multisig wallet named '{name}' already exists")
+            }
+            Self::WalletNotFound(name) => write!(f, "Multisig wallet '{name}' not found"),
+            Self::DuplicateBlindedKey(label) => {
+                write!(f, "A blinded key labeled '{label}' already exists")
+            }
         }
     }
 }
@@ -50,6 +98,18 @@ impl From<bip85::Error> for Error {
     }
 }
 
+impl From<bip129::Error> for Error {
+    fn from(e: bip129::Error) -> Self {
+        Self::BIP129(e)
+    }
+}
+
+impl From<blinded::Error> for Error {
+    fn from(e: blinded::Error) -> Self {
+        Self::Blinded(e)
+    }
+}
+
 impl From<crypto::Error> for Error {
     fn from(e: crypto::Error) -> Self {
         Self::Crypto(e)
@@ -62,6 +122,18 @@ impl From<descriptors::Error> for Error {
     }
 }
 
+impl From<multisig::Error> for Error {
+    fn from(e: multisig::Error) -> Self {
+        Self::Multisig(e)
+    }
+}
+
+impl From<xor::Error> for Error {
+    fn from(e: xor::Error) -> Self {
+        Self::SeedXor(e)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct EncryptedKeychain {
     pub(crate) master_bip32_root_pubkey: ExtendedPubKey,
@@ -117,57 +189,209 @@ impl EncryptedKeychain {
         Ok(Keychain::decrypt(password, self.raw.as_bytes())?)
     }
 
-    pub fn add_passphrase<T, S>(&mut self, password: T, passphrase: S) -> Result<(), Error>
+    pub fn add_passphrase<T, L, S, C>(
+        &mut self,
+        password: T,
+        label: L,
+        passphrase: S,
+        secp: &Secp256k1<C>,
+    ) -> Result<Passphrase, Error>
     where
         T: AsRef<[u8]>,
+        L: Into<String>,
         S: Into<String>,
+        C: Signing,
+    {
+        let mut keychain: Keychain = self.keychain(&password)?;
+        let passphrase: Passphrase = keychain.add_passphrase(label, passphrase, secp)?;
+        self.raw = keychain.encrypt(password)?;
+        Ok(passphrase)
+    }
+
+    pub fn remove_passphrase<T, S>(&mut self, password: T, label: S) -> Result<(), Error>
+    where
+        T: AsRef<[u8]>,
+        S: Into<String>,
+    {
+        let mut keychain: Keychain = self.keychain(&password)?;
+        keychain.remove_passphrase(label);
+        self.raw = keychain.encrypt(password)?;
+        Ok(())
+    }
+
+    pub fn add_multisig_wallet<T>(
+        &mut self,
+        password: T,
+        wallet: MultisigWallet,
+    ) -> Result<(), Error>
+    where
+        T: AsRef<[u8]>,
     {
         let mut keychain: Keychain = self.keychain(&password)?;
-        keychain.add_passphrase(passphrase);
+        keychain.add_multisig_wallet(wallet)?;
         self.raw = keychain.encrypt(password)?;
         Ok(())
     }
 
-    pub fn remove_passphrase<T, S>(&mut self, password: T, passphrase: S) -> Result<(), Error>
+    pub fn remove_multisig_wallet<T, S>(&mut self, password: T, name: S) -> Result<(), Error>
     where
         T: AsRef<[u8]>,
         S: Into<String>,
     {
         let mut keychain: Keychain = self.keychain(&password)?;
-        keychain.remove_passphrase(passphrase);
+        keychain.remove_multisig_wallet(name);
+        self.raw = keychain.encrypt(password)?;
+        Ok(())
+    }
+
+    pub fn add_blinded_key<T>(&mut self, password: T, key: BlindedKey) -> Result<(), Error>
+    where
+        T: AsRef<[u8]>,
+    {
+        let mut keychain: Keychain = self.keychain(&password)?;
+        keychain.add_blinded_key(key)?;
         self.raw = keychain.encrypt(password)?;
         Ok(())
     }
 
+    /// Apply passphrase
+    ///
+    /// Return the saved passphrase that produces the same fingerprint (`None` if it's unknown).
     pub fn apply_passphrase<T, S, C>(
         &mut self,
         password: T,
         passphrase: Option<S>,
         secp: &Secp256k1<C>,
-    ) -> Result<(), Error>
+    ) -> Result<Option<Passphrase>, Error>
     where
         T: AsRef<[u8]>,
         S: Into<String>,
         C: Signing,
     {
         let mut keychain: Keychain = self.keychain(&password)?;
-        keychain.apply_passphrase(passphrase);
+        keychain.apply_passphrase(passphrase)?;
         self.passphrase = keychain.seed.passphrase();
         self.current_bip32_root_pubkey = keychain.seed.to_bip32_root_pubkey(self.network, secp)?;
-        Ok(())
+        Ok(keychain.find_passphrase(self.fingerprint()))
+    }
+}
+
+/// Saved passphrase
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Zeroize, ZeroizeOnDrop)]
+pub struct Passphrase {
+    label: String,
+    passphrase: String,
+    /// Root fingerprint produced by the passphrase
+    #[zeroize(skip)]
+    fingerprint: Fingerprint,
+}
+
+impl fmt::Debug for Passphrase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({})", self.label, self.fingerprint)
+    }
+}
+
+impl Passphrase {
+    pub fn new<L, S, C>(
+        kind: &SeedKind,
+        label: L,
+        passphrase: S,
+        secp: &Secp256k1<C>,
+    ) -> Result<Self, Error>
+    where
+        L: Into<String>,
+        S: Into<String>,
+        C: Signing,
+    {
+        if !kind.supports_passphrase() {
+            return Err(Error::PassphraseNotSupported);
+        }
+
+        let passphrase: String = passphrase.into();
+        let seed = Seed::with_kind(kind.clone(), Some(passphrase.clone()));
+        Ok(Self {
+            label: label.into(),
+            passphrase,
+            // Root fingerprint doesn't depend on the network
+            fingerprint: seed.fingerprint(Network::Bitcoin, secp)?,
+        })
+    }
+
+    pub fn label(&self) -> String {
+        self.label.clone()
+    }
+
+    pub fn passphrase(&self) -> String {
+        self.passphrase.clone()
+    }
+
+    pub fn fingerprint(&self) -> Fingerprint {
+        self.fingerprint
+    }
+}
+
+/// Keychains created before passphrase labels saved only the passphrase
+#[derive(Serialize, Deserialize, Zeroize, ZeroizeOnDrop)]
+#[serde(untagged)]
+enum PassphraseSerde {
+    Labeled(Passphrase),
+    Legacy(String),
+}
+
+/// Parent of a keychain derived with BIP85
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Bip85Origin {
+    parent_fingerprint: Fingerprint,
+    index: u32,
+}
+
+impl Bip85Origin {
+    pub fn new(parent_fingerprint: Fingerprint, index: Index) -> Self {
+        Self {
+            parent_fingerprint,
+            index: index.as_u32(),
+        }
+    }
+
+    /// Root fingerprint of the parent keychain
+    pub fn parent_fingerprint(&self) -> Fingerprint {
+        self.parent_fingerprint
+    }
+
+    /// BIP85 index
+    pub fn index(&self) -> u32 {
+        self.index
     }
 }
 
 #[derive(Serialize, Deserialize, Zeroize, ZeroizeOnDrop)]
 struct KeychainIntermediate {
-    mnemonic: Mnemonic,
-    passphrases: Vec<String>,
+    mnemonic: SeedKind,
+    passphrases: Vec<PassphraseSerde>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[zeroize(skip)]
+    origin: Option<Bip85Origin>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[zeroize(skip)]
+    wallets: Vec<MultisigWallet>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[zeroize(skip)]
+    blinded_keys: Vec<BlindedKey>,
 }
 
 #[derive(Clone, Zeroize, ZeroizeOnDrop)]
 pub struct Keychain {
-    mnemonic: Mnemonic,
-    passphrases: Vec<String>,
+    kind: SeedKind,
+    passphrases: Vec<Passphrase>,
+    #[zeroize(skip)]
+    origin: Option<Bip85Origin>,
+    /// Multisig wallets
+    #[zeroize(skip)]
+    wallets: Vec<MultisigWallet>,
+    /// Blinded xpubs shared with cosigners
+    #[zeroize(skip)]
+    blinded_keys: Vec<BlindedKey>,
     pub seed: Seed,
 }
 
@@ -190,8 +414,16 @@ impl Serialize for Keychain {
         S: serde::Serializer,
     {
         let intermediate = KeychainIntermediate {
-            mnemonic: self.mnemonic.clone(),
-            passphrases: self.passphrases.clone(),
+            mnemonic: self.kind.clone(),
+            passphrases: self
+                .passphrases
+                .iter()
+                .cloned()
+                .map(PassphraseSerde::Labeled)
+                .collect(),
+            origin: self.origin,
+            wallets: self.wallets.clone(),
+            blinded_keys: self.blinded_keys.clone(),
         };
         intermediate.serialize(serializer)
     }
@@ -203,36 +435,92 @@ impl<'de> Deserialize<'de> for Keychain {
         D: Deserializer<'de>,
     {
         let intermediate = KeychainIntermediate::deserialize(deserializer)?;
-        Ok(Self::new(
-            intermediate.mnemonic.clone(),
-            intermediate.passphrases.clone(),
-        ))
+        let kind: SeedKind = intermediate.mnemonic.clone();
+        let mut passphrases: Vec<Passphrase> = Vec::with_capacity(intermediate.passphrases.len());
+        let mut secp: Option<Secp256k1<SignOnly>> = None;
+        for passphrase in intermediate.passphrases.iter() {
+            match passphrase {
+                PassphraseSerde::Labeled(passphrase) => passphrases.push(passphrase.clone()),
+                PassphraseSerde::Legacy(passphrase) => {
+                    // Label legacy passphrases with their position
+                    let label: String = format!("Passphrase #{}", passphrases.len() + 1);
+                    let ctx: &Secp256k1<SignOnly> =
+                        secp.get_or_insert_with(Secp256k1::signing_only);
+                    passphrases.push(
+                        Passphrase::new(&kind, label, passphrase, ctx)
+                            .map_err(<D::Error as serde::de::Error>::custom)?,
+                    );
+                }
+            }
+        }
+        let mut keychain = Self::new(kind, passphrases);
+        keychain.wallets = intermediate.wallets.clone();
+        keychain.blinded_keys = intermediate.blinded_keys.clone();
+        Ok(match intermediate.origin {
+            Some(origin) => keychain.with_origin(origin),
+            None => keychain,
+        })
     }
 }
 
 impl Keychain {
-    pub fn new(mnemonic: Mnemonic, passphrases: Vec<String>) -> Self {
+    pub fn new<K>(kind: K, passphrases: Vec<Passphrase>) -> Self
+    where
+        K: Into<SeedKind>,
+    {
+        let kind: SeedKind = kind.into();
         Self {
-            mnemonic: mnemonic.clone(),
+            kind: kind.clone(),
             passphrases,
-            seed: Seed::from_mnemonic(mnemonic),
+            origin: None,
+            wallets: Vec::new(),
+            blinded_keys: Vec::new(),
+            seed: Seed::with_kind::<String>(kind, None),
         }
     }
 
-    pub fn mnemonic(&self) -> Mnemonic {
-        self.mnemonic.clone()
+    /// Set the BIP85 parent
+    pub fn with_origin(mut self, origin: Bip85Origin) -> Self {
+        self.origin = Some(origin);
+        self
+    }
+
+    /// BIP85 parent (`None` if the keychain wasn't derived from another one)
+    pub fn origin(&self) -> Option<Bip85Origin> {
+        self.origin
     }
 
-    pub fn passphrases(&self) -> Vec<String> {
+    pub fn kind(&self) -> SeedKind {
+        self.kind.clone()
+    }
+
+    /// BIP39 mnemonic (`None` for other seed kinds)
+    pub fn mnemonic(&self) -> Option<Mnemonic> {
+        match &self.kind {
+            SeedKind::BIP39(mnemonic) => Some(mnemonic.clone()),
+            _ => None,
+        }
+    }
+
+    pub fn passphrases(&self) -> Vec<Passphrase> {
         self.passphrases.clone()
     }
 
+    /// Find the saved passphrase that produces `fingerprint`
+    pub fn find_passphrase(&self, fingerprint: Fingerprint) -> Option<Passphrase> {
+        self.passphrases
+            .iter()
+            .find(|p| p.fingerprint == fingerprint)
+            .cloned()
+    }
+
     pub fn seed(&self) -> Seed {
         self.seed.clone()
     }
 
     pub fn deterministic_entropy<C>(
         &self,
+        language: Language,
         word_count: WordCount,
         index: Index,
         secp: &Secp256k1<C>,
@@ -240,7 +528,36 @@ impl Keychain {
     where
         C: Signing,
     {
-        Ok(self.seed.derive_bip85_mnemonic(word_count, index, secp)?)
+        if let SeedKind::Xprv(..) = self.kind {
+            return Err(Error::XprvOnly);
+        }
+        Ok(self
+            .seed
+            .derive_bip85_mnemonic(language, word_count, index, secp)?)
+    }
+
+    /// Derive BIP85 application, formatted for display
+    pub fn bip85<C>(
+        &self,
+        application: Application,
+        index: Index,
+        secp: &Secp256k1<C>,
+    ) -> Result<String, Error>
+    where
+        C: Signing,
+    {
+        if let SeedKind::Xprv(..) = self.kind {
+            return Err(Error::XprvOnly);
+        }
+        Ok(self.seed.derive_bip85(application, index, secp)?)
+    }
+
+    /// Split the mnemonic into Seed XOR parts
+    pub fn seed_xor(&self, parts: usize) -> Result<Vec<Mnemonic>, Error> {
+        match self.mnemonic() {
+            Some(mnemonic) => Ok(xor::split(&mnemonic, parts)?),
+            None => Err(Error::NotBIP39),
+        }
     }
 
     pub fn descriptors<C>(
@@ -255,6 +572,163 @@ impl Keychain {
         Ok(Descriptors::new(&self.seed, network, account, secp)?)
     }
 
+    /// BIP48 account key (with origin) to share with the cosigners
+    pub fn multisig_key<C>(
+        &self,
+        network: Network,
+        account: Option<u32>,
+        script: ScriptType,
+        secp: &Secp256k1<C>,
+    ) -> Result<DescriptorPublicKey, Error>
+    where
+        C: Signing,
+    {
+        Ok(self
+            .descriptors(network, account, secp)?
+            .multisig_key(script)?)
+    }
+
+    /// BIP129 key record of the BIP48 account key, signed with it
+    pub fn bsms_key_record<S, C>(
+        &self,
+        network: Network,
+        account: Option<u32>,
+        script: ScriptType,
+        token: Token,
+        description: S,
+        secp: &Secp256k1<C>,
+    ) -> Result<KeyRecord, Error>
+    where
+        S: Into<String>,
+        C: Signing,
+    {
+        let root: ExtendedPrivKey = self.seed.to_bip32_root_key(network)?;
+        let path: DerivationPath = bip48::account_extended_path(network, account, script)?;
+        Ok(KeyRecord::new(token, description, &root, &path, secp)?)
+    }
+
+    /// Extended public key (with origin) at any derivation path
+    pub fn extended_public_key<C>(
+        &self,
+        network: Network,
+        path: &DerivationPath,
+        secp: &Secp256k1<C>,
+    ) -> Result<DescriptorPublicKey, Error>
+    where
+        C: Signing,
+    {
+        let root: ExtendedPrivKey = self.seed.to_bip32_root_key(network)?;
+        let derived_private_key: ExtendedPrivKey = root.derive_priv(secp, path)?;
+        let derived_public_key: ExtendedPubKey =
+            ExtendedPubKey::from_priv(secp, &derived_private_key);
+        Ok(descriptors::key_expression(
+            root.fingerprint(secp),
+            derived_public_key,
+            path,
+        )?)
+    }
+
+    /// Extended private key at any derivation path
+    pub fn extended_private_key<C>(
+        &self,
+        network: Network,
+        path: &DerivationPath,
+        secp: &Secp256k1<C>,
+    ) -> Result<ExtendedPrivKey, Error>
+    where
+        C: Signing,
+    {
+        let root: ExtendedPrivKey = self.seed.to_bip32_root_key(network)?;
+        Ok(root.derive_priv(secp, path)?)
+    }
+
+    pub fn multisig_wallets(&self) -> Vec<MultisigWallet> {
+        self.wallets.clone()
+    }
+
+    pub fn get_multisig_wallet<S>(&self, name: S) -> Option<MultisigWallet>
+    where
+        S: Into<String>,
+    {
+        let name: String = name.into();
+        self.wallets.iter().find(|w| w.name() == name).cloned()
+    }
+
+    pub fn blinded_keys(&self) -> Vec<BlindedKey> {
+        self.blinded_keys.clone()
+    }
+
+    pub fn get_blinded_key<S>(&self, label: S) -> Option<BlindedKey>
+    where
+        S: Into<String>,
+    {
+        let label: String = label.into();
+        self.blinded_keys
+            .iter()
+            .find(|key| key.label() == label)
+            .cloned()
+    }
+
+    /// Blind the BIP48 account key with a random blinding path
+    pub fn blind_multisig_key<S, C>(
+        &self,
+        network: Network,
+        account: Option<u32>,
+        script: ScriptType,
+        label: S,
+        secp: &Secp256k1<C>,
+    ) -> Result<BlindedKey, Error>
+    where
+        S: Into<String>,
+        C: Signing,
+    {
+        let root: ExtendedPrivKey = self.seed.to_bip32_root_key(network)?;
+        let path: DerivationPath = bip48::account_extended_path(network, account, script)?;
+        let blinding: DerivationPath = blinded::random_blinding_path();
+        Ok(BlindedKey::new(label, &root, &path, &blinding, secp)?)
+    }
+
+    /// Derive the addresses of a single-sig account
+    pub fn addresses<C>(
+        &self,
+        network: Network,
+        purpose: Purpose,
+        account: Option<u32>,
+        change: bool,
+        from: u32,
+        count: u32,
+        secp: &Secp256k1<C>,
+    ) -> Result<Vec<DerivedAddress>, Error>
+    where
+        C: Signing,
+    {
+        Ok(self
+            .descriptors(network, account, secp)?
+            .addresses(purpose, change, from, count, network)?)
+    }
+
+    /// Derive the addresses of a saved multisig wallet, with the derivation path of our key
+    pub fn multisig_addresses<S, C>(
+        &self,
+        network: Network,
+        name: S,
+        change: bool,
+        from: u32,
+        count: u32,
+        secp: &Secp256k1<C>,
+    ) -> Result<Vec<DerivedAddress>, Error>
+    where
+        S: Into<String>,
+        C: Signing,
+    {
+        let name: String = name.into();
+        let wallet: MultisigWallet = self
+            .get_multisig_wallet(name.clone())
+            .ok_or(Error::WalletNotFound(name))?;
+        let fingerprint: Fingerprint = self.seed.fingerprint(network, secp)?;
+        Ok(wallet.addresses(fingerprint, change, from, count, network)?)
+    }
+
     pub fn secrets<C>(&self, network: Network, secp: &Secp256k1<C>) -> Result<Secrets, Error>
     where
         C: Signing,
@@ -262,36 +736,294 @@ impl Keychain {
         Ok(Secrets::new(&self.seed, network, secp)?)
     }
 
-    pub(crate) fn add_passphrase<S>(&mut self, passphrase: S)
+    /// Save passphrase (an already saved passphrase is relabeled)
+    pub(crate) fn add_passphrase<L, S, C>(
+        &mut self,
+        label: L,
+        passphrase: S,
+        secp: &Secp256k1<C>,
+    ) -> Result<Passphrase, Error>
     where
+        L: Into<String>,
         S: Into<String>,
+        C: Signing,
     {
+        if !self.kind.supports_passphrase() {
+            return Err(Error::PassphraseNotSupported);
+        }
+
+        let label: String = label.into();
+        if label.trim().is_empty() {
+            return Err(Error::EmptyLabel);
+        }
+
         let passphrase: String = passphrase.into();
-        if !self.passphrases.contains(&passphrase) {
-            self.passphrases.push(passphrase);
+        if self
+            .passphrases
+            .iter()
+            .any(|p| p.label == label && p.passphrase != passphrase)
+        {
+            return Err(Error::DuplicateLabel(label));
+        }
+
+        let new: Passphrase = Passphrase::new(&self.kind, label, passphrase, secp)?;
+        match self
+            .passphrases
+            .iter_mut()
+            .find(|p| p.passphrase == new.passphrase)
+        {
+            Some(saved) => saved.label = new.label.clone(),
+            None => self.passphrases.push(new.clone()),
         }
+
+        Ok(new)
     }
 
-    pub(crate) fn remove_passphrase<S>(&mut self, passphrase: S)
+    pub(crate) fn remove_passphrase<S>(&mut self, label: S)
     where
         S: Into<String>,
     {
-        let passphrase = passphrase.into();
-        if let Some(index) = self.passphrases.iter().position(|p| p == &passphrase) {
+        let label: String = label.into();
+        if let Some(index) = self.passphrases.iter().position(|p| p.label == label) {
             self.passphrases.remove(index);
         }
     }
 
-    pub fn get_passphrase(&self, index: usize) -> Option<String> {
+    pub(crate) fn add_multisig_wallet(&mut self, wallet: MultisigWallet) -> Result<(), Error> {
+        if self.wallets.iter().any(|w| w.name() == wallet.name()) {
+            return Err(Error::DuplicateWallet(wallet.name()));
+        }
+        self.wallets.push(wallet);
+        Ok(())
+    }
+
+    pub(crate) fn remove_multisig_wallet<S>(&mut self, name: S)
+    where
+        S: Into<String>,
+    {
+        let name: String = name.into();
+        self.wallets.retain(|w| w.name() != name);
+    }
+
+    pub(crate) fn add_blinded_key(&mut self, key: BlindedKey) -> Result<(), Error> {
+        if self.blinded_keys.iter().any(|k| k.label() == key.label()) {
+            return Err(Error::DuplicateBlindedKey(key.label()));
+        }
+        self.blinded_keys.push(key);
+        Ok(())
+    }
+
+    pub fn get_passphrase(&self, index: usize) -> Option<Passphrase> {
         self.passphrases.get(index).cloned()
     }
 
-    pub(crate) fn apply_passphrase<S>(&mut self, passphrase: Option<S>)
+    pub(crate) fn apply_passphrase<S>(&mut self, passphrase: Option<S>) -> Result<(), Error>
     where
         S: Into<String>,
     {
-        self.seed = Seed::new(self.mnemonic.clone(), passphrase);
+        if passphrase.is_some() && !self.kind.supports_passphrase() {
+            return Err(Error::PassphraseNotSupported);
+        }
+        self.seed = Seed::with_kind(self.kind.clone(), passphrase);
+        Ok(())
     }
 }
 
 impl MultiEncryption for Keychain {}
+
+#[cfg(test)]
+mod tests {
+    use core::str::FromStr;
+
+    use super::*;
+
+    const MNEMONIC: &str = "easy uncover favorite crystal bless differ energy seat ecology match carry group refuse together chat observe hidden glad brave month diesel sustain depth salt";
+
+    #[test]
+    fn test_passphrases() {
+        let secp = Secp256k1::new();
+        let mnemonic = Mnemonic::from_str(MNEMONIC).unwrap();
+        let mut keychain = Keychain::new(mnemonic.clone(), Vec::new());
+
+        let saved = keychain
+            .add_passphrase("Main", "mypassphrase", &secp)
+            .unwrap();
+        let expected = Seed::new(mnemonic, Some("mypassphrase"))
+            .fingerprint(Network::Bitcoin, &secp)
+            .unwrap();
+        assert_eq!(saved.fingerprint(), expected);
+        assert_eq!(keychain.find_passphrase(expected), Some(saved));
+
+        // Relabel
+        keychain
+            .add_passphrase("Savings", "mypassphrase", &secp)
+            .unwrap();
+        assert_eq!(keychain.passphrases().len(), 1);
+        assert_eq!(keychain.passphrases()[0].label(), "Savings");
+
+        assert!(matches!(
+            keychain.add_passphrase("Savings", "other", &secp),
+            Err(Error::DuplicateLabel(_))
+        ));
+        assert!(matches!(
+            keychain.add_passphrase(" ", "other", &secp),
+            Err(Error::EmptyLabel)
+        ));
+
+        keychain.remove_passphrase("Savings");
+        assert!(keychain.passphrases().is_empty());
+    }
+
+    #[test]
+    fn test_passphrase_not_supported() {
+        let secp = Secp256k1::new();
+        let kind = SeedKind::from_raw_seed([7u8; 32]).unwrap();
+        assert!(matches!(
+            Passphrase::new(&kind, "Main", "mypassphrase", &secp),
+            Err(Error::PassphraseNotSupported)
+        ));
+
+        let mut keychain = Keychain::new(kind, Vec::new());
+        assert!(matches!(
+            keychain.add_passphrase("Main", "mypassphrase", &secp),
+            Err(Error::PassphraseNotSupported)
+        ));
+        assert!(keychain.passphrases().is_empty());
+        assert!(matches!(
+            keychain.apply_passphrase(Some("mypassphrase")),
+            Err(Error::PassphraseNotSupported)
+        ));
+        assert!(keychain.apply_passphrase::<&str>(None).is_ok());
+    }
+
+    #[test]
+    fn test_custom_path_keys() {
+        let secp = Secp256k1::new();
+        let mnemonic = Mnemonic::from_str("abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about").unwrap();
+        let keychain = Keychain::new(mnemonic, Vec::new());
+
+        let path = DerivationPath::from_str("m/45'/0'/7'").unwrap();
+        let key = keychain
+            .extended_public_key(Network::Bitcoin, &path, &secp)
+            .unwrap();
+        assert_eq!(key.to_string(), "[73c5da0a/45'/0'/7']xpub6Cih91nLw1tZRMGDCtyH5ryUmQnovGbXTeis3R6Dus3g8PvgkK9NdTbuGYJNj2opxmCqh51GgtzBWwqAbDD9ExPbafkH5oQWg78Yufz5gM8");
+
+        let path = DerivationPath::from_str("m/0'").unwrap();
+        let key = keychain
+            .extended_public_key(Network::Bitcoin, &path, &secp)
+            .unwrap();
+        assert_eq!(key.to_string(), "[73c5da0a/0']xpub68jrRzQopSUQm76hJ6TNtiJMJfhj38u1X12xCzExrw388hcN443UVnYpswdUkV7vPJ3KayiCdp3Q5E23s4wvkucohVTh7eSstJdBFyn2DMx");
+
+        let path = DerivationPath::from_str("m/44'/0'/0'/0/0").unwrap();
+        let xprv = keychain
+            .extended_private_key(Network::Bitcoin, &path, &secp)
+            .unwrap();
+        assert_eq!(xprv.to_string(), "xprvA2cWYEXRrpaYZmR4Mat3aHw7ARSGFAtb5LQNfSuyQCCGVJXRNWA3zkkHZcBM4voi9TBrb9WaC65HGv5e8gZgfnjzH71WofaXT3haLw8LYqQ");
+        assert_eq!(
+            xprv.to_priv().to_wif(),
+            "L4p2b9VAf8k5aUahF1JCJUzZkgNEAqLfq8DDdQiyAprQAKSbu8hf"
+        );
+    }
+
+    #[test]
+    fn test_legacy_passphrases() {
+        let secp = Secp256k1::new();
+        let json = format!(r#"{{"mnemonic":"{MNEMONIC}","passphrases":["mypassphrase"]}}"#);
+        let keychain: Keychain = serde_json::from_str(&json).unwrap();
+        let passphrases: Vec<Passphrase> = keychain.passphrases();
+        assert_eq!(passphrases.len(), 1);
+        assert_eq!(passphrases[0].label(), "Passphrase #1");
+        assert_eq!(passphrases[0].passphrase(), "mypassphrase");
+
+        let mnemonic = Mnemonic::from_str(MNEMONIC).unwrap();
+        let expected = Seed::new(mnemonic, Some("mypassphrase"))
+            .fingerprint(Network::Bitcoin, &secp)
+            .unwrap();
+        assert_eq!(passphrases[0].fingerprint(), expected);
+
+        // Round trip
+        let json: String = serde_json::to_string(&keychain).unwrap();
+        let decoded: Keychain = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded.passphrases(), passphrases);
+    }
+
+    #[test]
+    fn test_origin() {
+        let mnemonic = Mnemonic::from_str(MNEMONIC).unwrap();
+        let keychain = Keychain::new(mnemonic.clone(), Vec::new());
+        assert_eq!(keychain.origin(), None);
+        let json: String = serde_json::to_string(&keychain).unwrap();
+        assert!(!json.contains("origin"));
+
+        let origin = Bip85Origin::new(
+            Fingerprint::from_str("73c5da0a").unwrap(),
+            Index::new(7).unwrap(),
+        );
+        let keychain = Keychain::new(mnemonic, Vec::new()).with_origin(origin);
+        let json: String = serde_json::to_string(&keychain).unwrap();
+        let decoded: Keychain = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded.origin(), Some(origin));
+        assert_eq!(decoded.origin().unwrap().index(), 7);
+    }
+
+    #[test]
+    fn test_multisig_wallets() {
+        let secp = Secp256k1::new();
+        let mnemonic = Mnemonic::from_str(MNEMONIC).unwrap();
+        let mut keychain = Keychain::new(mnemonic, Vec::new());
+        let key = keychain
+            .multisig_key(Network::Testnet, None, ScriptType::P2WSH, &secp)
+            .unwrap();
+        let cosigner = multisig::Cosigner::from_str("[0f056943/48'/1'/0'/2']tpubDF2rnouQaaYrXF4noGTv6rQYmx87cQ4GrUdhpvXkhtChwQPbdGTi8GA88NUaSrwZBwNsTkC9bFkkC8vDyGBVVAQTZ2AS6gs68RQXtXcCvkP").unwrap();
+        let wallet = MultisigWallet::from_cosigners(
+            "Vault",
+            ScriptType::P2WSH,
+            2,
+            key,
+            &[cosigner],
+            Network::Testnet,
+        )
+        .unwrap();
+
+        keychain.add_multisig_wallet(wallet.clone()).unwrap();
+        assert!(matches!(
+            keychain.add_multisig_wallet(wallet.clone()),
+            Err(Error::DuplicateWallet(..))
+        ));
+
+        let addresses = keychain
+            .multisig_addresses(Network::Testnet, "Vault", false, 0, 2, &secp)
+            .unwrap();
+        assert_eq!(addresses.len(), 2);
+        assert_eq!(addresses[1].path.to_string(), "m/48'/1'/0'/2'/0/1");
+        assert!(matches!(
+            keychain.multisig_addresses(Network::Testnet, "Other", false, 0, 2, &secp),
+            Err(Error::WalletNotFound(..))
+        ));
+
+        let json: String = serde_json::to_string(&keychain).unwrap();
+        let mut decoded: Keychain = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded.get_multisig_wallet("Vault"), Some(wallet));
+
+        decoded.remove_multisig_wallet("Vault");
+        assert!(decoded.multisig_wallets().is_empty());
+    }
+
+    #[test]
+    fn test_xprv_only() {
+        let secp = Secp256k1::new();
+        let kind = SeedKind::from_str("xprv9s21ZrQH143K3QTDL4LXw2F7HEK3wJUD2nW2nRk4stbPy6cq3jPPqjiChkVvvNKmPGJxWUtg6LnF5kejMRNNU3TGtRBeJgk33yuGBxrMPHi").unwrap();
+        let keychain = Keychain::new(kind, Vec::new());
+        let index = Index::new(0).unwrap();
+        assert!(matches!(
+            keychain.deterministic_entropy(Language::English, WordCount::W12, index, &secp),
+            Err(Error::XprvOnly)
+        ));
+        assert!(matches!(keychain.seed_xor(3), Err(Error::NotBIP39)));
+        assert!(keychain.descriptors(Network::Bitcoin, None, &secp).is_ok());
+
+        let json: String = serde_json::to_string(&keychain).unwrap();
+        let decoded: Keychain = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded.kind(), keychain.kind());
+    }
+}
@@ -7,15 +7,16 @@ use std::str::FromStr;
 
 use bdk::bitcoin::secp256k1::{Secp256k1, Signing};
 use bdk::bitcoin::Network;
-use bip39::Mnemonic;
 
 pub mod keechain;
 pub mod keychain;
 pub mod seed;
+pub mod watch_only;
 
 pub use self::keechain::KeeChain;
-pub use self::keychain::{EncryptedKeychain, Keychain};
-pub use self::seed::Seed;
+pub use self::keychain::{Bip85Origin, EncryptedKeychain, Keychain, Passphrase};
+pub use self::seed::{RootXprv, Seed, SeedKind};
+pub use self::watch_only::WatchOnly;
 use crate::bips::bip32::{self, Bip32, ExtendedPrivKey, Fingerprint};
 use crate::util::hex;
 
@@ -111,10 +112,12 @@ impl fmt::Debug for Index {
 
 #[derive(Clone)]
 pub struct Secrets {
-    pub entropy: String,
-    pub mnemonic: Mnemonic,
+    /// BIP39 entropy (`None` for other seed kinds)
+    pub entropy: Option<String>,
+    pub mnemonic: SeedKind,
     pub passphrase: Option<String>,
-    pub seed_hex: String,
+    /// BIP32 seed (`None` for xprv keys)
+    pub seed_hex: Option<String>,
     pub network: Network,
     pub root_key: ExtendedPrivKey,
     pub fingerprint: Fingerprint,
@@ -131,12 +134,11 @@ impl Secrets {
     where
         C: Signing,
     {
-        let mnemonic: Mnemonic = seed.mnemonic();
         let root_key: ExtendedPrivKey = seed.to_bip32_root_key(network)?;
 
         Ok(Self {
-            entropy: hex::encode(mnemonic.to_entropy()),
-            mnemonic,
+            entropy: seed.mnemonic().map(|m| hex::encode(m.to_entropy())),
+            mnemonic: seed.kind(),
             passphrase: seed.passphrase(),
             seed_hex: seed.to_hex(),
             network,
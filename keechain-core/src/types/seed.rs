@@ -2,6 +2,7 @@
 // Distributed under the MIT software license
 
 use core::fmt;
+use core::str::FromStr;
 
 use bdk::bitcoin::Network;
 use bip39::Mnemonic;
@@ -11,11 +12,253 @@ use zeroize::{Zeroize, ZeroizeOnDrop};
 use crate::bips::bip32::{self, Bip32, ExtendedPrivKey};
 use crate::bips::bip85::Bip85;
 use crate::descriptors::ToDescriptor;
+use crate::seeds::aezeed::CipherSeed;
+use crate::seeds::electrum::ElectrumMnemonic;
 use crate::util::hex;
 
+/// BIP32 seed length range (bytes)
+const RAW_SEED_MIN_LEN: usize = 16;
+const RAW_SEED_MAX_LEN: usize = 64;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Error {
+    BIP32(bip32::Error),
+    Hex(hex::Error),
+    /// BIP32 seed must be between 16 and 64 bytes
+    InvalidSeedLength(usize),
+    /// Extended private key isn't a root key (depth 0)
+    NotRootKey,
+}
+
+impl std::error::Error for Error {}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::BIP32(e) => write!(f, "BIP32: {e}"),
+            Self::Hex(e) => write!(f, "Hex: {e}"),
+            Self::InvalidSeedLength(len) => write!(
+                f,
+                "Invalid seed length: {len} bytes (must be between {RAW_SEED_MIN_LEN} and {RAW_SEED_MAX_LEN})"
+            ),
+            Self::NotRootKey => write!(f, "Not a root extended private key (depth must be 0)"),
+        }
+    }
+}
+
+impl From<bip32::Error> for Error {
+    fn from(e: bip32::Error) -> Self {
+        Self::BIP32(e)
+    }
+}
+
+impl From<hex::Error> for Error {
+    fn from(e: hex::Error) -> Self {
+        Self::Hex(e)
+    }
+}
+
+/// Seed kind
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Zeroize, ZeroizeOnDrop)]
+#[serde(into = "SeedKindSerde", try_from = "SeedKindSerde")]
+pub enum SeedKind {
+    /// BIP39 mnemonic
+    BIP39(Mnemonic),
+    /// Electrum mnemonic (standard or segwit)
+    Electrum(ElectrumMnemonic),
+    /// LND aezeed (already deciphered: the BIP39 passphrases don't apply)
+    Aezeed(CipherSeed),
+    /// BIP32 seed (the BIP39 passphrases don't apply)
+    ///
+    /// Use [`SeedKind::from_raw_seed`] to check the length.
+    RawSeed(Vec<u8>),
+    /// BIP32 root extended private key (the BIP39 passphrases don't apply)
+    Xprv(RootXprv),
+}
+
+impl fmt::Debug for SeedKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<sensitive>")
+    }
+}
+
+impl fmt::Display for SeedKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::BIP39(mnemonic) => write!(f, "{mnemonic}"),
+            Self::Electrum(mnemonic) => write!(f, "{mnemonic}"),
+            Self::Aezeed(seed) => write!(f, "{}", seed.mnemonic()),
+            Self::RawSeed(seed) => write!(f, "{}", hex::encode(seed)),
+            Self::Xprv(xprv) => write!(f, "{xprv}"),
+        }
+    }
+}
+
+impl From<Mnemonic> for SeedKind {
+    fn from(mnemonic: Mnemonic) -> Self {
+        Self::BIP39(mnemonic)
+    }
+}
+
+impl From<ElectrumMnemonic> for SeedKind {
+    fn from(mnemonic: ElectrumMnemonic) -> Self {
+        Self::Electrum(mnemonic)
+    }
+}
+
+impl From<CipherSeed> for SeedKind {
+    fn from(seed: CipherSeed) -> Self {
+        Self::Aezeed(seed)
+    }
+}
+
+impl SeedKind {
+    /// BIP32 seed (between 16 and 64 bytes)
+    pub fn from_raw_seed<T>(seed: T) -> Result<Self, Error>
+    where
+        T: AsRef<[u8]>,
+    {
+        let seed: &[u8] = seed.as_ref();
+        if (RAW_SEED_MIN_LEN..=RAW_SEED_MAX_LEN).contains(&seed.len()) {
+            Ok(Self::RawSeed(seed.to_vec()))
+        } else {
+            Err(Error::InvalidSeedLength(seed.len()))
+        }
+    }
+
+    /// BIP32 seed, hex encoded
+    pub fn from_raw_seed_hex<S>(seed: S) -> Result<Self, Error>
+    where
+        S: AsRef<str>,
+    {
+        Self::from_raw_seed(hex::decode(seed.as_ref().trim())?)
+    }
+
+    /// BIP32 root extended private key
+    pub fn from_xprv(xprv: ExtendedPrivKey) -> Result<Self, Error> {
+        Ok(Self::Xprv(RootXprv::new(xprv)?))
+    }
+
+    pub fn name(&self) -> String {
+        match self {
+            Self::BIP39(..) => String::from("BIP39"),
+            Self::Electrum(mnemonic) => format!("Electrum {}", mnemonic.version()),
+            Self::Aezeed(..) => String::from("aezeed"),
+            Self::RawSeed(..) => String::from("BIP32 seed"),
+            Self::Xprv(..) => String::from("xprv"),
+        }
+    }
+
+    /// Mnemonic based seed kind (BIP39, Electrum or aezeed)
+    pub fn is_mnemonic(&self) -> bool {
+        !matches!(self, Self::RawSeed(..) | Self::Xprv(..))
+    }
+
+    /// Seed kind that mixes a passphrase into the BIP32 seed (BIP39 or Electrum)
+    pub fn supports_passphrase(&self) -> bool {
+        matches!(self, Self::BIP39(..) | Self::Electrum(..))
+    }
+}
+
+/// BIP32 root extended private key (depth 0), kept base58 encoded to be zeroized
+#[derive(Clone, PartialEq, Eq, Zeroize, ZeroizeOnDrop)]
+pub struct RootXprv(String);
+
+impl fmt::Debug for RootXprv {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<sensitive>")
+    }
+}
+
+impl fmt::Display for RootXprv {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl FromStr for RootXprv {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::new(ExtendedPrivKey::from_str(s.trim())?)
+    }
+}
+
+impl RootXprv {
+    pub fn new(xprv: ExtendedPrivKey) -> Result<Self, Error> {
+        if xprv.depth == 0 {
+            Ok(Self(xprv.to_string()))
+        } else {
+            Err(Error::NotRootKey)
+        }
+    }
+
+    pub fn to_xprv(&self) -> Result<ExtendedPrivKey, bip32::Error> {
+        ExtendedPrivKey::from_str(&self.0)
+    }
+}
+
+impl FromStr for SeedKind {
+    type Err = Error;
+
+    /// Parse a root extended private key
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_xprv(ExtendedPrivKey::from_str(s.trim())?)
+    }
+}
+
+/// BIP39 mnemonics are serialized as plain strings (as before the introduction of the seed kinds),
+/// all the other kinds as tagged objects.
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum SeedKindSerde {
+    BIP39(Mnemonic),
+    Tagged(TaggedSeedKind),
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum TaggedSeedKind {
+    Electrum(ElectrumMnemonic),
+    Aezeed(CipherSeed),
+    /// Hex encoded
+    RawSeed(String),
+    Xprv(String),
+}
+
+impl From<SeedKind> for SeedKindSerde {
+    fn from(kind: SeedKind) -> Self {
+        match &kind {
+            SeedKind::BIP39(mnemonic) => Self::BIP39(mnemonic.clone()),
+            SeedKind::Electrum(mnemonic) => {
+                Self::Tagged(TaggedSeedKind::Electrum(mnemonic.clone()))
+            }
+            SeedKind::Aezeed(seed) => Self::Tagged(TaggedSeedKind::Aezeed(seed.clone())),
+            SeedKind::RawSeed(seed) => Self::Tagged(TaggedSeedKind::RawSeed(hex::encode(seed))),
+            SeedKind::Xprv(xprv) => Self::Tagged(TaggedSeedKind::Xprv(xprv.to_string())),
+        }
+    }
+}
+
+impl TryFrom<SeedKindSerde> for SeedKind {
+    type Error = Error;
+
+    fn try_from(kind: SeedKindSerde) -> Result<Self, Self::Error> {
+        match kind {
+            SeedKindSerde::BIP39(mnemonic) => Ok(Self::BIP39(mnemonic)),
+            SeedKindSerde::Tagged(TaggedSeedKind::Electrum(mnemonic)) => {
+                Ok(Self::Electrum(mnemonic))
+            }
+            SeedKindSerde::Tagged(TaggedSeedKind::Aezeed(seed)) => Ok(Self::Aezeed(seed)),
+            SeedKindSerde::Tagged(TaggedSeedKind::RawSeed(seed)) => Self::from_raw_seed_hex(seed),
+            SeedKindSerde::Tagged(TaggedSeedKind::Xprv(xprv)) => Self::from_str(&xprv),
+        }
+    }
+}
+
 #[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Zeroize, ZeroizeOnDrop)]
 pub struct Seed {
-    mnemonic: Mnemonic,
+    kind: SeedKind,
     passphrase: Option<String>,
 }
 
@@ -27,44 +270,75 @@ impl fmt::Debug for Seed {
 
 impl Seed {
     pub fn new<S>(mnemonic: Mnemonic, passphrase: Option<S>) -> Self
+    where
+        S: Into<String>,
+    {
+        Self::with_kind(SeedKind::BIP39(mnemonic), passphrase)
+    }
+
+    pub fn with_kind<S>(kind: SeedKind, passphrase: Option<S>) -> Self
     where
         S: Into<String>,
     {
         Self {
-            mnemonic,
+            kind,
             passphrase: passphrase.map(|p| p.into()),
         }
     }
 
     pub fn from_mnemonic(mnemonic: Mnemonic) -> Self {
         Self {
-            mnemonic,
+            kind: SeedKind::BIP39(mnemonic),
             passphrase: None,
         }
     }
 
-    pub fn mnemonic(&self) -> Mnemonic {
-        self.mnemonic.clone()
+    pub fn kind(&self) -> SeedKind {
+        self.kind.clone()
+    }
+
+    /// BIP39 mnemonic (`None` for other seed kinds)
+    pub fn mnemonic(&self) -> Option<Mnemonic> {
+        match &self.kind {
+            SeedKind::BIP39(mnemonic) => Some(mnemonic.clone()),
+            _ => None,
+        }
     }
 
     pub fn passphrase(&self) -> Option<String> {
         self.passphrase.clone()
     }
 
-    pub fn to_bytes(&self) -> [u8; 64] {
-        self.mnemonic
-            .to_seed(self.passphrase.clone().unwrap_or_default())
+    /// BIP32 seed (`None` for xprv keys)
+    pub fn to_bytes(&self) -> Option<Vec<u8>> {
+        let passphrase: String = self.passphrase.clone().unwrap_or_default();
+        match &self.kind {
+            SeedKind::BIP39(mnemonic) => Some(mnemonic.to_seed(passphrase).to_vec()),
+            SeedKind::Electrum(mnemonic) => Some(mnemonic.to_seed(passphrase).to_vec()),
+            SeedKind::Aezeed(seed) => Some(seed.entropy().to_vec()),
+            SeedKind::RawSeed(seed) => Some(seed.clone()),
+            SeedKind::Xprv(..) => None,
+        }
     }
 
-    pub fn to_hex(&self) -> String {
-        hex::encode(self.to_bytes())
+    /// BIP32 seed, hex encoded (`None` for xprv keys)
+    pub fn to_hex(&self) -> Option<String> {
+        self.to_bytes().map(hex::encode)
     }
 }
 
 impl Bip32 for Seed {
     type Err = bip32::Error;
     fn to_bip32_root_key(&self, network: Network) -> Result<ExtendedPrivKey, Self::Err> {
-        ExtendedPrivKey::new_master(network, &self.to_bytes())
+        match &self.kind {
+            SeedKind::Xprv(xprv) => {
+                let mut root: ExtendedPrivKey = xprv.to_xprv()?;
+                root.network = network;
+                Ok(root)
+            }
+            // Only the xprv keys have no seed
+            _ => ExtendedPrivKey::new_master(network, &self.to_bytes().unwrap_or_default()),
+        }
     }
 }
 
@@ -82,6 +356,105 @@ mod tests {
         let mnemonic = Mnemonic::from_str("easy uncover favorite crystal bless differ energy seat ecology match carry group refuse together chat observe hidden glad brave month diesel sustain depth salt").unwrap();
         let passphrase: Option<&str> = Some("mypassphrase");
         let seed = Seed::new(mnemonic, passphrase);
-        assert_eq!(&seed.to_hex(), "fb826595a0d679f5e9f8c799bd1decb8dc2ad3fb4e39a1ffaa4708a150e0e81ae55d3f340a188cd6188a2b76601aeae16945b36ae0ecfced9645029796c33713")
+        assert_eq!(seed.to_hex().unwrap(), "fb826595a0d679f5e9f8c799bd1decb8dc2ad3fb4e39a1ffaa4708a150e0e81ae55d3f340a188cd6188a2b76601aeae16945b36ae0ecfced9645029796c33713")
+    }
+
+    #[test]
+    fn test_electrum_seed() {
+        let mnemonic = ElectrumMnemonic::from_str(
+            "bitter grass shiver impose acquire brush forget axis eager alone wine silver",
+        )
+        .unwrap();
+        let seed = Seed::with_kind::<&str>(SeedKind::Electrum(mnemonic), None);
+        assert!(seed.mnemonic().is_none());
+        assert_eq!(seed.to_bip32_root_key(Network::Bitcoin).unwrap().to_string(), "xprv9s21ZrQH143K4GC8tb4zPSyogY87cBXJdJw3TCA8iV7FUjrDxPrJmS8wqvEuFE3QQVmj53i1iA7LZ4Dz2QPoKkttejWDRVE9SxQmLEP23RV".to_string());
+    }
+
+    #[test]
+    fn test_aezeed_seed() {
+        let entropy: [u8; 16] = [0x81; 16];
+        let cipher_seed = CipherSeed::new::<&str>(entropy, 5000, *b"salt1", None).unwrap();
+        let seed = Seed::with_kind(SeedKind::Aezeed(cipher_seed), Some("ignored"));
+        assert_eq!(seed.to_bytes(), Some(entropy.to_vec()));
+        assert_eq!(
+            seed.to_bip32_root_key(Network::Bitcoin).unwrap(),
+            ExtendedPrivKey::new_master(Network::Bitcoin, &entropy).unwrap()
+        );
+
+        let json: String = serde_json::to_string(&seed.kind()).unwrap();
+        assert!(json.starts_with(r#"{"aezeed":"#));
+        assert_eq!(
+            serde_json::from_str::<SeedKind>(&json).unwrap(),
+            seed.kind()
+        );
+    }
+
+    #[test]
+    fn test_seed_kind_serde() {
+        let mnemonic = Mnemonic::from_str("easy uncover favorite crystal bless differ energy seat ecology match carry group refuse together chat observe hidden glad brave month diesel sustain depth salt").unwrap();
+        let kind = SeedKind::BIP39(mnemonic.clone());
+        let json: String = serde_json::to_string(&kind).unwrap();
+        assert_eq!(json, serde_json::to_string(&mnemonic).unwrap());
+        assert_eq!(serde_json::from_str::<SeedKind>(&json).unwrap(), kind);
+
+        let mnemonic = ElectrumMnemonic::from_str(
+            "cycle rocket west magnet parrot shuffle foot correct salt library feed song",
+        )
+        .unwrap();
+        let kind = SeedKind::Electrum(mnemonic);
+        let json: String = serde_json::to_string(&kind).unwrap();
+        assert_eq!(
+            json,
+            r#"{"electrum":"cycle rocket west magnet parrot shuffle foot correct salt library feed song"}"#
+        );
+        assert_eq!(serde_json::from_str::<SeedKind>(&json).unwrap(), kind);
+    }
+
+    #[test]
+    fn test_raw_seed() {
+        let hex_seed: &str = "000102030405060708090a0b0c0d0e0f";
+        let kind = SeedKind::from_raw_seed_hex(hex_seed).unwrap();
+        let seed = Seed::with_kind(kind.clone(), Some("ignored"));
+        assert!(seed.mnemonic().is_none());
+        assert_eq!(seed.to_hex().unwrap(), hex_seed);
+        // BIP32 test vector 1
+        assert_eq!(seed.to_bip32_root_key(Network::Bitcoin).unwrap().to_string(), "xprv9s21ZrQH143K3QTDL4LXw2F7HEK3wJUD2nW2nRk4stbPy6cq3jPPqjiChkVvvNKmPGJxWUtg6LnF5kejMRNNU3TGtRBeJgk33yuGBxrMPHi".to_string());
+
+        let json: String = serde_json::to_string(&kind).unwrap();
+        assert_eq!(json, format!(r#"{{"raw_seed":"{hex_seed}"}}"#));
+        assert_eq!(serde_json::from_str::<SeedKind>(&json).unwrap(), kind);
+
+        assert_eq!(
+            SeedKind::from_raw_seed([0u8; 15]),
+            Err(Error::InvalidSeedLength(15))
+        );
+        assert!(serde_json::from_str::<SeedKind>(r#"{"raw_seed":"0011"}"#).is_err());
+    }
+
+    #[test]
+    fn test_xprv() {
+        let root = ExtendedPrivKey::from_str("xprv9s21ZrQH143K3QTDL4LXw2F7HEK3wJUD2nW2nRk4stbPy6cq3jPPqjiChkVvvNKmPGJxWUtg6LnF5kejMRNNU3TGtRBeJgk33yuGBxrMPHi").unwrap();
+        let kind = SeedKind::from_xprv(root).unwrap();
+        let seed = Seed::with_kind::<&str>(kind.clone(), None);
+        assert_eq!(seed.to_bytes(), None);
+        assert_eq!(seed.to_bip32_root_key(Network::Bitcoin).unwrap(), root);
+        assert_eq!(
+            seed.to_bip32_root_key(Network::Testnet).unwrap().network,
+            Network::Testnet
+        );
+
+        let json: String = serde_json::to_string(&kind).unwrap();
+        assert_eq!(serde_json::from_str::<SeedKind>(&json).unwrap(), kind);
+
+        let secp = bdk::bitcoin::secp256k1::Secp256k1::new();
+        let child = root
+            .derive_priv(&secp, &bip32::DerivationPath::from_str("m/0'").unwrap())
+            .unwrap();
+        assert_eq!(SeedKind::from_xprv(child), Err(Error::NotRootKey));
+        assert_eq!(
+            RootXprv::from_str(&child.to_string()),
+            Err(Error::NotRootKey)
+        );
+        assert!(RootXprv::from_str("xprv").is_err());
     }
 }
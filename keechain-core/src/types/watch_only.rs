@@ -0,0 +1,414 @@
+// Copyright (c) 2022-2023 Yuki Kishimoto
+// Distributed under the MIT software license
+
+//! Watch-only keychain
+//!
+//! Store only the account descriptors (no secrets): useful to verify addresses and review PSBTs.
+
+use core::fmt;
+use core::str::FromStr;
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use bdk::bitcoin::psbt::PartiallySignedTransaction;
+use bdk::bitcoin::{Address, Network};
+use bdk::miniscript::descriptor::{Descriptor, DescriptorPublicKey};
+use bdk::miniscript::ForEachKey;
+use serde::{Deserialize, Serialize};
+
+use crate::bips::bip32::Fingerprint;
+use crate::bips::bip43::Purpose;
+use crate::descriptors::{self, typed_descriptor, Descriptors};
+use crate::export::coldcard::{self, ColdcardGenericJson};
+use crate::psbt::PsbtReview;
+use crate::util;
+use crate::util::dir::{self, KEECHAIN_EXTENSION, WATCH_ONLY_DOT_EXTENSION, WATCH_ONLY_EXTENSION};
+
+const WATCH_ONLY_FILE_VERSION: u8 = 1;
+
+#[derive(Debug)]
+pub enum Error {
+    IO(std::io::Error),
+    Dir(dir::Error),
+    Json(serde_json::Error),
+    Descriptors(descriptors::Error),
+    Coldcard(coldcard::Error),
+    Miniscript(bdk::miniscript::Error),
+    InvalidName,
+    FileNotFound,
+    FileAlreadyExists,
+    NoDescriptors,
+    FingerprintNotFound,
+    UnsupportedKey,
+    WrongNetwork,
+    UnknownVersion(u8),
+}
+
+impl std::error::Error for Error {}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::IO(e) => write!(f, "IO: {e}"),
+            Self::Dir(e) => write!(f, "Dir: {e}"),
+            Self::Json(e) => write!(f, "Json: {e}"),
+            Self::Descriptors(e) => write!(f, "Descriptors: {e}"),
+            Self::Coldcard(e) => write!(f, "Coldcard: {e}"),
+            Self::Miniscript(e) => write!(f, "Miniscript: {e}"),
+            Self::InvalidName => write!(f, "Invalid name"),
+            Self::FileNotFound => write!(f, "File not found"),
+            Self::FileAlreadyExists => write!(
+                f,
+                "There is already a file with the same name! Please, choose another name"
+            ),
+            Self::NoDescriptors => write!(f, "No descriptors provided"),
+            Self::FingerprintNotFound => write!(f, "Root fingerprint not found in key origin"),
+            Self::UnsupportedKey => write!(
+                f,
+                "Unsupported key: expected an account extended public key with origin"
+            ),
+            Self::WrongNetwork => write!(f, "Keys are for another network"),
+            Self::UnknownVersion(v) => write!(f, "Unknown watch-only file version: {v}"),
+        }
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Self::IO(e)
+    }
+}
+
+impl From<dir::Error> for Error {
+    fn from(e: dir::Error) -> Self {
+        Self::Dir(e)
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(e: serde_json::Error) -> Self {
+        Self::Json(e)
+    }
+}
+
+impl From<descriptors::Error> for Error {
+    fn from(e: descriptors::Error) -> Self {
+        Self::Descriptors(e)
+    }
+}
+
+impl From<coldcard::Error> for Error {
+    fn from(e: coldcard::Error) -> Self {
+        Self::Coldcard(e)
+    }
+}
+
+impl From<bdk::miniscript::Error> for Error {
+    fn from(e: bdk::miniscript::Error) -> Self {
+        Self::Miniscript(e)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct WatchOnlyRaw {
+    version: u8,
+    fingerprint: Fingerprint,
+    descriptors: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct WatchOnly {
+    file: PathBuf,
+    fingerprint: Fingerprint,
+    descriptors: Descriptors,
+    network: Network,
+}
+
+impl WatchOnly {
+    /// Create watch-only keychain from descriptors
+    pub fn new<P, S>(
+        base_path: P,
+        name: S,
+        descriptors: Descriptors,
+        network: Network,
+    ) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+        S: Into<String>,
+    {
+        let name: String = name.into();
+        if name.is_empty() {
+            return Err(Error::InvalidName);
+        }
+
+        let base_path: &Path = base_path.as_ref();
+        if dir::get_keychain_file(base_path, name.clone())?.exists() {
+            return Err(Error::FileAlreadyExists);
+        }
+
+        let file: PathBuf = dir::get_watch_only_file(base_path, name)?;
+        if file.exists() {
+            return Err(Error::FileAlreadyExists);
+        }
+
+        let watch_only = Self::from_descriptors(file, descriptors, network)?;
+        watch_only.save()?;
+        Ok(watch_only)
+    }
+
+    /// Import watch-only keychain from Coldcard generic JSON (single-sig purposes only)
+    pub fn from_coldcard<P, S>(
+        base_path: P,
+        name: S,
+        json: &ColdcardGenericJson,
+        network: Network,
+    ) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+        S: Into<String>,
+    {
+        Self::new(base_path, name, json.descriptors()?, network)
+    }
+
+    /// Import watch-only keychain from an account key with origin (ex. `[fingerprint/84'/0'/0']xpub...`)
+    ///
+    /// The purpose is taken from the origin path.
+    pub fn from_key<P, S>(
+        base_path: P,
+        name: S,
+        key: DescriptorPublicKey,
+        network: Network,
+    ) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+        S: Into<String>,
+    {
+        let (fingerprint, xpub, path) = match key {
+            DescriptorPublicKey::XPub(key) => match key.origin {
+                Some((fingerprint, path)) => (fingerprint, key.xkey, path),
+                None => return Err(Error::FingerprintNotFound),
+            },
+            _ => return Err(Error::UnsupportedKey),
+        };
+        let descriptors = Descriptors::from_descriptors([
+            typed_descriptor(fingerprint, xpub, &path, false)?,
+            typed_descriptor(fingerprint, xpub, &path, true)?,
+        ])?;
+        Self::new(base_path, name, descriptors, network)
+    }
+
+    fn from_descriptors(
+        file: PathBuf,
+        descriptors: Descriptors,
+        network: Network,
+    ) -> Result<Self, Error> {
+        if descriptors.is_empty() {
+            return Err(Error::NoDescriptors);
+        }
+
+        // Check network of extended keys (testnet keys are used for all the test networks)
+        let is_mainnet: bool = network == Network::Bitcoin;
+        for desc in descriptors
+            .external()
+            .iter()
+            .chain(descriptors.internal().iter())
+        {
+            let mismatch: bool = desc.for_any_key(|key| match key {
+                DescriptorPublicKey::XPub(key) => {
+                    (key.xkey.network == Network::Bitcoin) != is_mainnet
+                }
+                _ => false,
+            });
+            if mismatch {
+                return Err(Error::WrongNetwork);
+            }
+        }
+
+        Ok(Self {
+            file,
+            fingerprint: descriptors
+                .root_fingerprint()
+                .ok_or(Error::FingerprintNotFound)?,
+            descriptors,
+            network,
+        })
+    }
+
+    pub fn open<P, S>(base_path: P, name: S, network: Network) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+        S: Into<String>,
+    {
+        let name: String = name.into();
+        if name.is_empty() {
+            return Err(Error::InvalidName);
+        }
+
+        let file: PathBuf = dir::get_watch_only_file(base_path, name)?;
+        if !file.exists() {
+            return Err(Error::FileNotFound);
+        }
+
+        let mut content: Vec<u8> = Vec::new();
+        File::open(file.as_path())?.read_to_end(&mut content)?;
+        let raw: WatchOnlyRaw = util::serde::deserialize(content)?;
+
+        if raw.version != WATCH_ONLY_FILE_VERSION {
+            return Err(Error::UnknownVersion(raw.version));
+        }
+
+        let mut list: Vec<Descriptor<DescriptorPublicKey>> =
+            Vec::with_capacity(raw.descriptors.len());
+        for desc in raw.descriptors.iter() {
+            list.push(Descriptor::from_str(desc)?);
+        }
+
+        Self::from_descriptors(file, Descriptors::from_descriptors(list)?, network)
+    }
+
+    pub fn save(&self) -> Result<(), Error> {
+        let raw = WatchOnlyRaw {
+            version: WATCH_ONLY_FILE_VERSION,
+            fingerprint: self.fingerprint,
+            descriptors: self
+                .descriptors
+                .external()
+                .iter()
+                .chain(self.descriptors.internal().iter())
+                .map(|desc| desc.to_string())
+                .collect(),
+        };
+        let data: Vec<u8> = util::serde::serialize(raw)?;
+        let mut file: File = File::options()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(self.file.as_path())?;
+        file.write_all(&data)?;
+        Ok(())
+    }
+
+    pub fn file_path(&self) -> PathBuf {
+        self.file.clone()
+    }
+
+    /// Get watch-only file name
+    pub fn name(&self) -> Option<String> {
+        let file_name = self.file.file_name()?.to_str()?;
+        Some(file_name.replace(WATCH_ONLY_DOT_EXTENSION, ""))
+    }
+
+    /// Root fingerprint
+    pub fn identity(&self) -> Fingerprint {
+        self.fingerprint
+    }
+
+    pub fn network(&self) -> Network {
+        self.network
+    }
+
+    pub fn descriptors(&self) -> &Descriptors {
+        &self.descriptors
+    }
+
+    pub fn address(&self, purpose: Purpose, change: bool, index: u32) -> Result<Address, Error> {
+        Ok(self
+            .descriptors
+            .address(purpose, change, index, self.network)?)
+    }
+
+    pub fn review_psbt(&self, psbt: &PartiallySignedTransaction) -> PsbtReview {
+        PsbtReview::new(psbt, &self.descriptors, self.network)
+    }
+
+    pub fn rename<S>(&mut self, new_name: S) -> Result<(), Error>
+    where
+        S: Into<String>,
+    {
+        let new_name: String = new_name.into();
+        if new_name.is_empty() {
+            return Err(Error::InvalidName);
+        }
+
+        let mut new: PathBuf = self.file.clone();
+        new.set_file_name(new_name);
+        new.set_extension(WATCH_ONLY_EXTENSION);
+        // Keychains and watch-only keychains share the same names
+        if new.exists() || new.with_extension(KEECHAIN_EXTENSION).exists() {
+            Err(Error::FileAlreadyExists)
+        } else {
+            fs::rename(self.file.as_path(), new.as_path())?;
+            self.file = new;
+            Ok(())
+        }
+    }
+
+    pub fn remove(&self) -> Result<(), Error> {
+        Ok(fs::remove_file(self.file.as_path())?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: &str = "[91ef223d/84'/1'/2345']tpubDCgYuiX1p1eecECkhNc2bLSktmSDoMTj5J3v184ErUXqHTywQ7X5afv51UGfDVSaYzDWvdHhVyJ6UK8fM27EwGByWdczEERfAA9j2nzHUAj";
+
+    #[test]
+    fn test_watch_only() {
+        let base_path: PathBuf = std::env::temp_dir().join("keechain-test-watch-only");
+        let _ = fs::remove_dir_all(&base_path);
+        fs::create_dir_all(&base_path).unwrap();
+
+        let key = DescriptorPublicKey::from_str(KEY).unwrap();
+        assert!(matches!(
+            WatchOnly::from_key(&base_path, "test", key.clone(), Network::Bitcoin),
+            Err(Error::WrongNetwork)
+        ));
+
+        let watch_only = WatchOnly::from_key(&base_path, "test", key, Network::Testnet).unwrap();
+        assert_eq!(
+            watch_only.identity(),
+            Fingerprint::from_str("91ef223d").unwrap()
+        );
+        assert_eq!(watch_only.descriptors().purposes(), vec![Purpose::BIP84]);
+        assert_eq!(watch_only.name(), Some(String::from("test")));
+        assert_eq!(dir::get_watch_only_list(&base_path).unwrap(), vec!["test"]);
+        assert!(dir::get_keychains_list(&base_path).unwrap().is_empty());
+        assert!(dir::is_watch_only(&base_path, "test").unwrap());
+
+        let opened = WatchOnly::open(&base_path, "test", Network::Testnet).unwrap();
+        assert_eq!(opened.identity(), watch_only.identity());
+        assert_eq!(
+            opened.address(Purpose::BIP84, true, 0).unwrap(),
+            watch_only.address(Purpose::BIP84, true, 0).unwrap()
+        );
+        assert!(opened.address(Purpose::BIP86, false, 0).is_err());
+
+        // Rename
+        let mut renamed = opened;
+        let other = WatchOnly::from_key(
+            &base_path,
+            "other",
+            DescriptorPublicKey::from_str(KEY).unwrap(),
+            Network::Testnet,
+        )
+        .unwrap();
+        assert!(matches!(
+            renamed.rename("other"),
+            Err(Error::FileAlreadyExists)
+        ));
+        fs::write(base_path.join("keychain.keechain"), b"").unwrap();
+        assert!(matches!(
+            renamed.rename("keychain"),
+            Err(Error::FileAlreadyExists)
+        ));
+        renamed.rename("renamed").unwrap();
+        assert_eq!(renamed.name(), Some(String::from("renamed")));
+
+        renamed.remove().unwrap();
+        other.remove().unwrap();
+        fs::remove_dir_all(&base_path).unwrap();
+    }
+}
@@ -8,6 +8,8 @@ use std::path::{Path, PathBuf};
 
 pub const KEECHAIN_EXTENSION: &str = "keechain";
 pub(crate) const KEECHAIN_DOT_EXTENSION: &str = ".keechain";
+pub const WATCH_ONLY_EXTENSION: &str = "keechain-watch";
+pub(crate) const WATCH_ONLY_DOT_EXTENSION: &str = ".keechain-watch";
 
 #[derive(Debug)]
 pub enum Error {
@@ -33,6 +35,21 @@ impl From<std::io::Error> for Error {
 }
 
 pub fn get_keychains_list<P>(path: P) -> Result<Vec<String>, Error>
+where
+    P: AsRef<Path>,
+{
+    get_list(path, KEECHAIN_DOT_EXTENSION)
+}
+
+/// Watch-only keychains (no secrets)
+pub fn get_watch_only_list<P>(path: P) -> Result<Vec<String>, Error>
+where
+    P: AsRef<Path>,
+{
+    get_list(path, WATCH_ONLY_DOT_EXTENSION)
+}
+
+fn get_list<P>(path: P, dot_extension: &str) -> Result<Vec<String>, Error>
 where
     P: AsRef<Path>,
 {
@@ -41,8 +58,8 @@ where
     for path in paths {
         let path: PathBuf = path?.path();
         if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
-            if name.ends_with(KEECHAIN_DOT_EXTENSION) {
-                let splitted: Vec<&str> = name.split(KEECHAIN_DOT_EXTENSION).collect();
+            if name.ends_with(dot_extension) {
+                let splitted: Vec<&str> = name.split(dot_extension).collect();
                 if let Some(value) = splitted.first() {
                     names.push(value.to_string());
                 }
@@ -63,6 +80,28 @@ where
     Ok(keychain_file)
 }
 
+pub(crate) fn get_watch_only_file<P, S>(path: P, name: S) -> Result<PathBuf, Error>
+where
+    P: AsRef<Path>,
+    S: Into<String>,
+{
+    let mut file: PathBuf = path.as_ref().join(name.into());
+    file.set_extension(WATCH_ONLY_EXTENSION);
+    Ok(file)
+}
+
+/// Check if `name` is a watch-only keychain (and not a keychain with secrets)
+pub fn is_watch_only<P, S>(path: P, name: S) -> Result<bool, Error>
+where
+    P: AsRef<Path>,
+    S: Into<String>,
+{
+    let name: String = name.into();
+    let path: &Path = path.as_ref();
+    Ok(!get_keychain_file(path, name.clone())?.exists()
+        && get_watch_only_file(path, name)?.exists())
+}
+
 pub fn rename_psbt(psbt_file: &mut PathBuf, finalized: bool) -> Result<(), Error> {
     if let Some(mut file_name) = psbt_file.file_name().and_then(OsStr::to_str) {
         if let Some(ext) = psbt_file.extension().and_then(OsStr::to_str) {
@@ -0,0 +1,420 @@
+// Copyright (c) 2022-2023 Yuki Kishimoto
+// Distributed under the MIT software license
+
+//! Vanity address search
+//!
+//! Derive the addresses of an account chain until one matches a prefix, a suffix or a regex.
+
+use core::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use bdk::bitcoin::secp256k1::{Secp256k1, Signing};
+use bdk::bitcoin::{Address, Network};
+use bdk::miniscript::descriptor::{Descriptor, DescriptorPublicKey};
+use regex::Regex;
+
+use crate::bips::bip43::Purpose;
+use crate::descriptors::{self, DerivedAddress, Descriptors};
+use crate::recovery;
+use crate::types::Seed;
+
+/// Number of non-hardened indexes
+const MAX_INDEX: u64 = 1 << 31;
+const BECH32_CHARSET: &str = "qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+const BASE58_CHARSET: &str = "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+#[derive(Debug)]
+pub enum Error {
+    Descriptors(descriptors::Error),
+    Recovery(recovery::Error),
+    Regex(regex::Error),
+    /// Multisig purposes are not supported
+    UnsupportedPurpose,
+    /// Pattern that no address of the chosen type can match
+    InvalidPattern(String),
+}
+
+impl std::error::Error for Error {}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Descriptors(e) => write!(f, "Descriptors: {e}"),
+            Self::Recovery(e) => write!(f, "Recovery: {e}"),
+            Self::Regex(e) => write!(f, "Regex: {e}"),
+            Self::UnsupportedPurpose => write!(f, "Unsupported purpose: expected single-sig"),
+            Self::InvalidPattern(reason) => write!(f, "Invalid pattern: {reason}"),
+        }
+    }
+}
+
+impl From<descriptors::Error> for Error {
+    fn from(e: descriptors::Error) -> Self {
+        Self::Descriptors(e)
+    }
+}
+
+impl From<recovery::Error> for Error {
+    fn from(e: recovery::Error) -> Self {
+        Self::Recovery(e)
+    }
+}
+
+impl From<regex::Error> for Error {
+    fn from(e: regex::Error) -> Self {
+        Self::Regex(e)
+    }
+}
+
+/// What the address must look like
+#[derive(Debug, Clone)]
+pub enum Pattern {
+    /// Beginning of the address (for bech32 addresses the `bc1q`/`bc1p` part can be omitted)
+    Prefix(String),
+    /// End of the address
+    Suffix(String),
+    /// Regex (matched anywhere in the address, use `^`/`$` to anchor it)
+    Regex(Regex),
+}
+
+impl Pattern {
+    pub fn regex<S>(regex: S) -> Result<Self, Error>
+    where
+        S: AsRef<str>,
+    {
+        Ok(Self::Regex(Regex::new(regex.as_ref())?))
+    }
+}
+
+/// Pattern normalized and checked for the address encoding
+#[derive(Debug)]
+struct Matcher {
+    pattern: Pattern,
+    /// Address chars that are always the same
+    fixed: usize,
+    /// Number of possible chars at every position
+    base: f64,
+}
+
+impl Matcher {
+    fn new(pattern: Pattern, sample: &Address, bech32: bool) -> Result<Self, Error> {
+        let sample: String = sample.to_string();
+        let (fixed, charset, base): (String, &str, f64) = if bech32 {
+            // HRP, separator and witness version
+            let separator: usize = sample.rfind('1').unwrap_or_default();
+            (sample[..separator + 2].to_string(), BECH32_CHARSET, 32.0)
+        } else {
+            (sample[..1].to_string(), BASE58_CHARSET, 58.0)
+        };
+
+        let check = |text: &str| -> Result<(), Error> {
+            if text.is_empty() {
+                return Err(Error::InvalidPattern(String::from("empty")));
+            }
+            match text.chars().find(|c| !charset.contains(*c)) {
+                Some(c) => Err(Error::InvalidPattern(format!(
+                    "'{c}' can't appear in {} addresses",
+                    if bech32 { "bech32" } else { "base58" }
+                ))),
+                None => Ok(()),
+            }
+        };
+
+        let pattern: Pattern = match pattern {
+            Pattern::Prefix(prefix) => {
+                let prefix: String = if bech32 {
+                    let prefix: String = prefix.to_lowercase();
+                    if prefix.starts_with(&fixed) {
+                        prefix
+                    } else {
+                        format!("{fixed}{prefix}")
+                    }
+                } else {
+                    check(&prefix)?;
+                    if !prefix.starts_with(&fixed) {
+                        return Err(Error::InvalidPattern(format!(
+                            "these addresses start with '{fixed}'"
+                        )));
+                    }
+                    prefix
+                };
+                check(&prefix[fixed.len()..])?;
+                Pattern::Prefix(prefix)
+            }
+            Pattern::Suffix(suffix) => {
+                let suffix: String = if bech32 {
+                    suffix.to_lowercase()
+                } else {
+                    suffix
+                };
+                check(&suffix)?;
+                Pattern::Suffix(suffix)
+            }
+            Pattern::Regex(regex) => Pattern::Regex(regex),
+        };
+
+        Ok(Self {
+            pattern,
+            fixed: fixed.len(),
+            base,
+        })
+    }
+
+    fn matches(&self, address: &str) -> bool {
+        match &self.pattern {
+            Pattern::Prefix(prefix) => address.starts_with(prefix.as_str()),
+            Pattern::Suffix(suffix) => address.ends_with(suffix.as_str()),
+            Pattern::Regex(regex) => regex.is_match(address),
+        }
+    }
+
+    /// Average number of addresses to derive for a match (unknown for regexes)
+    fn difficulty(&self) -> Option<f64> {
+        let chars: usize = match &self.pattern {
+            Pattern::Prefix(prefix) => prefix.len().saturating_sub(self.fixed),
+            Pattern::Suffix(suffix) => suffix.len(),
+            Pattern::Regex(..) => return None,
+        };
+        Some(self.base.powi(chars as i32))
+    }
+}
+
+/// Search progress
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Progress {
+    /// Derived addresses
+    pub tested: u64,
+    pub elapsed: Duration,
+    /// Addresses per second
+    pub rate: f64,
+    /// Average time to find a match (`None` for regexes)
+    pub expected: Option<Duration>,
+}
+
+impl Progress {
+    fn new(tested: u64, elapsed: Duration, difficulty: Option<f64>) -> Self {
+        let seconds: f64 = elapsed.as_secs_f64();
+        let rate: f64 = if seconds > 0.0 {
+            tested as f64 / seconds
+        } else {
+            0.0
+        };
+        let expected: Option<Duration> = match difficulty {
+            Some(difficulty) if rate > 0.0 => {
+                let seconds: f64 = difficulty / rate;
+                if seconds < u64::MAX as f64 {
+                    Some(Duration::from_secs_f64(seconds))
+                } else {
+                    Some(Duration::MAX)
+                }
+            }
+            _ => None,
+        };
+        Self {
+            tested,
+            elapsed,
+            rate,
+            expected,
+        }
+    }
+}
+
+fn derive_address(
+    descriptor: &Descriptor<DescriptorPublicKey>,
+    index: u32,
+    network: Network,
+) -> Result<Address, descriptors::Error> {
+    Ok(descriptor.at_derivation_index(index)?.address(network)?)
+}
+
+/// Search a vanity address in the `purpose`/`account`/`change` chain, using all cores by default
+///
+/// Stops at the first match or when `stop` is set (returning `None`).
+pub fn search<P, C>(
+    seed: &Seed,
+    purpose: Purpose,
+    account: Option<u32>,
+    change: bool,
+    pattern: Pattern,
+    network: Network,
+    threads: Option<usize>,
+    stop: Arc<AtomicBool>,
+    mut progress: P,
+    secp: &Secp256k1<C>,
+) -> Result<Option<DerivedAddress>, Error>
+where
+    P: FnMut(Progress),
+    C: Signing,
+{
+    let bech32: bool = match purpose {
+        Purpose::BIP44 | Purpose::BIP49 => false,
+        Purpose::BIP84 | Purpose::BIP86 => true,
+        Purpose::BIP45 | Purpose::BIP48 { .. } | Purpose::Custom(..) => {
+            return Err(Error::UnsupportedPurpose)
+        }
+    };
+    let descriptor: Descriptor<DescriptorPublicKey> =
+        Descriptors::new(seed, network, account, secp)?.get_by_purpose(purpose, change)?;
+    let sample: Address = derive_address(&descriptor, 0, network)?;
+    let matcher: Matcher = Matcher::new(pattern, &sample, bech32)?;
+    let difficulty: Option<f64> = matcher.difficulty();
+
+    let descriptor: Arc<Descriptor<DescriptorPublicKey>> = Arc::new(descriptor);
+    let worker_descriptor = descriptor.clone();
+    let start: Instant = Instant::now();
+    let found: Option<Option<u32>> = recovery::search(
+        MAX_INDEX,
+        threads,
+        move |index| {
+            if stop.load(Ordering::Relaxed) {
+                return Ok(Some(None));
+            }
+            let index: u32 = index as u32;
+            let address: Address = derive_address(&worker_descriptor, index, network)?;
+            if matcher.matches(&address.to_string()) {
+                Ok(Some(Some(index)))
+            } else {
+                Ok(None)
+            }
+        },
+        |tested, _| progress(Progress::new(tested, start.elapsed(), difficulty)),
+    )?;
+
+    match found.flatten() {
+        Some(index) => {
+            let derived: Vec<DerivedAddress> =
+                descriptors::derive_addresses(&descriptor, None, index, 1, network)?;
+            Ok(derived.into_iter().next())
+        }
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::str::FromStr;
+
+    use super::*;
+    use crate::bips::bip39::Mnemonic;
+    use crate::bips::bip48::ScriptType;
+
+    const MNEMONIC: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+    fn matcher(pattern: Pattern, sample: &str, bech32: bool) -> Result<Matcher, Error> {
+        let sample = Address::from_str(sample)
+            .unwrap()
+            .require_network(Network::Bitcoin)
+            .unwrap();
+        Matcher::new(pattern, &sample, bech32)
+    }
+
+    #[test]
+    fn test_matcher() {
+        let sample: &str = "bc1qcr8te4kr609gcawutmrza0j4xv80jy8z306fyu";
+
+        let m = matcher(Pattern::Prefix(String::from("CR8")), sample, true).unwrap();
+        assert!(m.matches(sample));
+        assert_eq!(m.difficulty(), Some(32_768.0));
+
+        let m = matcher(Pattern::Prefix(String::from("bc1qcr")), sample, true).unwrap();
+        assert!(m.matches(sample));
+        assert_eq!(m.difficulty(), Some(1_024.0));
+
+        let m = matcher(Pattern::Suffix(String::from("fyu")), sample, true).unwrap();
+        assert!(m.matches(sample));
+
+        let m = matcher(Pattern::regex("^bc1q.*yu$").unwrap(), sample, true).unwrap();
+        assert!(m.matches(sample));
+        assert_eq!(m.difficulty(), None);
+
+        // Unanchored regexes match anywhere in the address
+        let m = matcher(Pattern::regex("te4k").unwrap(), sample, true).unwrap();
+        assert!(m.matches(sample));
+        let m = matcher(Pattern::regex("^te4k").unwrap(), sample, true).unwrap();
+        assert!(!m.matches(sample));
+
+        // `b`, `i`, `o` and `1` are not in the bech32 charset
+        assert!(matches!(
+            matcher(Pattern::Prefix(String::from("bob")), sample, true),
+            Err(Error::InvalidPattern(..))
+        ));
+
+        // Base58: `0`, `O`, `I` and `l` are not in the charset
+        let sample: &str = "1LqBGSKuX5yYUonjxT5qGfpUsXKYYWeabA";
+        assert!(matches!(
+            matcher(Pattern::Suffix(String::from("O0")), sample, false),
+            Err(Error::InvalidPattern(..))
+        ));
+        assert!(matches!(
+            matcher(Pattern::Prefix(String::from("3Lq")), sample, false),
+            Err(Error::InvalidPattern(..))
+        ));
+        let m = matcher(Pattern::Prefix(String::from("1Lq")), sample, false).unwrap();
+        assert!(m.matches(sample));
+        assert_eq!(m.difficulty(), Some(3_364.0));
+    }
+
+    #[test]
+    fn test_search() {
+        let secp = Secp256k1::new();
+        let seed = Seed::new(Mnemonic::from_str(MNEMONIC).unwrap(), None::<String>);
+
+        // m/84'/0'/2'/1/7
+        let found = search(
+            &seed,
+            Purpose::BIP84,
+            Some(2),
+            true,
+            Pattern::Suffix(String::from("z9gp7")),
+            Network::Bitcoin,
+            Some(2),
+            Arc::new(AtomicBool::new(false)),
+            |_| (),
+            &secp,
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(found.index, 7);
+        assert_eq!(found.path.to_string(), "m/84'/0'/2'/1/7");
+        assert_eq!(
+            found.address.to_string(),
+            "bc1qk25senelucp2c8ejm8lyx6l3xfrmfltx0z9gp7"
+        );
+
+        // Stopped
+        let found = search(
+            &seed,
+            Purpose::BIP84,
+            None,
+            false,
+            Pattern::Prefix(String::from("qqqqqqqqqq")),
+            Network::Bitcoin,
+            Some(2),
+            Arc::new(AtomicBool::new(true)),
+            |_| (),
+            &secp,
+        )
+        .unwrap();
+        assert_eq!(found, None);
+
+        assert!(matches!(
+            search(
+                &seed,
+                Purpose::BIP48 {
+                    script: ScriptType::P2WSH
+                },
+                None,
+                false,
+                Pattern::Suffix(String::from("q")),
+                Network::Bitcoin,
+                None,
+                Arc::new(AtomicBool::new(false)),
+                |_| (),
+                &secp,
+            ),
+            Err(Error::UnsupportedPurpose)
+        ));
+    }
+}
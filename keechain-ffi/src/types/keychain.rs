@@ -4,11 +4,14 @@
 use std::ops::Deref;
 use std::sync::Arc;
 
-use keechain_core::types::keychain;
+use keechain_core::secp256k1::{Secp256k1, SignOnly};
+use keechain_core::types::keychain::{self, Passphrase};
+use keechain_core::types::SeedKind;
 use uniffi::Object;
 
 use super::seed::Seed;
 use crate::bips::bip39::Mnemonic;
+use crate::error::Result;
 
 #[derive(Object)]
 pub struct Keychain {
@@ -18,18 +21,35 @@ pub struct Keychain {
 #[uniffi::export]
 impl Keychain {
     #[uniffi::constructor]
-    pub fn new(mnemonic: Arc<Mnemonic>, passphrases: Vec<String>) -> Arc<Self> {
-        Arc::new(Self {
-            inner: keychain::Keychain::new(mnemonic.as_ref().deref().clone(), passphrases),
-        })
+    pub fn new(mnemonic: Arc<Mnemonic>, passphrases: Vec<String>) -> Result<Arc<Self>> {
+        let kind: SeedKind = mnemonic.as_ref().deref().clone().into();
+        let secp = Secp256k1::<SignOnly>::signing_only();
+        let mut labeled: Vec<Passphrase> = Vec::with_capacity(passphrases.len());
+        for passphrase in passphrases.into_iter() {
+            // Label them by position, like the passphrases of legacy keychains
+            let label: String = format!("Passphrase #{}", labeled.len() + 1);
+            labeled.push(Passphrase::new(&kind, label, passphrase, &secp)?);
+        }
+        Ok(Arc::new(Self {
+            inner: keychain::Keychain::new(kind, labeled),
+        }))
     }
 
-    pub fn mnemonic(&self) -> Arc<Mnemonic> {
-        Arc::new(self.inner.mnemonic().into())
+    pub fn mnemonic(&self) -> Option<Arc<Mnemonic>> {
+        self.inner.mnemonic().map(|m| Arc::new(m.into()))
     }
 
     pub fn passphrases(&self) -> Vec<String> {
-        self.inner.passphrases()
+        self.inner
+            .passphrases()
+            .iter()
+            .map(|p| p.passphrase())
+            .collect()
+    }
+
+    /// Labels of the saved passphrases, in the same order as [`Keychain::passphrases`]
+    pub fn passphrase_labels(&self) -> Vec<String> {
+        self.inner.passphrases().iter().map(|p| p.label()).collect()
     }
 
     pub fn seed(&self) -> Arc<Seed> {
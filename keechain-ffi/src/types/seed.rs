@@ -4,7 +4,7 @@
 use std::ops::Deref;
 use std::sync::Arc;
 
-use keechain_core::types::seed;
+use keechain_core::types::{seed, SeedKind};
 use uniffi::Object;
 
 use crate::bips::bip39::Mnemonic;
@@ -29,19 +29,30 @@ impl Seed {
         })
     }
 
-    pub fn mnemonic(&self) -> String {
-        self.inner.mnemonic().to_string()
+    /// Mnemonic of the seed (`None` for raw seeds and xprv keys)
+    pub fn mnemonic(&self) -> Option<String> {
+        let kind: SeedKind = self.inner.kind();
+        if kind.is_mnemonic() {
+            Some(kind.to_string())
+        } else {
+            None
+        }
+    }
+
+    /// Name of the seed kind (ex. `BIP39`, `xprv`)
+    pub fn kind(&self) -> String {
+        self.inner.kind().name()
     }
 
     pub fn passphrase(&self) -> Option<String> {
         self.inner.passphrase()
     }
 
-    pub fn to_bytes(&self) -> Vec<u8> {
-        self.inner.to_bytes().to_vec()
+    pub fn to_bytes(&self) -> Option<Vec<u8>> {
+        self.inner.to_bytes()
     }
 
-    pub fn to_hex(&self) -> String {
+    pub fn to_hex(&self) -> Option<String> {
         self.inner.to_hex()
     }
 }
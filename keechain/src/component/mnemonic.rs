@@ -3,21 +3,29 @@
 
 use eframe::egui::style::Spacing;
 use eframe::egui::{Grid, Ui};
-use keechain_core::bips::bip39::Mnemonic;
 
 pub struct MnemonicViewer {
-    mnemonic: Mnemonic,
+    words: Vec<String>,
 }
 
 impl MnemonicViewer {
-    pub fn new(mnemonic: Mnemonic) -> Self {
-        Self { mnemonic }
+    pub fn new<T>(mnemonic: T) -> Self
+    where
+        T: ToString,
+    {
+        Self {
+            words: mnemonic
+                .to_string()
+                .split_whitespace()
+                .map(String::from)
+                .collect(),
+        }
     }
 
     pub fn render(self, ui: &mut Ui) {
         ui.group(|ui| {
-            let colunm_size: usize = self.mnemonic.word_count() / 2;
-            let words: Vec<&str> = self.mnemonic.word_iter().collect();
+            let colunm_size: usize = (self.words.len() + 1) / 2;
+            let words: Vec<String> = self.words;
             Grid::new("mnemonic_viewer")
                 .min_col_width((ui.available_width() - Spacing::default().item_spacing.x) / 2.0)
                 .show(ui, |ui| {
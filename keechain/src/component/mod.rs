@@ -7,6 +7,7 @@ mod heading;
 mod identity;
 mod input_field;
 mod mnemonic;
+mod qr_code;
 mod read_only_field;
 mod version;
 mod view;
@@ -17,6 +18,7 @@ pub use self::heading::Heading;
 pub use self::identity::Identity;
 pub use self::input_field::InputField;
 pub use self::mnemonic::MnemonicViewer;
+pub use self::qr_code::QrCodeViewer;
 pub use self::read_only_field::ReadOnlyField;
 pub use self::version::Version;
 pub use self::view::View;
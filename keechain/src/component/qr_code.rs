@@ -0,0 +1,55 @@
+// Copyright (c) 2022-2023 Yuki Kishimoto
+// Distributed under the MIT software license
+
+use eframe::egui::{Color32, Rect, Rounding, Sense, Ui, Vec2};
+use qrcode::{Color, QrCode};
+
+use super::Error;
+
+const QR_CODE_SIZE: f32 = 240.0;
+/// Modules of white border around the code
+const QUIET_ZONE: usize = 4;
+
+pub struct QrCodeViewer {
+    data: String,
+}
+
+impl QrCodeViewer {
+    pub fn new<S>(data: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self { data: data.into() }
+    }
+
+    pub fn render(self, ui: &mut Ui) {
+        let code: QrCode = match QrCode::new(self.data.as_bytes()) {
+            Ok(code) => code,
+            Err(e) => {
+                Error::new(e.to_string()).render(ui);
+                return;
+            }
+        };
+
+        let width: usize = code.width();
+        let module: f32 = QR_CODE_SIZE / (width + 2 * QUIET_ZONE) as f32;
+
+        ui.vertical_centered(|ui| {
+            let (response, painter) =
+                ui.allocate_painter(Vec2::splat(QR_CODE_SIZE), Sense::hover());
+            let rect: Rect = response.rect;
+            painter.rect_filled(rect, Rounding::none(), Color32::WHITE);
+            for (index, color) in code.to_colors().into_iter().enumerate() {
+                if color == Color::Dark {
+                    let x: f32 = (index % width + QUIET_ZONE) as f32 * module;
+                    let y: f32 = (index / width + QUIET_ZONE) as f32 * module;
+                    painter.rect_filled(
+                        Rect::from_min_size(rect.min + Vec2::new(x, y), Vec2::splat(module)),
+                        Rounding::none(),
+                        Color32::BLACK,
+                    );
+                }
+            }
+        });
+    }
+}
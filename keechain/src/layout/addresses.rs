@@ -0,0 +1,234 @@
+// Copyright (c) 2022-2023 Yuki Kishimoto
+// Distributed under the MIT software license
+
+use std::str::FromStr;
+
+use eframe::egui::{Align, ComboBox, Layout, RichText, Ui};
+use keechain_core::descriptors::DerivedAddress;
+use keechain_core::{Index, KeeChain, Purpose, Result};
+
+use crate::component::{
+    Button, Error, Heading, Identity, InputField, QrCodeViewer, ReadOnlyField, View,
+};
+use crate::theme::color::ORANGE;
+use crate::{AppState, Menu, Stage, SECP256K1};
+
+const ADDRESSES_PER_PAGE: u32 = 10;
+
+const PURPOSES: [(Purpose, &str); 4] = [
+    (Purpose::BIP44, "Legacy - P2PKH (BIP44)"),
+    (Purpose::BIP49, "Nested Segwit - P2SH-WPKH (BIP49)"),
+    (Purpose::BIP84, "Native Segwit - P2WPKH (BIP84)"),
+    (Purpose::BIP86, "Taproot - P2TR (BIP86)"),
+];
+
+fn derive_addresses(
+    keechain: &KeeChain,
+    state: &AddressesState,
+    from: u32,
+) -> Result<Vec<DerivedAddress>> {
+    let password: String = state.password.clone();
+    if state.wallet.is_empty() {
+        let account: Index = if state.account.is_empty() {
+            Index::new(0)?
+        } else {
+            Index::from_str(state.account.as_str())?
+        };
+        Ok(keechain.addresses(
+            password,
+            state.purpose,
+            Some(account.as_u32()),
+            state.change,
+            from,
+            ADDRESSES_PER_PAGE,
+            &SECP256K1,
+        )?)
+    } else {
+        Ok(keechain.multisig_addresses(
+            password,
+            state.wallet.clone(),
+            state.change,
+            from,
+            ADDRESSES_PER_PAGE,
+            &SECP256K1,
+        )?)
+    }
+}
+
+pub struct AddressesState {
+    password: String,
+    purpose: Purpose,
+    wallet: String,
+    account: String,
+    change: bool,
+    from: String,
+    addresses: Vec<DerivedAddress>,
+    qr: Option<usize>,
+    error: Option<String>,
+}
+
+impl Default for AddressesState {
+    fn default() -> Self {
+        Self {
+            password: String::new(),
+            purpose: Purpose::BIP84,
+            wallet: String::new(),
+            account: String::new(),
+            change: false,
+            from: String::new(),
+            addresses: Vec::new(),
+            qr: None,
+            error: None,
+        }
+    }
+}
+
+impl AddressesState {
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+}
+
+pub fn update(app: &mut AppState, ui: &mut Ui) {
+    if app.keechain.is_none() {
+        app.set_stage(Stage::Start);
+    }
+
+    View::show(ui, |ui| {
+        Heading::new("Addresses").render(ui);
+
+        if let Some(keechain) = &app.keechain {
+            Identity::new(keechain.identity(), keechain.passphrase()).render(ui);
+            ui.add_space(15.0);
+        }
+
+        InputField::new("Password")
+            .placeholder("Password")
+            .is_password()
+            .render(ui, &mut app.layouts.addresses.password);
+
+        ui.add_space(7.0);
+
+        InputField::new("Multisig wallet")
+            .placeholder("Wallet name (leave empty for single-sig)")
+            .render(ui, &mut app.layouts.addresses.wallet);
+
+        if app.layouts.addresses.wallet.is_empty() {
+            ui.add_space(7.0);
+
+            ui.with_layout(Layout::top_down(Align::Min), |ui| {
+                ui.add_space(1.0);
+                ui.label("Address type");
+                ui.horizontal_wrapped(|ui| {
+                    ComboBox::from_id_source("addresses_purpose")
+                        .width(ui.available_width())
+                        .selected_text(
+                            PURPOSES
+                                .iter()
+                                .find(|&&p| p.0 == app.layouts.addresses.purpose)
+                                .map(|p| p.1)
+                                .unwrap_or("Impossible to get value"),
+                        )
+                        .show_ui(ui, |ui| {
+                            for (purpose, label) in PURPOSES.into_iter() {
+                                ui.selectable_value(
+                                    &mut app.layouts.addresses.purpose,
+                                    purpose,
+                                    label,
+                                );
+                            }
+                        });
+                })
+            });
+
+            ui.add_space(7.0);
+
+            InputField::new("Account")
+                .placeholder("0")
+                .render(ui, &mut app.layouts.addresses.account);
+        }
+
+        ui.add_space(7.0);
+
+        ui.checkbox(&mut app.layouts.addresses.change, "Change addresses");
+
+        ui.add_space(7.0);
+
+        InputField::new("First index")
+            .placeholder("0")
+            .render(ui, &mut app.layouts.addresses.from);
+
+        if !app.layouts.addresses.addresses.is_empty() {
+            ui.add_space(7.0);
+            ui.group(|ui| {
+                for (position, derived) in app.layouts.addresses.addresses.iter().enumerate() {
+                    ui.horizontal(|ui| {
+                        if ui.small_button("QR").clicked() {
+                            app.layouts.addresses.qr = Some(position);
+                        }
+                        ui.label(
+                            RichText::new(format!(
+                                "{}: {} ({})",
+                                derived.index, derived.address, derived.path
+                            ))
+                            .small(),
+                        );
+                    });
+                }
+            });
+        }
+
+        if let Some(derived) = app
+            .layouts
+            .addresses
+            .qr
+            .and_then(|position| app.layouts.addresses.addresses.get(position))
+        {
+            ui.add_space(7.0);
+            ReadOnlyField::new(derived.path.to_string(), derived.address.to_string()).render(ui);
+            ui.add_space(7.0);
+            QrCodeViewer::new(derived.address.to_qr_uri()).render(ui);
+        }
+
+        if let Some(error) = &app.layouts.addresses.error {
+            ui.add_space(7.0);
+            Error::new(error).render(ui);
+        }
+
+        ui.add_space(15.0);
+
+        let is_ready: bool = !app.layouts.addresses.password.is_empty();
+
+        let button = Button::new("Show addresses")
+            .background_color(ORANGE)
+            .enabled(is_ready)
+            .render(ui);
+
+        if is_ready && button.clicked() {
+            let from: u32 = app.layouts.addresses.from.parse().unwrap_or_default();
+            match app.keechain.as_ref() {
+                Some(keechain) => match derive_addresses(keechain, &app.layouts.addresses, from) {
+                    Ok(addresses) => {
+                        app.layouts.addresses.error = None;
+                        app.layouts.addresses.qr = None;
+                        app.layouts.addresses.addresses = addresses;
+                    }
+                    Err(e) => {
+                        app.layouts.addresses.addresses.clear();
+                        app.layouts.addresses.error = Some(e.to_string());
+                    }
+                },
+                None => {
+                    app.layouts.addresses.error = Some("Impossible to get keechain".to_string())
+                }
+            }
+        }
+
+        ui.add_space(5.0);
+
+        if Button::new("Back").render(ui).clicked() {
+            app.layouts.addresses.clear();
+            app.stage = Stage::Menu(Menu::Main);
+        }
+    });
+}
@@ -0,0 +1,113 @@
+// Copyright (c) 2022-2023 Yuki Kishimoto
+// Distributed under the MIT software license
+
+use std::str::FromStr;
+
+use eframe::egui::{Key, Ui};
+use keechain_core::bips::bip32::{DerivationPath, ExtendedPrivKey};
+use keechain_core::{KeeChain, Result};
+
+use crate::component::{Button, Error, Heading, InputField, ReadOnlyField, View};
+use crate::theme::color::ORANGE;
+use crate::{AppState, Menu, Stage, SECP256K1};
+
+fn extended_private_key(
+    keechain: &KeeChain,
+    state: &ExportPrivateKeyState,
+) -> Result<(DerivationPath, ExtendedPrivKey)> {
+    let path = DerivationPath::from_str(state.path.trim())?;
+    let xprv: ExtendedPrivKey = keechain
+        .keychain(state.password.clone())?
+        .extended_private_key(keechain.network(), &path, &SECP256K1)?;
+    Ok((path, xprv))
+}
+
+#[derive(Default)]
+pub struct ExportPrivateKeyState {
+    password: String,
+    path: String,
+    key: Option<(DerivationPath, ExtendedPrivKey)>,
+    error: Option<String>,
+}
+
+impl ExportPrivateKeyState {
+    pub fn clear(&mut self) {
+        self.password = String::new();
+        self.path = String::new();
+        self.key = None;
+        self.error = None;
+    }
+}
+
+pub fn update(app: &mut AppState, ui: &mut Ui) {
+    if app.keechain.is_none() {
+        app.set_stage(Stage::Start);
+    }
+
+    View::show(ui, |ui| {
+        Heading::new("Export private key").render(ui);
+
+        if let Some((path, xprv)) = &app.layouts.export_private_key.key {
+            ReadOnlyField::new("Path", path.to_string())
+                .rows(1)
+                .render(ui);
+            ui.add_space(5.0);
+            ReadOnlyField::new("Extended private key", xprv.to_string())
+                .rows(3)
+                .render(ui);
+            ui.add_space(5.0);
+            ReadOnlyField::new("WIF", xprv.to_priv().to_wif())
+                .rows(2)
+                .render(ui);
+            ui.add_space(10.0);
+        } else {
+            InputField::new("Password")
+                .placeholder("Password")
+                .is_password()
+                .render(ui, &mut app.layouts.export_private_key.password);
+
+            ui.add_space(7.0);
+
+            InputField::new("Derivation path")
+                .placeholder("m/0'/0'/0'")
+                .render(ui, &mut app.layouts.export_private_key.path);
+
+            ui.add_space(7.0);
+
+            if let Some(error) = &app.layouts.export_private_key.error {
+                Error::new(error).render(ui);
+            }
+
+            ui.add_space(15.0);
+
+            let is_ready: bool = !app.layouts.export_private_key.password.is_empty()
+                && !app.layouts.export_private_key.path.is_empty();
+
+            let button = Button::new("Export")
+                .background_color(ORANGE)
+                .enabled(is_ready)
+                .render(ui);
+
+            if is_ready && (ui.input(|i| i.key_pressed(Key::Enter)) || button.clicked()) {
+                match app.keechain.as_ref() {
+                    Some(keechain) => {
+                        match extended_private_key(keechain, &app.layouts.export_private_key) {
+                            Ok(key) => app.layouts.export_private_key.key = Some(key),
+                            Err(e) => app.layouts.export_private_key.error = Some(e.to_string()),
+                        }
+                    }
+                    None => {
+                        app.layouts.export_private_key.error =
+                            Some("Impossible to get keechain".to_string())
+                    }
+                }
+            }
+        }
+
+        ui.add_space(5.0);
+        if Button::new("Back").render(ui).clicked() {
+            app.layouts.export_private_key.clear();
+            app.stage = Stage::Menu(Menu::Danger);
+        }
+    });
+}
@@ -1,5 +1,6 @@
 // Copyright (c) 2022-2023 Yuki Kishimoto
 // Distributed under the MIT software license
 
+pub mod export_private_key;
 pub mod view_secrets;
 pub mod wipe;
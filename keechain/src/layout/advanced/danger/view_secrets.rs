@@ -2,7 +2,7 @@
 // Distributed under the MIT software license
 
 use eframe::egui::{Key, Ui};
-use keechain_core::types::Secrets;
+use keechain_core::types::{Secrets, SeedKind};
 
 use crate::component::{Button, Error, Heading, InputField, MnemonicViewer, ReadOnlyField, View};
 use crate::theme::color::ORANGE;
@@ -32,11 +32,23 @@ pub fn update(app: &mut AppState, ui: &mut Ui) {
         Heading::new("View secrets").render(ui);
 
         if let Some(secrets) = &app.layouts.view_secrets.secrets {
-            ReadOnlyField::new("Entropy", &secrets.entropy)
-                .rows(2)
-                .render(ui);
-            ui.add_space(5.0);
-            MnemonicViewer::new(secrets.mnemonic.clone()).render(ui);
+            if let Some(entropy) = &secrets.entropy {
+                ReadOnlyField::new("Entropy", entropy).rows(2).render(ui);
+                ui.add_space(5.0);
+            }
+            if secrets.mnemonic.is_mnemonic() {
+                MnemonicViewer::new(secrets.mnemonic.clone()).render(ui);
+            } else {
+                ReadOnlyField::new(secrets.mnemonic.name(), secrets.mnemonic.to_string())
+                    .rows(2)
+                    .render(ui);
+            }
+            if let SeedKind::Aezeed(seed) = &secrets.mnemonic {
+                ui.add_space(5.0);
+                ReadOnlyField::new("Birthday", seed.birthday_date())
+                    .rows(1)
+                    .render(ui);
+            }
             if let Some(passphrase) = secrets.passphrase.as_ref() {
                 ui.add_space(5.0);
                 ReadOnlyField::new("Passphrase", passphrase)
@@ -1,35 +1,139 @@
 // Copyright (c) 2022-2023 Yuki Kishimoto
 // Distributed under the MIT software license
 
+use core::fmt;
+use std::num::ParseIntError;
 use std::str::FromStr;
 
-use eframe::egui::{Align, ComboBox, Key, Layout, Ui};
-use keechain_core::bips::bip39::Mnemonic;
+use eframe::egui::{Align, ComboBox, Key, Layout, RichText, Ui};
+use keechain_core::bips::bip39::{Language, Mnemonic};
+use keechain_core::bips::bip85::Application;
 use keechain_core::types::{Index, WordCount};
 
-use crate::component::{Button, Error, Heading, InputField, MnemonicViewer, View};
-use crate::theme::color::ORANGE;
+use crate::component::{Button, Error, Heading, InputField, MnemonicViewer, ReadOnlyField, View};
+use crate::theme::color::{DARK_GREEN, ORANGE};
 use crate::{AppState, Menu, Stage, SECP256K1};
 
 const WORD_COUNT_OPTIONS: [WordCount; 3] = [WordCount::W12, WordCount::W18, WordCount::W24];
+/// BIP39 languages with a BIP85 code
+const LANGUAGE_OPTIONS: [Language; 9] = [
+    Language::English,
+    Language::Japanese,
+    Language::Korean,
+    Language::Spanish,
+    Language::SimplifiedChinese,
+    Language::TraditionalChinese,
+    Language::French,
+    Language::Italian,
+    Language::Czech,
+];
+const APPLICATION_OPTIONS: [ApplicationKind; 7] = [
+    ApplicationKind::Mnemonic,
+    ApplicationKind::Hex,
+    ApplicationKind::Wif,
+    ApplicationKind::Xprv,
+    ApplicationKind::PwdBase64,
+    ApplicationKind::PwdBase85,
+    ApplicationKind::Dice,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ApplicationKind {
+    Mnemonic,
+    Hex,
+    Wif,
+    Xprv,
+    PwdBase64,
+    PwdBase85,
+    Dice,
+}
+
+impl Default for ApplicationKind {
+    fn default() -> Self {
+        Self::Mnemonic
+    }
+}
+
+impl fmt::Display for ApplicationKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Mnemonic => write!(f, "BIP39 mnemonic"),
+            Self::Hex => write!(f, "HEX"),
+            Self::Wif => write!(f, "WIF"),
+            Self::Xprv => write!(f, "XPRV"),
+            Self::PwdBase64 => write!(f, "Password (base64)"),
+            Self::PwdBase85 => write!(f, "Password (base85)"),
+            Self::Dice => write!(f, "Dice"),
+        }
+    }
+}
 
 #[derive(Default)]
 pub struct DeterministicEntropyState {
     password: String,
+    application: ApplicationKind,
+    language: Language,
     word_count: WordCount,
+    num_bytes: String,
+    length: String,
+    sides: String,
+    rolls: String,
     index: String,
     mnemonic: Option<Mnemonic>,
+    /// Derivation of the displayed mnemonic
+    child: Option<(Language, WordCount, Index)>,
+    derived: Option<String>,
+    new_name: String,
+    new_password: String,
+    confirm_new_password: String,
+    saved: Option<String>,
     error: Option<String>,
 }
 
 impl DeterministicEntropyState {
     pub fn clear(&mut self) {
         self.password.clear();
+        self.application = ApplicationKind::Mnemonic;
+        self.language = Language::English;
         self.word_count = WordCount::W24;
+        self.num_bytes = String::new();
+        self.length = String::new();
+        self.sides = String::new();
+        self.rolls = String::new();
         self.index = String::new();
         self.mnemonic = None;
+        self.child = None;
+        self.derived = None;
+        self.new_name.clear();
+        self.new_password.clear();
+        self.confirm_new_password.clear();
+        self.saved = None;
         self.error = None;
     }
+
+    fn application(&self) -> Result<Application, ParseIntError> {
+        Ok(match self.application {
+            ApplicationKind::Mnemonic => Application::Mnemonic {
+                language: self.language,
+                word_count: self.word_count,
+            },
+            ApplicationKind::Hex => Application::Hex {
+                num_bytes: self.num_bytes.parse()?,
+            },
+            ApplicationKind::Wif => Application::Wif,
+            ApplicationKind::Xprv => Application::Xprv,
+            ApplicationKind::PwdBase64 => Application::PwdBase64 {
+                length: self.length.parse()?,
+            },
+            ApplicationKind::PwdBase85 => Application::PwdBase85 {
+                length: self.length.parse()?,
+            },
+            ApplicationKind::Dice => Application::Dice {
+                sides: self.sides.parse()?,
+                rolls: self.rolls.parse()?,
+            },
+        })
+    }
 }
 
 pub fn update(app: &mut AppState, ui: &mut Ui) {
@@ -49,23 +153,17 @@ pub fn update(app: &mut AppState, ui: &mut Ui) {
 
         ui.with_layout(Layout::top_down(Align::Min), |ui| {
             ui.add_space(1.0);
-            ui.label("Word count");
+            ui.label("Application");
             ui.horizontal_wrapped(|ui| {
-                ComboBox::from_id_source("word_count")
+                ComboBox::from_id_source("application")
                     .width(ui.available_width())
-                    .selected_text(
-                        app.layouts
-                            .deterministic_entropy
-                            .word_count
-                            .as_u32()
-                            .to_string(),
-                    )
+                    .selected_text(app.layouts.deterministic_entropy.application.to_string())
                     .show_ui(ui, |ui| {
-                        for value in WORD_COUNT_OPTIONS.into_iter() {
+                        for value in APPLICATION_OPTIONS.into_iter() {
                             ui.selectable_value(
-                                &mut app.layouts.deterministic_entropy.word_count,
+                                &mut app.layouts.deterministic_entropy.application,
                                 value,
-                                value.as_u32().to_string(),
+                                value.to_string(),
                             );
                         }
                     });
@@ -74,6 +172,87 @@ pub fn update(app: &mut AppState, ui: &mut Ui) {
 
         ui.add_space(7.0);
 
+        match app.layouts.deterministic_entropy.application {
+            ApplicationKind::Mnemonic => {
+                ui.with_layout(Layout::top_down(Align::Min), |ui| {
+                    ui.add_space(1.0);
+                    ui.label("Language");
+                    ui.horizontal_wrapped(|ui| {
+                        ComboBox::from_id_source("language")
+                            .width(ui.available_width())
+                            .selected_text(app.layouts.deterministic_entropy.language.to_string())
+                            .show_ui(ui, |ui| {
+                                for value in LANGUAGE_OPTIONS.into_iter() {
+                                    ui.selectable_value(
+                                        &mut app.layouts.deterministic_entropy.language,
+                                        value,
+                                        value.to_string(),
+                                    );
+                                }
+                            });
+                    })
+                });
+
+                ui.add_space(7.0);
+
+                ui.with_layout(Layout::top_down(Align::Min), |ui| {
+                    ui.add_space(1.0);
+                    ui.label("Word count");
+                    ui.horizontal_wrapped(|ui| {
+                        ComboBox::from_id_source("word_count")
+                            .width(ui.available_width())
+                            .selected_text(
+                                app.layouts
+                                    .deterministic_entropy
+                                    .word_count
+                                    .as_u32()
+                                    .to_string(),
+                            )
+                            .show_ui(ui, |ui| {
+                                for value in WORD_COUNT_OPTIONS.into_iter() {
+                                    ui.selectable_value(
+                                        &mut app.layouts.deterministic_entropy.word_count,
+                                        value,
+                                        value.as_u32().to_string(),
+                                    );
+                                }
+                            });
+                    })
+                });
+
+                ui.add_space(7.0);
+            }
+            ApplicationKind::Hex => {
+                InputField::new("Number of bytes")
+                    .placeholder("Number of bytes (between 16 and 64)")
+                    .render(ui, &mut app.layouts.deterministic_entropy.num_bytes);
+                ui.add_space(7.0);
+            }
+            ApplicationKind::PwdBase64 => {
+                InputField::new("Length")
+                    .placeholder("Password length (between 20 and 86)")
+                    .render(ui, &mut app.layouts.deterministic_entropy.length);
+                ui.add_space(7.0);
+            }
+            ApplicationKind::PwdBase85 => {
+                InputField::new("Length")
+                    .placeholder("Password length (between 10 and 80)")
+                    .render(ui, &mut app.layouts.deterministic_entropy.length);
+                ui.add_space(7.0);
+            }
+            ApplicationKind::Dice => {
+                InputField::new("Sides")
+                    .placeholder("Number of sides")
+                    .render(ui, &mut app.layouts.deterministic_entropy.sides);
+                ui.add_space(7.0);
+                InputField::new("Rolls")
+                    .placeholder("Number of rolls")
+                    .render(ui, &mut app.layouts.deterministic_entropy.rolls);
+                ui.add_space(7.0);
+            }
+            ApplicationKind::Wif | ApplicationKind::Xprv => (),
+        }
+
         InputField::new("Index")
             .placeholder("Index (between 0 and 2^31 - 1)")
             .render(ui, &mut app.layouts.deterministic_entropy.index);
@@ -83,6 +262,50 @@ pub fn update(app: &mut AppState, ui: &mut Ui) {
         if let Some(mnemonic) = app.layouts.deterministic_entropy.mnemonic.as_ref() {
             MnemonicViewer::new(mnemonic.clone()).render(ui);
             ui.add_space(7.0);
+
+            InputField::new("New keychain name")
+                .placeholder("Name of the child keychain")
+                .render(ui, &mut app.layouts.deterministic_entropy.new_name);
+            ui.add_space(7.0);
+            InputField::new("New keychain password")
+                .placeholder("Password of the child keychain")
+                .is_password()
+                .render(ui, &mut app.layouts.deterministic_entropy.new_password);
+            ui.add_space(7.0);
+            InputField::new("Confirm new keychain password")
+                .placeholder("Confirm password")
+                .is_password()
+                .render(
+                    ui,
+                    &mut app.layouts.deterministic_entropy.confirm_new_password,
+                );
+            ui.add_space(7.0);
+
+            if let Some(saved) = &app.layouts.deterministic_entropy.saved {
+                ui.label(RichText::new(saved).color(DARK_GREEN));
+                ui.add_space(7.0);
+            }
+
+            let can_save: bool = !app.layouts.deterministic_entropy.new_name.is_empty()
+                && !app.layouts.deterministic_entropy.new_password.is_empty();
+            if Button::new("Save as keychain")
+                .enabled(can_save)
+                .render(ui)
+                .clicked()
+            {
+                save_child_keychain(app);
+            }
+            ui.add_space(7.0);
+        }
+
+        if let Some(derived) = app.layouts.deterministic_entropy.derived.as_ref() {
+            ReadOnlyField::new(
+                app.layouts.deterministic_entropy.application.to_string(),
+                derived,
+            )
+            .rows(2)
+            .render(ui);
+            ui.add_space(7.0);
         }
 
         if let Some(error) = &app.layouts.deterministic_entropy.error {
@@ -101,20 +324,45 @@ pub fn update(app: &mut AppState, ui: &mut Ui) {
         if is_ready && (ui.input(|i| i.key_pressed(Key::Enter)) || button.clicked()) {
             match app.keechain.as_mut() {
                 Some(keechain) => {
-                    match Index::from_str(app.layouts.deterministic_entropy.index.as_str()) {
-                        Ok(index) => match keechain.deterministic_entropy(
-                            app.layouts.deterministic_entropy.password.clone(),
-                            app.layouts.deterministic_entropy.word_count,
-                            index,
-                            &SECP256K1,
-                        ) {
-                            Ok(mnemonic) => {
-                                app.layouts.deterministic_entropy.error = None;
-                                app.layouts.deterministic_entropy.mnemonic = Some(mnemonic);
-                            }
-                            Err(e) => app.layouts.deterministic_entropy.error = Some(e.to_string()),
+                    let state = &mut app.layouts.deterministic_entropy;
+                    state.mnemonic = None;
+                    state.child = None;
+                    state.derived = None;
+                    state.saved = None;
+                    match Index::from_str(state.index.as_str()) {
+                        Ok(index) => match state.application() {
+                            Ok(Application::Mnemonic {
+                                language,
+                                word_count,
+                            }) => match keechain.deterministic_entropy(
+                                state.password.clone(),
+                                language,
+                                word_count,
+                                index,
+                                &SECP256K1,
+                            ) {
+                                Ok(mnemonic) => {
+                                    state.error = None;
+                                    state.mnemonic = Some(mnemonic);
+                                    state.child = Some((language, word_count, index));
+                                }
+                                Err(e) => state.error = Some(e.to_string()),
+                            },
+                            Ok(application) => match keechain.bip85(
+                                state.password.clone(),
+                                application,
+                                index,
+                                &SECP256K1,
+                            ) {
+                                Ok(derived) => {
+                                    state.error = None;
+                                    state.derived = Some(derived);
+                                }
+                                Err(e) => state.error = Some(e.to_string()),
+                            },
+                            Err(e) => state.error = Some(e.to_string()),
                         },
-                        Err(e) => app.layouts.deterministic_entropy.error = Some(e.to_string()),
+                        Err(e) => state.error = Some(e.to_string()),
                     }
                 }
                 None => {
@@ -131,3 +379,35 @@ pub fn update(app: &mut AppState, ui: &mut Ui) {
         }
     });
 }
+
+fn save_child_keychain(app: &mut AppState) {
+    let state = &mut app.layouts.deterministic_entropy;
+    if state.new_password != state.confirm_new_password {
+        state.error = Some("Passwords don't match".to_string());
+        return;
+    }
+
+    match (app.keechain.as_ref(), state.child) {
+        (Some(keechain), Some((language, word_count, index))) => {
+            match keechain.derive_child_keychain(
+                state.password.clone(),
+                language,
+                word_count,
+                index,
+                state.new_name.clone(),
+                state.new_password.clone(),
+                &SECP256K1,
+            ) {
+                Ok(_) => {
+                    state.error = None;
+                    state.saved = Some(format!("Keychain '{}' saved", state.new_name));
+                    state.new_name.clear();
+                    state.new_password.clear();
+                    state.confirm_new_password.clear();
+                }
+                Err(e) => state.error = Some(e.to_string()),
+            }
+        }
+        _ => state.error = Some("Impossible to get keechain".to_string()),
+    }
+}
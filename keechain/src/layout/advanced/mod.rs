@@ -3,3 +3,5 @@
 
 pub mod danger;
 pub mod deterministic_entropy;
+pub mod seed_xor;
+pub mod vanity;
@@ -0,0 +1,94 @@
+// Copyright (c) 2022-2023 Yuki Kishimoto
+// Distributed under the MIT software license
+
+use eframe::egui::{Key, Ui};
+use keechain_core::bips::bip39::Mnemonic;
+
+use crate::component::{Button, Error, Heading, InputField, MnemonicViewer, View};
+use crate::theme::color::ORANGE;
+use crate::{AppState, Menu, Stage};
+
+#[derive(Default)]
+pub struct SeedXorState {
+    password: String,
+    parts: String,
+    mnemonics: Vec<Mnemonic>,
+    error: Option<String>,
+}
+
+impl SeedXorState {
+    pub fn clear(&mut self) {
+        self.password.clear();
+        self.parts = String::new();
+        self.mnemonics.clear();
+        self.error = None;
+    }
+}
+
+pub fn update(app: &mut AppState, ui: &mut Ui) {
+    if app.keechain.is_none() {
+        app.set_stage(Stage::Start);
+    }
+
+    View::show(ui, |ui| {
+        Heading::new("Seed XOR").render(ui);
+
+        InputField::new("Password")
+            .placeholder("Password")
+            .is_password()
+            .render(ui, &mut app.layouts.seed_xor.password);
+
+        ui.add_space(7.0);
+
+        InputField::new("Parts")
+            .placeholder("Number of parts (at least 2)")
+            .render(ui, &mut app.layouts.seed_xor.parts);
+
+        ui.add_space(7.0);
+
+        for (index, mnemonic) in app.layouts.seed_xor.mnemonics.iter().enumerate() {
+            ui.label(format!("Part {}", index + 1));
+            ui.push_id(index, |ui| {
+                MnemonicViewer::new(mnemonic.clone()).render(ui);
+            });
+            ui.add_space(7.0);
+        }
+
+        if let Some(error) = &app.layouts.seed_xor.error {
+            Error::new(error).render(ui);
+        }
+
+        ui.add_space(15.0);
+
+        let is_ready: bool = !app.layouts.seed_xor.parts.is_empty();
+
+        let button = Button::new("Split")
+            .background_color(ORANGE)
+            .enabled(is_ready)
+            .render(ui);
+
+        if is_ready && (ui.input(|i| i.key_pressed(Key::Enter)) || button.clicked()) {
+            match app.keechain.as_ref() {
+                Some(keechain) => match app.layouts.seed_xor.parts.parse::<usize>() {
+                    Ok(parts) => {
+                        match keechain.seed_xor(app.layouts.seed_xor.password.clone(), parts) {
+                            Ok(mnemonics) => {
+                                app.layouts.seed_xor.error = None;
+                                app.layouts.seed_xor.mnemonics = mnemonics;
+                            }
+                            Err(e) => app.layouts.seed_xor.error = Some(e.to_string()),
+                        }
+                    }
+                    Err(e) => app.layouts.seed_xor.error = Some(e.to_string()),
+                },
+                None => app.layouts.seed_xor.error = Some("Impossible to get keechain".to_string()),
+            }
+        }
+
+        ui.add_space(5.0);
+        if Button::new("Back").render(ui).clicked() {
+            app.layouts.seed_xor.clear();
+            app.stage = Stage::Menu(Menu::Advanced);
+        }
+    });
+}
@@ -0,0 +1,339 @@
+// Copyright (c) 2022-2023 Yuki Kishimoto
+// Distributed under the MIT software license
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use eframe::egui::{Align, ComboBox, Layout, RichText, Ui};
+use keechain_core::descriptors::DerivedAddress;
+use keechain_core::vanity::{Pattern, Progress};
+use keechain_core::{Index, KeeChain, Purpose};
+
+use crate::component::{
+    Button, Error, Heading, Identity, InputField, QrCodeViewer, ReadOnlyField, View,
+};
+use crate::theme::color::{DARK_RED, ORANGE};
+use crate::{AppState, Menu, Stage, SECP256K1};
+
+const PURPOSES: [(Purpose, &str); 4] = [
+    (Purpose::BIP44, "Legacy - P2PKH (BIP44)"),
+    (Purpose::BIP49, "Nested Segwit - P2SH-WPKH (BIP49)"),
+    (Purpose::BIP84, "Native Segwit - P2WPKH (BIP84)"),
+    (Purpose::BIP86, "Taproot - P2TR (BIP86)"),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PatternKind {
+    Prefix,
+    Suffix,
+    Regex,
+}
+
+const PATTERN_KINDS: [(PatternKind, &str); 3] = [
+    (PatternKind::Prefix, "Prefix"),
+    (PatternKind::Suffix, "Suffix"),
+    (PatternKind::Regex, "Regex"),
+];
+
+enum VanityMessage {
+    Progress(Progress),
+    Done(Result<Option<DerivedAddress>, String>),
+}
+
+/// Run the search in a background thread, to keep the UI responsive
+fn start_search(
+    keechain: KeeChain,
+    password: String,
+    purpose: Purpose,
+    account: u32,
+    change: bool,
+    pattern: Pattern,
+    stop: Arc<AtomicBool>,
+) -> Receiver<VanityMessage> {
+    let (sender, receiver) = mpsc::channel::<VanityMessage>();
+    thread::spawn(move || {
+        let progress_sender = sender.clone();
+        let result = keechain
+            .vanity_address(
+                password,
+                purpose,
+                Some(account),
+                change,
+                pattern,
+                None,
+                stop,
+                |progress| {
+                    let _ = progress_sender.send(VanityMessage::Progress(progress));
+                },
+                &SECP256K1,
+            )
+            .map_err(|e| e.to_string());
+        let _ = sender.send(VanityMessage::Done(result));
+    });
+    receiver
+}
+
+fn format_duration(duration: Duration) -> String {
+    let seconds: u64 = duration.as_secs();
+    if seconds >= 86400 {
+        format!("{}d {}h", seconds / 86400, seconds % 86400 / 3600)
+    } else {
+        format!(
+            "{}h {}m {}s",
+            seconds / 3600,
+            seconds % 3600 / 60,
+            seconds % 60
+        )
+    }
+}
+
+pub struct VanityState {
+    password: String,
+    purpose: Purpose,
+    account: String,
+    change: bool,
+    kind: PatternKind,
+    pattern: String,
+    stop: Arc<AtomicBool>,
+    receiver: Option<Receiver<VanityMessage>>,
+    progress: Option<Progress>,
+    found: Option<DerivedAddress>,
+    error: Option<String>,
+}
+
+impl Default for VanityState {
+    fn default() -> Self {
+        Self {
+            password: String::new(),
+            purpose: Purpose::BIP84,
+            account: String::new(),
+            change: false,
+            kind: PatternKind::Prefix,
+            pattern: String::new(),
+            stop: Arc::new(AtomicBool::new(false)),
+            receiver: None,
+            progress: None,
+            found: None,
+            error: None,
+        }
+    }
+}
+
+impl VanityState {
+    pub fn clear(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        *self = Self::default();
+    }
+
+    fn is_running(&self) -> bool {
+        self.receiver.is_some()
+    }
+
+    fn pattern(&self) -> Result<Pattern, String> {
+        match self.kind {
+            PatternKind::Prefix => Ok(Pattern::Prefix(self.pattern.clone())),
+            PatternKind::Suffix => Ok(Pattern::Suffix(self.pattern.clone())),
+            PatternKind::Regex => Pattern::regex(&self.pattern).map_err(|e| e.to_string()),
+        }
+    }
+}
+
+pub fn update(app: &mut AppState, ui: &mut Ui) {
+    if app.keechain.is_none() {
+        app.set_stage(Stage::Start);
+    }
+
+    let messages: Vec<VanityMessage> = match &app.layouts.vanity.receiver {
+        Some(receiver) => receiver.try_iter().collect(),
+        None => Vec::new(),
+    };
+    for message in messages.into_iter() {
+        match message {
+            VanityMessage::Progress(progress) => app.layouts.vanity.progress = Some(progress),
+            VanityMessage::Done(result) => {
+                app.layouts.vanity.receiver = None;
+                match result {
+                    Ok(found) => app.layouts.vanity.found = found,
+                    Err(e) => app.layouts.vanity.error = Some(e),
+                }
+            }
+        }
+    }
+    if app.layouts.vanity.is_running() {
+        ui.ctx().request_repaint_after(Duration::from_millis(250));
+    }
+
+    View::show(ui, |ui| {
+        Heading::new("Vanity address").render(ui);
+
+        if let Some(keechain) = &app.keechain {
+            Identity::new(keechain.identity(), keechain.passphrase()).render(ui);
+            ui.add_space(15.0);
+        }
+
+        InputField::new("Password")
+            .placeholder("Password")
+            .is_password()
+            .render(ui, &mut app.layouts.vanity.password);
+
+        ui.add_space(7.0);
+
+        ui.with_layout(Layout::top_down(Align::Min), |ui| {
+            ui.add_space(1.0);
+            ui.label("Address type");
+            ui.horizontal_wrapped(|ui| {
+                ComboBox::from_id_source("vanity_purpose")
+                    .width(ui.available_width())
+                    .selected_text(
+                        PURPOSES
+                            .iter()
+                            .find(|&&p| p.0 == app.layouts.vanity.purpose)
+                            .map(|p| p.1)
+                            .unwrap_or("Impossible to get value"),
+                    )
+                    .show_ui(ui, |ui| {
+                        for (purpose, label) in PURPOSES.into_iter() {
+                            ui.selectable_value(&mut app.layouts.vanity.purpose, purpose, label);
+                        }
+                    });
+            })
+        });
+
+        ui.add_space(7.0);
+
+        InputField::new("Account")
+            .placeholder("0")
+            .render(ui, &mut app.layouts.vanity.account);
+
+        ui.add_space(7.0);
+
+        ui.checkbox(&mut app.layouts.vanity.change, "Change address");
+
+        ui.add_space(7.0);
+
+        ui.with_layout(Layout::top_down(Align::Min), |ui| {
+            ui.add_space(1.0);
+            ui.label("Match");
+            ui.horizontal_wrapped(|ui| {
+                ComboBox::from_id_source("vanity_pattern_kind")
+                    .width(ui.available_width())
+                    .selected_text(
+                        PATTERN_KINDS
+                            .iter()
+                            .find(|&&k| k.0 == app.layouts.vanity.kind)
+                            .map(|k| k.1)
+                            .unwrap_or("Impossible to get value"),
+                    )
+                    .show_ui(ui, |ui| {
+                        for (kind, label) in PATTERN_KINDS.into_iter() {
+                            ui.selectable_value(&mut app.layouts.vanity.kind, kind, label);
+                        }
+                    });
+            })
+        });
+
+        ui.add_space(7.0);
+
+        InputField::new("Pattern")
+            .placeholder("Pattern (bech32 prefixes can omit `bc1q`/`bc1p`)")
+            .render(ui, &mut app.layouts.vanity.pattern);
+
+        if let Some(progress) = &app.layouts.vanity.progress {
+            ui.add_space(7.0);
+            ui.group(|ui| {
+                ui.label(
+                    RichText::new(format!(
+                        "Tested: {} ({:.0} addr/s)",
+                        progress.tested, progress.rate
+                    ))
+                    .small(),
+                );
+                ui.label(
+                    RichText::new(format!("Elapsed: {}", format_duration(progress.elapsed)))
+                        .small(),
+                );
+                let expected: String = match progress.expected {
+                    Some(expected) => format_duration(expected),
+                    None => String::from("unknown"),
+                };
+                ui.label(RichText::new(format!("Expected: {expected}")).small());
+            });
+        }
+
+        if let Some(found) = &app.layouts.vanity.found {
+            ui.add_space(7.0);
+            ReadOnlyField::new(found.path.to_string(), found.address.to_string()).render(ui);
+            ui.add_space(7.0);
+            QrCodeViewer::new(found.address.to_qr_uri()).render(ui);
+        }
+
+        if let Some(error) = &app.layouts.vanity.error {
+            ui.add_space(7.0);
+            Error::new(error).render(ui);
+        }
+
+        ui.add_space(15.0);
+
+        if app.layouts.vanity.is_running() {
+            if Button::new("Stop")
+                .background_color(DARK_RED)
+                .render(ui)
+                .clicked()
+            {
+                app.layouts.vanity.stop.store(true, Ordering::Relaxed);
+            }
+        } else {
+            let is_ready: bool =
+                !app.layouts.vanity.password.is_empty() && !app.layouts.vanity.pattern.is_empty();
+
+            let button = Button::new("Search")
+                .background_color(ORANGE)
+                .enabled(is_ready)
+                .render(ui);
+
+            if is_ready && button.clicked() {
+                let account: Result<Index, String> = if app.layouts.vanity.account.is_empty() {
+                    Index::new(0).map_err(|e| e.to_string())
+                } else {
+                    app.layouts
+                        .vanity
+                        .account
+                        .parse::<Index>()
+                        .map_err(|e| e.to_string())
+                };
+                match (app.keechain.clone(), account, app.layouts.vanity.pattern()) {
+                    (Some(keechain), Ok(account), Ok(pattern)) => {
+                        let stop = Arc::new(AtomicBool::new(false));
+                        app.layouts.vanity.receiver = Some(start_search(
+                            keechain,
+                            app.layouts.vanity.password.clone(),
+                            app.layouts.vanity.purpose,
+                            account.as_u32(),
+                            app.layouts.vanity.change,
+                            pattern,
+                            stop.clone(),
+                        ));
+                        app.layouts.vanity.stop = stop;
+                        app.layouts.vanity.progress = None;
+                        app.layouts.vanity.found = None;
+                        app.layouts.vanity.error = None;
+                    }
+                    (None, ..) => {
+                        app.layouts.vanity.error = Some("Impossible to get keechain".to_string())
+                    }
+                    (_, Err(e), _) | (_, _, Err(e)) => app.layouts.vanity.error = Some(e),
+                }
+            }
+        }
+
+        ui.add_space(5.0);
+
+        if Button::new("Back").render(ui).clicked() {
+            app.layouts.vanity.clear();
+            app.stage = Stage::Menu(Menu::Advanced);
+        }
+    });
+}
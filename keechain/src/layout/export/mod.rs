@@ -6,6 +6,7 @@ use eframe::egui::Ui;
 pub mod bitcoin_core;
 pub mod descriptors;
 pub mod electrum;
+pub mod multisig_key;
 
 use crate::{AppState, ExportTypes};
 
@@ -14,5 +15,6 @@ pub fn update(app: &mut AppState, export_type: ExportTypes, ui: &mut Ui) {
         ExportTypes::Descriptors => self::descriptors::update(app, ui),
         ExportTypes::BitcoinCore => self::bitcoin_core::update(app, ui),
         ExportTypes::Electrum => self::electrum::update(app, ui),
+        ExportTypes::MultisigKey => self::multisig_key::update(app, ui),
     }
 }
@@ -0,0 +1,169 @@
+// Copyright (c) 2022-2023 Yuki Kishimoto
+// Distributed under the MIT software license
+
+use std::str::FromStr;
+
+use eframe::egui::{Align, ComboBox, Layout, Ui};
+use keechain_core::bdk::miniscript::DescriptorPublicKey;
+use keechain_core::bips::bip48::ScriptType;
+use keechain_core::bitcoin::Network;
+use keechain_core::{Index, KeeChain, Result};
+
+use crate::component::{Button, Error, Heading, Identity, InputField, ReadOnlyField, View};
+use crate::theme::color::ORANGE;
+use crate::{AppState, Menu, Stage, SECP256K1};
+
+fn export_multisig_key(
+    keechain: &KeeChain,
+    password: String,
+    network: Network,
+    script: ScriptType,
+    account: Option<u32>,
+) -> Result<DescriptorPublicKey> {
+    let descriptors = keechain
+        .keychain(password)?
+        .descriptors(network, account, &SECP256K1)?;
+    Ok(descriptors.multisig_key(script)?)
+}
+
+const SCRIPT_TYPES: [(ScriptType, &str); 3] = [
+    (ScriptType::P2SHWSH, "P2SH-P2WSH (BIP48/1')"),
+    (ScriptType::P2WSH, "P2WSH (BIP48/2')"),
+    (ScriptType::P2TR, "P2TR (BIP48/3')"),
+];
+
+pub struct ExportMultisigKeyState {
+    password: String,
+    script: ScriptType,
+    account: String,
+    key: Option<String>,
+    error: Option<String>,
+}
+
+impl Default for ExportMultisigKeyState {
+    fn default() -> Self {
+        Self {
+            password: String::new(),
+            script: ScriptType::P2WSH,
+            account: String::new(),
+            key: None,
+            error: None,
+        }
+    }
+}
+
+impl ExportMultisigKeyState {
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+}
+
+pub fn update(app: &mut AppState, ui: &mut Ui) {
+    if app.keechain.is_none() {
+        app.set_stage(Stage::Start);
+    }
+
+    View::show(ui, |ui| {
+        Heading::new("Export multisig key").render(ui);
+
+        if let Some(keechain) = &app.keechain {
+            Identity::new(keechain.identity(), keechain.passphrase()).render(ui);
+            ui.add_space(15.0);
+        }
+
+        InputField::new("Password")
+            .placeholder("Password")
+            .is_password()
+            .render(ui, &mut app.layouts.export_multisig_key.password);
+
+        ui.add_space(7.0);
+
+        ui.with_layout(Layout::top_down(Align::Min), |ui| {
+            ui.add_space(1.0);
+            ui.label("Script type");
+            ui.horizontal_wrapped(|ui| {
+                ComboBox::from_id_source("multisig_script_type")
+                    .width(ui.available_width())
+                    .selected_text(
+                        SCRIPT_TYPES
+                            .iter()
+                            .find(|&&t| t.0 == app.layouts.export_multisig_key.script)
+                            .map(|t| t.1)
+                            .unwrap_or("Impossible to get value"),
+                    )
+                    .show_ui(ui, |ui| {
+                        for (script, label) in SCRIPT_TYPES.into_iter() {
+                            ui.selectable_value(
+                                &mut app.layouts.export_multisig_key.script,
+                                script,
+                                label,
+                            );
+                        }
+                    });
+            })
+        });
+
+        ui.add_space(7.0);
+
+        InputField::new("Account")
+            .placeholder("Account (between 0 and 2^31 - 1)")
+            .render(ui, &mut app.layouts.export_multisig_key.account);
+
+        if let Some(key) = &app.layouts.export_multisig_key.key {
+            ui.add_space(7.0);
+            ReadOnlyField::new("Key", key).rows(4).render(ui);
+        }
+
+        if let Some(error) = &app.layouts.export_multisig_key.error {
+            ui.add_space(7.0);
+            Error::new(error).render(ui);
+        }
+
+        ui.add_space(15.0);
+
+        let is_ready: bool = !app.layouts.export_multisig_key.account.is_empty();
+
+        let button = Button::new("Export")
+            .background_color(ORANGE)
+            .enabled(is_ready)
+            .render(ui);
+
+        if is_ready && button.clicked() {
+            match app.keechain.as_ref() {
+                Some(keechain) => {
+                    match Index::from_str(app.layouts.export_multisig_key.account.as_str()) {
+                        Ok(index) => {
+                            match export_multisig_key(
+                                keechain,
+                                app.layouts.export_multisig_key.password.clone(),
+                                app.network,
+                                app.layouts.export_multisig_key.script,
+                                Some(index.as_u32()),
+                            ) {
+                                Ok(key) => {
+                                    app.layouts.export_multisig_key.error = None;
+                                    app.layouts.export_multisig_key.key = Some(key.to_string());
+                                }
+                                Err(e) => {
+                                    app.layouts.export_multisig_key.error = Some(e.to_string())
+                                }
+                            }
+                        }
+                        Err(e) => app.layouts.export_multisig_key.error = Some(e.to_string()),
+                    }
+                }
+                None => {
+                    app.layouts.export_multisig_key.error =
+                        Some("Impossible to get keechain".to_string())
+                }
+            }
+        }
+
+        ui.add_space(5.0);
+
+        if Button::new("Back").render(ui).clicked() {
+            app.layouts.export_multisig_key.clear();
+            app.stage = Stage::Menu(Menu::Export);
+        }
+    });
+}
@@ -0,0 +1,193 @@
+// Copyright (c) 2022-2023 Yuki Kishimoto
+// Distributed under the MIT software license
+
+use std::path::PathBuf;
+
+use eframe::egui::{Align, ComboBox, Layout, RichText, Ui};
+use eframe::epaint::Color32;
+use keechain_core::bdk::miniscript::{Descriptor, DescriptorPublicKey};
+use keechain_core::bitcoin::Network;
+use keechain_core::descriptors::{self, Descriptors};
+use keechain_core::export::coldcard::ColdcardGenericJson;
+use keechain_core::{Result, WatchOnly};
+use rfd::FileDialog;
+
+use crate::component::{Button, Heading, InputField, View};
+use crate::theme::color::{DARK_GREEN, ORANGE};
+use crate::{AppState, Stage, KEYCHAINS_PATH};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Source {
+    Descriptors,
+    Key,
+    Coldcard,
+}
+
+impl Default for Source {
+    fn default() -> Self {
+        Self::Descriptors
+    }
+}
+
+const SOURCES: [(Source, &str); 3] = [
+    (Source::Descriptors, "Descriptors"),
+    (Source::Key, "Account key (with origin)"),
+    (Source::Coldcard, "Coldcard generic JSON"),
+];
+
+#[derive(Default)]
+pub struct ImportWatchOnlyState {
+    name: String,
+    source: Source,
+    text: String,
+    coldcard_file: Option<PathBuf>,
+    error: Option<String>,
+}
+
+impl ImportWatchOnlyState {
+    pub fn clear(&mut self) {
+        self.name = String::new();
+        self.source = Source::default();
+        self.text = String::new();
+        self.coldcard_file = None;
+        self.error = None;
+    }
+
+    fn import(&self, network: Network) -> Result<WatchOnly> {
+        let path = KEYCHAINS_PATH.as_path();
+        let name: String = self.name.clone();
+        match self.source {
+            Source::Descriptors => {
+                let mut list: Vec<Descriptor<DescriptorPublicKey>> = Vec::new();
+                for line in self
+                    .text
+                    .lines()
+                    .map(|l| l.trim())
+                    .filter(|l| !l.is_empty())
+                {
+                    list.push(descriptors::parse_descriptor(line)?);
+                }
+                let descriptors = Descriptors::from_descriptors(list)?;
+                Ok(WatchOnly::new(path, name, descriptors, network)?)
+            }
+            Source::Key => {
+                let key = descriptors::parse_key(&self.text)?;
+                Ok(WatchOnly::from_key(path, name, key, network)?)
+            }
+            Source::Coldcard => match &self.coldcard_file {
+                Some(file) => {
+                    let json = ColdcardGenericJson::from_file(file)?;
+                    Ok(WatchOnly::from_coldcard(path, name, &json, network)?)
+                }
+                None => Err("Coldcard file not selected".into()),
+            },
+        }
+    }
+}
+
+pub fn update(app: &mut AppState, ui: &mut Ui) {
+    View::show(ui, |ui| {
+        Heading::new("Import watch-only").render(ui);
+
+        InputField::new("Name")
+            .placeholder("Name of keychain")
+            .render(ui, &mut app.layouts.import_watch_only.name);
+
+        ui.add_space(7.0);
+
+        ui.with_layout(Layout::top_down(Align::Min), |ui| {
+            ui.add_space(1.0);
+            ui.label("Source");
+            ui.horizontal_wrapped(|ui| {
+                ComboBox::from_id_source("watch_only_source")
+                    .width(ui.available_width())
+                    .selected_text(
+                        SOURCES
+                            .iter()
+                            .find(|&&s| s.0 == app.layouts.import_watch_only.source)
+                            .map(|s| s.1)
+                            .unwrap_or("Impossible to get value"),
+                    )
+                    .show_ui(ui, |ui| {
+                        for (source, label) in SOURCES.into_iter() {
+                            ui.selectable_value(
+                                &mut app.layouts.import_watch_only.source,
+                                source,
+                                label,
+                            );
+                        }
+                    });
+            })
+        });
+
+        ui.add_space(7.0);
+
+        match app.layouts.import_watch_only.source {
+            Source::Descriptors => {
+                InputField::new("Descriptors")
+                    .placeholder("Descriptors (one per line)")
+                    .rows(5)
+                    .render(ui, &mut app.layouts.import_watch_only.text);
+            }
+            Source::Key => {
+                InputField::new("Account key")
+                    .placeholder("[fingerprint/84'/0'/0']xpub... (or ypub/zpub)")
+                    .rows(3)
+                    .render(ui, &mut app.layouts.import_watch_only.text);
+            }
+            Source::Coldcard => {
+                if let Some(file) = &app.layouts.import_watch_only.coldcard_file {
+                    ui.label(RichText::new(file.display().to_string()).small());
+                    ui.add_space(5.0);
+                }
+                if Button::new("Select file")
+                    .background_color(DARK_GREEN)
+                    .render(ui)
+                    .clicked()
+                {
+                    if let Some(path) = FileDialog::new().add_filter("json", &["json"]).pick_file()
+                    {
+                        app.layouts.import_watch_only.coldcard_file = Some(path);
+                    }
+                }
+            }
+        }
+
+        ui.add_space(7.0);
+
+        if let Some(error) = &app.layouts.import_watch_only.error {
+            ui.label(RichText::new(error).color(Color32::RED));
+        }
+
+        ui.add_space(15.0);
+
+        let is_ready: bool = !app.layouts.import_watch_only.name.is_empty()
+            && match app.layouts.import_watch_only.source {
+                Source::Coldcard => app.layouts.import_watch_only.coldcard_file.is_some(),
+                _ => !app.layouts.import_watch_only.text.is_empty(),
+            };
+
+        if Button::new("Import")
+            .background_color(ORANGE)
+            .enabled(is_ready)
+            .render(ui)
+            .clicked()
+        {
+            match app.layouts.import_watch_only.import(app.network) {
+                Ok(watch_only) => {
+                    app.layouts.import_watch_only.clear();
+                    app.set_watch_only(Some(watch_only));
+                    app.set_stage(Stage::WatchOnly);
+                }
+                Err(e) => app.layouts.import_watch_only.error = Some(e.to_string()),
+            }
+        }
+
+        ui.add_space(5.0);
+
+        if Button::new("Back").render(ui).clicked() {
+            app.layouts.import_watch_only.clear();
+            app.set_stage(Stage::Start);
+        }
+    });
+}
@@ -28,6 +28,14 @@ pub fn update(app: &mut AppState, ui: &mut Ui) {
             app.stage = Stage::Command(Command::ViewSecrets);
         }
         ui.add_space(5.0);
+        if Button::new("Export private key")
+            .background_color(DARK_RED)
+            .render(ui)
+            .clicked()
+        {
+            app.stage = Stage::Command(Command::ExportPrivateKey);
+        }
+        ui.add_space(5.0);
         if Button::new("Delete keychain")
             .background_color(DARK_RED)
             .render(ui)
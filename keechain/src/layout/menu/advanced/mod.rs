@@ -26,6 +26,14 @@ pub fn update(app: &mut AppState, ui: &mut Ui) {
             app.stage = Stage::Command(Command::DeterministicEntropy);
         }
         ui.add_space(5.0);
+        if Button::new("Seed XOR").render(ui).clicked() {
+            app.stage = Stage::Command(Command::SeedXor);
+        }
+        ui.add_space(5.0);
+        if Button::new("Vanity address").render(ui).clicked() {
+            app.stage = Stage::Command(Command::Vanity);
+        }
+        ui.add_space(5.0);
         if Button::new("Danger")
             .background_color(DARK_RED)
             .render(ui)
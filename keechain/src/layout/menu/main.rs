@@ -24,6 +24,10 @@ pub fn update(app: &mut AppState, ui: &mut Ui, frame: &mut Frame) {
             app.set_stage(Stage::Command(Command::Sign));
         }
         ui.add_space(5.0);
+        if Button::new("Addresses").render(ui).clicked() {
+            app.set_stage(Stage::Command(Command::Addresses));
+        }
+        ui.add_space(5.0);
         if Button::new("Passphrase").render(ui).clicked() {
             app.set_stage(Stage::Command(Command::Passphrase));
         }
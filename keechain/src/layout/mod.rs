@@ -1,8 +1,10 @@
 // Copyright (c) 2022-2023 Yuki Kishimoto
 // Distributed under the MIT software license
 
+pub mod addresses;
 pub mod advanced;
 pub mod export;
+pub mod import_watch_only;
 pub mod menu;
 pub mod new_keychain;
 pub mod passphrase;
@@ -10,11 +12,18 @@ pub mod restore;
 pub mod setting;
 pub mod sign;
 pub mod start;
+pub mod watch_only;
 
+pub use self::addresses::AddressesState;
+pub use self::advanced::danger::export_private_key::ExportPrivateKeyState;
 pub use self::advanced::danger::view_secrets::ViewSecretsState;
 pub use self::advanced::danger::wipe::WipeKeychainState;
 pub use self::advanced::deterministic_entropy::DeterministicEntropyState;
+pub use self::advanced::seed_xor::SeedXorState;
+pub use self::advanced::vanity::VanityState;
 pub use self::export::electrum::ExportElectrumState;
+pub use self::export::multisig_key::ExportMultisigKeyState;
+pub use self::import_watch_only::ImportWatchOnlyState;
 pub use self::new_keychain::NewKeychainState;
 pub use self::passphrase::PassphraseState;
 pub use self::restore::RestoreState;
@@ -22,3 +31,4 @@ pub use self::setting::change_password::ChangePasswordState;
 pub use self::setting::rename::RenameKeychainState;
 pub use self::sign::SignState;
 pub use self::start::StartState;
+pub use self::watch_only::WatchOnlyState;
@@ -3,6 +3,7 @@
 
 use eframe::egui::{Align, Key, Layout, RichText, Ui};
 use eframe::epaint::Color32;
+use keechain_core::types::Passphrase;
 
 use crate::component::{Button, Error, Heading, Identity, InputField, View};
 use crate::theme::color::{DARK_RED, ORANGE};
@@ -13,8 +14,11 @@ pub struct PassphraseState {
     password: String,
     unlocked: bool,
     passphrase: String,
+    label: String,
     save: bool,
     show_saved: bool,
+    show_passphrases: bool,
+    warning: Option<String>,
     error: Option<String>,
 }
 
@@ -23,8 +27,11 @@ impl PassphraseState {
         self.password.clear();
         self.unlocked = false;
         self.passphrase = String::new();
+        self.label = String::new();
         self.save = false;
         self.show_saved = false;
+        self.show_passphrases = false;
+        self.warning = None;
         self.error = None;
     }
 }
@@ -92,6 +99,11 @@ pub fn apply_new_layout(app: &mut AppState, ui: &mut Ui) {
 
     ui.add_space(7.0);
 
+    if let Some(warning) = &app.layouts.passphrase.warning {
+        ui.label(RichText::new(warning).color(ORANGE));
+        ui.add_space(7.0);
+    }
+
     if let Some(error) = &app.layouts.passphrase.error {
         ui.label(RichText::new(error).color(Color32::RED));
     }
@@ -105,9 +117,17 @@ pub fn apply_new_layout(app: &mut AppState, ui: &mut Ui) {
         );
     });
 
+    if app.layouts.passphrase.save {
+        ui.add_space(7.0);
+        InputField::new("Label")
+            .placeholder("Label")
+            .render(ui, &mut app.layouts.passphrase.label);
+    }
+
     ui.add_space(15.0);
 
-    let is_ready: bool = !app.layouts.passphrase.passphrase.is_empty();
+    let is_ready: bool = !app.layouts.passphrase.passphrase.is_empty()
+        && (!app.layouts.passphrase.save || !app.layouts.passphrase.label.is_empty());
 
     let button = Button::new("Apply")
         .background_color(ORANGE)
@@ -132,6 +152,8 @@ pub fn apply_new_layout(app: &mut AppState, ui: &mut Ui) {
     ui.add_space(5.0);
 
     if Button::new("Saved").render(ui).clicked() {
+        app.layouts.passphrase.passphrase.clear();
+        app.layouts.passphrase.warning = None;
         app.layouts.passphrase.show_saved = true;
     }
 
@@ -146,29 +168,35 @@ pub fn apply_new_layout(app: &mut AppState, ui: &mut Ui) {
         match app.keechain.as_mut() {
             Some(keechain) => {
                 if app.layouts.passphrase.save {
-                    let _ = keechain.add_passphrase(
+                    if let Err(e) = keechain.add_passphrase(
                         app.layouts.passphrase.password.clone(),
+                        app.layouts.passphrase.label.clone(),
                         app.layouts.passphrase.passphrase.clone(),
-                    );
-                    if let Err(e) = keechain.save() {
+                        &SECP256K1,
+                    ) {
                         app.layouts.passphrase.error = Some(e.to_string());
-                    } else {
-                        let _ = keechain.apply_passphrase(
-                            app.layouts.passphrase.password.clone(),
-                            Some(app.layouts.passphrase.passphrase.clone()),
-                            &SECP256K1,
-                        );
+                        return;
+                    }
+                }
+
+                match keechain.apply_passphrase(
+                    app.layouts.passphrase.password.clone(),
+                    Some(app.layouts.passphrase.passphrase.clone()),
+                    &SECP256K1,
+                ) {
+                    Ok(Some(_)) => {
                         app.layouts.passphrase.clear();
                         app.set_stage(Stage::Menu(Menu::Main));
                     }
-                } else {
-                    let _ = keechain.apply_passphrase(
-                        app.layouts.passphrase.password.clone(),
-                        Some(app.layouts.passphrase.passphrase.clone()),
-                        &SECP256K1,
-                    );
-                    app.layouts.passphrase.clear();
-                    app.set_stage(Stage::Menu(Menu::Main));
+                    Ok(None) => {
+                        // Applied, but warn about a possible typo
+                        app.layouts.passphrase.error = None;
+                        app.layouts.passphrase.warning = Some(format!(
+                            "Applied, but fingerprint {} doesn't match any saved passphrase",
+                            keechain.identity()
+                        ));
+                    }
+                    Err(e) => app.layouts.passphrase.error = Some(e.to_string()),
                 }
             }
             None => app.layouts.passphrase.error = Some("Impossible to get keechain".to_string()),
@@ -190,14 +218,24 @@ pub fn show_saved_layout(app: &mut AppState, ui: &mut Ui) {
                     }
                 } else {
                     for passphrase in passphrases.iter() {
-                        ui.radio_value(
-                            &mut app.layouts.passphrase.passphrase,
-                            passphrase.clone(),
-                            passphrase,
-                        );
+                        let mut text: String =
+                            format!("{} ({})", passphrase.label(), passphrase.fingerprint());
+                        if app.layouts.passphrase.show_passphrases {
+                            text.push_str(&format!(": {}", passphrase.passphrase()));
+                        }
+                        ui.radio_value(&mut app.layouts.passphrase.label, passphrase.label(), text);
                         ui.add_space(5.0);
                     }
 
+                    ui.add_space(7.0);
+
+                    ui.with_layout(Layout::top_down(Align::Min), |ui| {
+                        ui.checkbox(
+                            &mut app.layouts.passphrase.show_passphrases,
+                            "Show passphrases",
+                        );
+                    });
+
                     if let Some(error) = &app.layouts.passphrase.error {
                         ui.add_space(7.0);
                         Error::new(error).render(ui);
@@ -205,7 +243,10 @@ pub fn show_saved_layout(app: &mut AppState, ui: &mut Ui) {
 
                     ui.add_space(15.0);
 
-                    let is_ready: bool = !app.layouts.passphrase.passphrase.is_empty();
+                    let selected: Option<&Passphrase> = passphrases
+                        .iter()
+                        .find(|p| p.label() == app.layouts.passphrase.label);
+                    let is_ready: bool = selected.is_some();
 
                     let button = Button::new("Apply")
                         .background_color(ORANGE)
@@ -225,20 +266,28 @@ pub fn show_saved_layout(app: &mut AppState, ui: &mut Ui) {
                         app.layouts.passphrase.clear();
                     }
 
-                    if is_ready && (ui.input(|i| i.key_pressed(Key::Enter)) || button.clicked()) {
-                        let _ = keechain.apply_passphrase(
-                            app.layouts.passphrase.password.clone(),
-                            Some(app.layouts.passphrase.passphrase.clone()),
-                            &SECP256K1,
-                        );
-                        app.layouts.passphrase.clear();
-                        app.set_stage(Stage::Menu(Menu::Main));
-                    } else if is_ready && delete_button.clicked() {
-                        let _ = keechain.remove_passphrase(
-                            app.layouts.passphrase.password.clone(),
-                            app.layouts.passphrase.passphrase.clone(),
-                        );
-                        app.layouts.passphrase.passphrase.clear();
+                    if let Some(selected) = selected {
+                        if ui.input(|i| i.key_pressed(Key::Enter)) || button.clicked() {
+                            match keechain.apply_passphrase(
+                                app.layouts.passphrase.password.clone(),
+                                Some(selected.passphrase()),
+                                &SECP256K1,
+                            ) {
+                                Ok(_) => {
+                                    app.layouts.passphrase.clear();
+                                    app.set_stage(Stage::Menu(Menu::Main));
+                                }
+                                Err(e) => app.layouts.passphrase.error = Some(e.to_string()),
+                            }
+                        } else if delete_button.clicked() {
+                            match keechain.remove_passphrase(
+                                app.layouts.passphrase.password.clone(),
+                                selected.label(),
+                            ) {
+                                Ok(_) => app.layouts.passphrase.label.clear(),
+                                Err(e) => app.layouts.passphrase.error = Some(e.to_string()),
+                            }
+                        }
                     }
                 }
             }
@@ -3,18 +3,63 @@
 
 use std::str::FromStr;
 
-use eframe::egui::{Key, RichText, Ui};
+use eframe::egui::{Align, ComboBox, Key, Layout, RichText, Ui};
 use eframe::epaint::Color32;
 use keechain_core::bips::bip39::Mnemonic;
-use keechain_core::types::KeeChain;
+use keechain_core::seeds::electrum::ElectrumMnemonic;
+use keechain_core::seeds::xor;
+use keechain_core::types::{KeeChain, SeedKind};
+use keechain_core::Result;
 
 use crate::component::{Button, Heading, InputField, View};
 use crate::theme::color::ORANGE;
 use crate::{AppState, Menu, Stage, KEYCHAINS_PATH, SECP256K1};
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SeedType {
+    BIP39,
+    Electrum,
+    SeedXor,
+    RawSeed,
+    Xprv,
+}
+
+impl Default for SeedType {
+    fn default() -> Self {
+        Self::BIP39
+    }
+}
+
+impl SeedType {
+    fn parse(&self, mnemonic: &str) -> Result<SeedKind> {
+        match self {
+            Self::BIP39 => Ok(SeedKind::BIP39(Mnemonic::from_str(mnemonic)?)),
+            Self::Electrum => Ok(SeedKind::Electrum(ElectrumMnemonic::from_str(mnemonic)?)),
+            Self::SeedXor => {
+                let mut parts: Vec<Mnemonic> = Vec::new();
+                for line in mnemonic.lines().filter(|l| !l.trim().is_empty()) {
+                    parts.push(Mnemonic::from_str(line)?);
+                }
+                Ok(SeedKind::BIP39(xor::combine(&parts)?))
+            }
+            Self::RawSeed => Ok(SeedKind::from_raw_seed_hex(mnemonic)?),
+            Self::Xprv => Ok(SeedKind::from_str(mnemonic)?),
+        }
+    }
+}
+
+const SEED_TYPES: [(SeedType, &str); 5] = [
+    (SeedType::BIP39, "BIP39"),
+    (SeedType::Electrum, "Electrum (standard or segwit)"),
+    (SeedType::SeedXor, "Seed XOR (Coldcard)"),
+    (SeedType::RawSeed, "BIP32 seed (hex)"),
+    (SeedType::Xprv, "BIP32 root key (xprv)"),
+];
+
 #[derive(Default)]
 pub struct RestoreState {
     name: String,
+    seed_type: SeedType,
     mnemonic: String,
     password: String,
     confirm_password: String,
@@ -24,6 +69,7 @@ pub struct RestoreState {
 impl RestoreState {
     pub fn clear(&mut self) {
         self.name = String::new();
+        self.seed_type = SeedType::default();
         self.mnemonic = String::new();
         self.password = String::new();
         self.confirm_password = String::new();
@@ -55,8 +101,41 @@ pub fn update(app: &mut AppState, ui: &mut Ui) {
 
         ui.add_space(7.0);
 
-        InputField::new("Mnemonic (BIP39)")
-            .placeholder("Mnemonic")
+        ui.with_layout(Layout::top_down(Align::Min), |ui| {
+            ui.add_space(1.0);
+            ui.label("Seed type");
+            ui.horizontal_wrapped(|ui| {
+                ComboBox::from_id_source("seed_type")
+                    .width(ui.available_width())
+                    .selected_text(
+                        SEED_TYPES
+                            .iter()
+                            .find(|&&t| t.0 == app.layouts.restore.seed_type)
+                            .map(|t| t.1)
+                            .unwrap_or("Impossible to get value"),
+                    )
+                    .show_ui(ui, |ui| {
+                        for (seed_type, label) in SEED_TYPES.into_iter() {
+                            ui.selectable_value(
+                                &mut app.layouts.restore.seed_type,
+                                seed_type,
+                                label,
+                            );
+                        }
+                    });
+            })
+        });
+
+        ui.add_space(7.0);
+
+        let placeholder: &str = match app.layouts.restore.seed_type {
+            SeedType::SeedXor => "Seed XOR parts (one per line)",
+            SeedType::RawSeed => "Seed (hex, between 16 and 64 bytes)",
+            SeedType::Xprv => "Root extended private key (xprv)",
+            _ => "Mnemonic",
+        };
+        InputField::new("Mnemonic")
+            .placeholder(placeholder)
             .rows(5)
             .render(ui, &mut app.layouts.restore.mnemonic);
 
@@ -86,13 +165,18 @@ pub fn update(app: &mut AppState, ui: &mut Ui) {
         }
 
         if is_ready && (ui.input(|i| i.key_pressed(Key::Enter)) || button.clicked()) {
-            match Mnemonic::from_str(&app.layouts.restore.mnemonic) {
-                Ok(mnemonic) => match KeeChain::restore(
+            match app
+                .layouts
+                .restore
+                .seed_type
+                .parse(&app.layouts.restore.mnemonic)
+            {
+                Ok(kind) => match KeeChain::restore(
                     KEYCHAINS_PATH.as_path(),
                     app.layouts.restore.name.clone(),
                     || Ok(app.layouts.restore.password.clone()),
                     || Ok(app.layouts.restore.confirm_password.clone()),
-                    || Ok(mnemonic),
+                    || Ok(kind),
                     app.network,
                     &SECP256K1,
                 ) {
@@ -5,11 +5,11 @@ use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
 use eframe::egui::{RichText, Ui};
-use keechain_core::bdk::miniscript::Descriptor;
 use keechain_core::bitcoin::psbt::PartiallySignedTransaction;
-use keechain_core::bitcoin::Network;
+use keechain_core::bitcoin::Address;
+use keechain_core::psbt::MultisigReview;
 use keechain_core::util::dir;
-use keechain_core::{KeeChain, PsbtUtility, Seed};
+use keechain_core::{KeeChain, PsbtUtility, SigningDescriptor};
 use rfd::FileDialog;
 
 use crate::component::{Button, Error, Heading, Identity, InputField, View};
@@ -20,28 +20,26 @@ pub fn sign_file_from_seed<P>(
     keechain: &KeeChain,
     password: String,
     descriptor: String,
-    network: Network,
     path: P,
 ) -> crate::Result<bool>
 where
     P: AsRef<Path>,
 {
-    let seed: Seed = keechain.keychain(password.clone())?.seed();
     let psbt_file = path.as_ref();
     let mut psbt: PartiallySignedTransaction = PartiallySignedTransaction::from_file(psbt_file)?;
-    let finalized: bool = if descriptor.is_empty() {
-        psbt.sign_with_seed(&seed, network, &SECP256K1)?
+    let descriptor: Option<SigningDescriptor> = if descriptor.is_empty() {
+        None
     } else {
-        let descriptor = Descriptor::from_str(&descriptor)?;
-        psbt.sign_with_descriptor(&seed, descriptor, network, &SECP256K1)?
+        Some(SigningDescriptor::from_str(&descriptor)?)
     };
+    let finalized: bool =
+        keechain.sign_psbt(password, &mut psbt, descriptor, Vec::new(), &SECP256K1)?;
     let mut psbt_file: PathBuf = psbt_file.to_path_buf();
     dir::rename_psbt(&mut psbt_file, finalized)?;
     psbt.save_to_file(psbt_file)?;
     Ok(finalized)
 }
 
-#[allow(dead_code)]
 pub struct PsbtFile {
     psbt: PartiallySignedTransaction,
     path: PathBuf,
@@ -53,6 +51,8 @@ pub struct SignState {
     descriptor: String,
     custom_descriptor: bool,
     psbt_file: Option<PsbtFile>,
+    /// Multisig review of the PSBT (`Some(None)` if it doesn't spend a script)
+    review: Option<Option<MultisigReview>>,
     error: Option<String>,
     finish: bool,
 }
@@ -63,6 +63,7 @@ impl SignState {
         self.descriptor = String::new();
         self.custom_descriptor = false;
         self.psbt_file = None;
+        self.review = None;
         self.error = None;
         self.finish = false;
     }
@@ -105,8 +106,8 @@ pub fn update(app: &mut AppState, ui: &mut Ui) {
 
             if is_ready_to_sign && !is_signed {
                 if app.layouts.sign.custom_descriptor {
-                    InputField::new("Custom descriptor (optional)")
-                        .placeholder("Custom descriptor (ex. multisig desc")
+                    InputField::new("Custom descriptor or wallet policy (optional)")
+                        .placeholder("Descriptor or BIP388 wallet policy JSON")
                         .rows(3)
                         .render(ui, &mut app.layouts.sign.descriptor);
                 }
@@ -114,28 +115,88 @@ pub fn update(app: &mut AppState, ui: &mut Ui) {
                     &mut app.layouts.sign.custom_descriptor,
                     "Use custom descriptor",
                 );
+                InputField::new("Password")
+                    .placeholder("Password")
+                    .is_password()
+                    .render(ui, &mut app.layouts.sign.password);
+                ui.add_space(7.0);
                 if let Some(psbt_file) = app.layouts.sign.psbt_file.as_ref() {
-                    if Button::new("Sign")
-                        .background_color(ORANGE)
-                        .render(ui)
-                        .clicked()
-                    {
-                        match sign_file_from_seed(
-                            keechain,
-                            app.layouts.sign.password.clone(),
-                            app.layouts.sign.descriptor.clone(),
-                            app.network,
-                            psbt_file.path.clone(),
-                        ) {
-                            Ok(finalized) => {
-                                app.layouts.sign.clear();
-                                app.layouts.sign.finish = true;
-                                if !finalized {
-                                    app.layouts.sign.error =
-                                        Some("PSBT signed but not finalized".to_string());
+                    match &app.layouts.sign.review {
+                        None => {
+                            if Button::new("Review")
+                                .background_color(DARK_GREEN)
+                                .enabled(!app.layouts.sign.password.is_empty())
+                                .render(ui)
+                                .clicked()
+                            {
+                                match keechain.review_multisig(
+                                    app.layouts.sign.password.clone(),
+                                    &psbt_file.psbt,
+                                ) {
+                                    Ok(review) => {
+                                        app.layouts.sign.error = None;
+                                        app.layouts.sign.review = Some(review);
+                                    }
+                                    Err(e) => app.layouts.sign.error = Some(e.to_string()),
+                                }
+                            }
+                        }
+                        Some(review) => {
+                            if let Some(review) = review {
+                                ui.group(|ui| {
+                                    ui.label(format!("Wallet: {}", review.wallet.name()));
+                                    for (index, txout) in
+                                        psbt_file.psbt.unsigned_tx.output.iter().enumerate()
+                                    {
+                                        let address: String = match Address::from_script(
+                                            &txout.script_pubkey,
+                                            app.network,
+                                        ) {
+                                            Ok(address) => address.to_string(),
+                                            Err(_) => String::from("unknown script"),
+                                        };
+                                        let text = RichText::new(format!(
+                                            "Output #{index}: {address} - {} sat - {}",
+                                            txout.value,
+                                            if review.is_change(index) {
+                                                "change"
+                                            } else {
+                                                "external"
+                                            }
+                                        ))
+                                        .small();
+                                        if review.is_change(index) {
+                                            ui.label(text);
+                                        } else {
+                                            ui.label(text.color(ORANGE));
+                                        }
+                                    }
+                                });
+                                ui.add_space(7.0);
+                            }
+
+                            if Button::new("Sign")
+                                .background_color(ORANGE)
+                                .render(ui)
+                                .clicked()
+                            {
+                                match sign_file_from_seed(
+                                    keechain,
+                                    app.layouts.sign.password.clone(),
+                                    app.layouts.sign.descriptor.clone(),
+                                    psbt_file.path.clone(),
+                                ) {
+                                    Ok(finalized) => {
+                                        app.layouts.sign.clear();
+                                        app.layouts.sign.finish = true;
+                                        if !finalized {
+                                            app.layouts.sign.error =
+                                                Some("PSBT signed but not finalized".to_string());
+                                        }
+                                    }
+                                    Err(e) => app.layouts.sign.error = Some(e.to_string()),
                                 }
                             }
-                            Err(e) => app.layouts.sign.error = Some(e.to_string()),
                         }
                     }
                 }
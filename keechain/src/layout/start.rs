@@ -6,7 +6,7 @@ use std::sync::Arc;
 
 use eframe::egui::{self, Align, ComboBox, Key, Layout, Ui};
 use egui_extras::RetainedImage;
-use keechain_core::types::KeeChain;
+use keechain_core::types::{KeeChain, WatchOnly};
 use keechain_core::util::dir;
 
 use crate::component::{Button, Error, InputField, View};
@@ -17,6 +17,7 @@ const LOGO: &[u8] = include_bytes!("../../assets/logo.png");
 
 pub struct StartState {
     name: String,
+    watch_only: bool,
     password: String,
     error: Option<String>,
     logo: Arc<RetainedImage>,
@@ -26,6 +27,7 @@ impl Default for StartState {
     fn default() -> Self {
         Self {
             name: String::new(),
+            watch_only: false,
             password: String::new(),
             error: None,
             logo: Arc::new(
@@ -38,6 +40,7 @@ impl Default for StartState {
 impl StartState {
     pub fn clear(&mut self) {
         self.name = String::new();
+        self.watch_only = false;
         self.password = String::new();
         self.error = None;
     }
@@ -61,19 +64,35 @@ pub fn update(app: &mut AppState, ui: &mut Ui) {
                 ComboBox::from_id_source("name")
                     .width(ui.available_width())
                     .selected_text(if app.layouts.start.name.is_empty() {
-                        "Select keychain"
+                        String::from("Select keychain")
+                    } else if app.layouts.start.watch_only {
+                        format!("{} (watch-only)", app.layouts.start.name)
                     } else {
-                        app.layouts.start.name.as_str()
+                        app.layouts.start.name.clone()
                     })
                     .show_ui(ui, |ui| {
                         if let Ok(list) = dir::get_keychains_list::<&Path>(KEYCHAINS_PATH.as_ref())
                         {
                             for value in list.into_iter() {
-                                ui.selectable_value(
-                                    &mut app.layouts.start.name,
-                                    value.clone(),
-                                    value.as_str(),
-                                );
+                                let selected: bool = !app.layouts.start.watch_only
+                                    && app.layouts.start.name == value;
+                                if ui.selectable_label(selected, value.as_str()).clicked() {
+                                    app.layouts.start.name = value;
+                                    app.layouts.start.watch_only = false;
+                                }
+                            }
+                        }
+                        if let Ok(list) = dir::get_watch_only_list::<&Path>(KEYCHAINS_PATH.as_ref())
+                        {
+                            for value in list.into_iter() {
+                                let selected: bool =
+                                    app.layouts.start.watch_only && app.layouts.start.name == value;
+                                let label: String = format!("{value} (watch-only)");
+                                if ui.selectable_label(selected, label).clicked() {
+                                    app.layouts.start.name = value;
+                                    app.layouts.start.watch_only = true;
+                                    app.layouts.start.password = String::new();
+                                }
                             }
                         }
                     });
@@ -82,12 +101,14 @@ pub fn update(app: &mut AppState, ui: &mut Ui) {
 
         ui.add_space(7.0);
 
-        InputField::new("Password")
-            .placeholder("Password")
-            .is_password()
-            .render(ui, &mut app.layouts.start.password);
+        if !app.layouts.start.watch_only {
+            InputField::new("Password")
+                .placeholder("Password")
+                .is_password()
+                .render(ui, &mut app.layouts.start.password);
 
-        ui.add_space(7.0);
+            ui.add_space(7.0);
+        }
 
         if let Some(error) = &app.layouts.start.error {
             Error::new(error).render(ui);
@@ -95,8 +116,8 @@ pub fn update(app: &mut AppState, ui: &mut Ui) {
 
         ui.add_space(15.0);
 
-        let is_ready: bool =
-            !app.layouts.start.name.is_empty() && !app.layouts.start.password.is_empty();
+        let is_ready: bool = !app.layouts.start.name.is_empty()
+            && (app.layouts.start.watch_only || !app.layouts.start.password.is_empty());
         let button = Button::new("Open")
             .background_color(ORANGE)
             .enabled(is_ready)
@@ -118,7 +139,29 @@ pub fn update(app: &mut AppState, ui: &mut Ui) {
             app.set_stage(Stage::RestoreKeychain);
         }
 
-        if is_ready && (ui.input(|i| i.key_pressed(Key::Enter)) || button.clicked()) {
+        ui.add_space(5.0);
+
+        if Button::new("Import watch-only").render(ui).clicked() {
+            app.layouts.start.clear();
+            app.set_stage(Stage::ImportWatchOnly);
+        }
+
+        let open: bool = is_ready && (ui.input(|i| i.key_pressed(Key::Enter)) || button.clicked());
+
+        if open && app.layouts.start.watch_only {
+            match WatchOnly::open(
+                KEYCHAINS_PATH.as_path(),
+                app.layouts.start.name.clone(),
+                app.network,
+            ) {
+                Ok(watch_only) => {
+                    app.layouts.start.clear();
+                    app.set_watch_only(Some(watch_only));
+                    app.set_stage(Stage::WatchOnly);
+                }
+                Err(e) => app.layouts.start.error = Some(e.to_string()),
+            }
+        } else if open {
             match KeeChain::open(
                 KEYCHAINS_PATH.as_path(),
                 app.layouts.start.name.clone(),
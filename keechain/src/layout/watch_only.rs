@@ -0,0 +1,179 @@
+// Copyright (c) 2022-2023 Yuki Kishimoto
+// Distributed under the MIT software license
+
+use eframe::egui::{Align, ComboBox, Layout, RichText, Ui};
+use keechain_core::bitcoin::psbt::PartiallySignedTransaction;
+use keechain_core::psbt::{Ownership, PsbtReview};
+use keechain_core::{PsbtUtility, Purpose};
+use rfd::FileDialog;
+
+use crate::component::{Button, Error, Heading, Identity, InputField, ReadOnlyField, View};
+use crate::theme::color::{DARK_GREEN, ORANGE};
+use crate::{AppState, Stage};
+
+const ADDRESSES_PER_PAGE: u32 = 10;
+
+#[derive(Default)]
+pub struct WatchOnlyState {
+    purpose: Option<Purpose>,
+    change: bool,
+    from: String,
+    review: Option<PsbtReview>,
+    error: Option<String>,
+}
+
+impl WatchOnlyState {
+    pub fn clear(&mut self) {
+        self.purpose = None;
+        self.change = false;
+        self.from = String::new();
+        self.review = None;
+        self.error = None;
+    }
+}
+
+pub fn update(app: &mut AppState, ui: &mut Ui) {
+    if let Some(watch_only) = &app.watch_only {
+        View::show(ui, |ui| {
+            Heading::new("Watch-only").render(ui);
+
+            Identity::new(watch_only.identity(), None).render(ui);
+            ui.add_space(15.0);
+
+            let purposes: Vec<Purpose> = watch_only.descriptors().purposes();
+            let purpose: Purpose = app
+                .layouts
+                .watch_only
+                .purpose
+                .or_else(|| purposes.first().copied())
+                .unwrap_or(Purpose::BIP84);
+
+            ui.with_layout(Layout::top_down(Align::Min), |ui| {
+                ui.add_space(1.0);
+                ui.label("Address type");
+                ui.horizontal_wrapped(|ui| {
+                    ComboBox::from_id_source("watch_only_purpose")
+                        .width(ui.available_width())
+                        .selected_text(format!("BIP{}", purpose.as_u32()))
+                        .show_ui(ui, |ui| {
+                            for value in purposes.into_iter() {
+                                ui.selectable_value(
+                                    &mut app.layouts.watch_only.purpose,
+                                    Some(value),
+                                    format!("BIP{}", value.as_u32()),
+                                );
+                            }
+                        });
+                })
+            });
+
+            ui.add_space(7.0);
+
+            if let Ok(desc) = watch_only
+                .descriptors()
+                .get_by_purpose(purpose, app.layouts.watch_only.change)
+            {
+                ReadOnlyField::new("Descriptor", desc.to_string())
+                    .rows(4)
+                    .render(ui);
+                ui.add_space(7.0);
+            }
+
+            ui.checkbox(&mut app.layouts.watch_only.change, "Change addresses");
+
+            ui.add_space(7.0);
+
+            InputField::new("First index")
+                .placeholder("0")
+                .render(ui, &mut app.layouts.watch_only.from);
+
+            ui.add_space(7.0);
+
+            let from: u32 = app.layouts.watch_only.from.parse().unwrap_or_default();
+            ui.group(|ui| {
+                for index in from..from.saturating_add(ADDRESSES_PER_PAGE) {
+                    match watch_only.address(purpose, app.layouts.watch_only.change, index) {
+                        Ok(address) => {
+                            ui.label(RichText::new(format!("{index}: {address}")).small());
+                        }
+                        Err(e) => {
+                            ui.label(RichText::new(e.to_string()).small());
+                            break;
+                        }
+                    }
+                }
+            });
+
+            ui.add_space(15.0);
+
+            if let Some(error) = &app.layouts.watch_only.error {
+                Error::new(error).render(ui);
+                ui.add_space(7.0);
+            }
+
+            if let Some(review) = &app.layouts.watch_only.review {
+                ui.group(|ui| {
+                    for (index, input) in review.inputs.iter().enumerate() {
+                        let amount: String = match input.amount {
+                            Some(amount) => format!("{amount} sat"),
+                            None => String::from("unknown amount"),
+                        };
+                        ui.label(
+                            RichText::new(format!(
+                                "Input #{index}: {amount} - {}",
+                                input.ownership
+                            ))
+                            .small(),
+                        );
+                    }
+                    for (index, output) in review.outputs.iter().enumerate() {
+                        let address: String = match &output.address {
+                            Some(address) => address.to_string(),
+                            None => String::from("unknown script"),
+                        };
+                        let text = RichText::new(format!(
+                            "Output #{index}: {address} - {} sat - {}",
+                            output.amount, output.ownership
+                        ))
+                        .small();
+                        if output.ownership == Ownership::External {
+                            ui.label(text.color(ORANGE));
+                        } else {
+                            ui.label(text);
+                        }
+                    }
+                    ui.label(RichText::new(format!("Spending: {} sat", review.spending())).small());
+                    if let Some(fee) = review.fee() {
+                        ui.label(RichText::new(format!("Fee: {fee} sat")).small());
+                    }
+                });
+                ui.add_space(7.0);
+            }
+
+            if Button::new("Review PSBT")
+                .background_color(DARK_GREEN)
+                .render(ui)
+                .clicked()
+            {
+                if let Some(path) = FileDialog::new().add_filter("psbt", &["psbt"]).pick_file() {
+                    match PartiallySignedTransaction::from_file(path) {
+                        Ok(psbt) => {
+                            app.layouts.watch_only.error = None;
+                            app.layouts.watch_only.review = Some(watch_only.review_psbt(&psbt));
+                        }
+                        Err(e) => app.layouts.watch_only.error = Some(e.to_string()),
+                    }
+                }
+            }
+
+            ui.add_space(5.0);
+
+            if Button::new("Close").render(ui).clicked() {
+                app.layouts.watch_only.clear();
+                app.stage = Stage::Start;
+            }
+        });
+    } else {
+        app.set_stage(Stage::Start);
+    }
+}
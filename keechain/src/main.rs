@@ -11,7 +11,7 @@ use eframe::{App, Frame, NativeOptions, Theme};
 use egui::TextStyle::{Body, Button, Heading, Monospace, Small};
 use keechain_core::bitcoin::secp256k1::{rand, All, Secp256k1};
 use keechain_core::bitcoin::Network;
-use keechain_core::types::KeeChain;
+use keechain_core::types::{KeeChain, WatchOnly};
 use keechain_core::Result;
 use once_cell::sync::Lazy;
 
@@ -20,9 +20,10 @@ mod layout;
 mod theme;
 
 use self::layout::{
-    ChangePasswordState, DeterministicEntropyState, ExportElectrumState, NewKeychainState,
-    PassphraseState, RenameKeychainState, RestoreState, SignState, StartState, ViewSecretsState,
-    WipeKeychainState,
+    AddressesState, ChangePasswordState, DeterministicEntropyState, ExportElectrumState,
+    ExportMultisigKeyState, ExportPrivateKeyState, ImportWatchOnlyState, NewKeychainState,
+    PassphraseState, RenameKeychainState, RestoreState, SeedXorState, SignState, StartState,
+    VanityState, ViewSecretsState, WatchOnlyState, WipeKeychainState,
 };
 
 const MIN_WINDOWS_SIZE: Vec2 = egui::vec2(350.0, 530.0);
@@ -81,17 +82,22 @@ pub enum ExportTypes {
     Descriptors,
     BitcoinCore,
     Electrum,
+    MultisigKey,
 }
 
 pub enum Command {
     Passphrase,
     Sign,
+    Addresses,
     Export(ExportTypes),
     RenameKeychain,
     ChangePassword,
     ViewSecrets,
+    ExportPrivateKey,
     WipeKeychain,
     DeterministicEntropy,
+    SeedXor,
+    Vanity,
 }
 
 #[derive(Clone)]
@@ -107,6 +113,8 @@ pub enum Stage {
     Start,
     NewKeychain,
     RestoreKeychain,
+    ImportWatchOnly,
+    WatchOnly,
     Menu(Menu),
     Command(Command),
 }
@@ -123,19 +131,27 @@ pub struct AppLayoutStates {
     new_keychain: NewKeychainState,
     restore: RestoreState,
     sign: SignState,
+    addresses: AddressesState,
     passphrase: PassphraseState,
     rename_keychain: RenameKeychainState,
     change_password: ChangePasswordState,
     view_secrets: ViewSecretsState,
+    export_private_key: ExportPrivateKeyState,
     wipe_keychain: WipeKeychainState,
     deterministic_entropy: DeterministicEntropyState,
+    seed_xor: SeedXorState,
+    vanity: VanityState,
     export_electrum: ExportElectrumState,
+    export_multisig_key: ExportMultisigKeyState,
+    import_watch_only: ImportWatchOnlyState,
+    watch_only: WatchOnlyState,
 }
 
 pub struct AppState {
     network: Network,
     stage: Stage,
     keechain: Option<KeeChain>,
+    watch_only: Option<WatchOnly>,
     layouts: AppLayoutStates,
 }
 
@@ -145,6 +161,7 @@ impl AppState {
             network: *network,
             stage: Stage::default(),
             keechain: None,
+            watch_only: None,
             layouts: AppLayoutStates::default(),
         }
     }
@@ -156,6 +173,10 @@ impl AppState {
     fn set_keechain(&mut self, keechain: Option<KeeChain>) {
         self.keechain = keechain;
     }
+
+    fn set_watch_only(&mut self, watch_only: Option<WatchOnly>) {
+        self.watch_only = watch_only;
+    }
 }
 
 impl App for AppState {
@@ -175,20 +196,28 @@ impl App for AppState {
             Stage::Start => layout::start::update(self, ui),
             Stage::NewKeychain => layout::new_keychain::update(self, ui),
             Stage::RestoreKeychain => layout::restore::update(self, ui),
+            Stage::ImportWatchOnly => layout::import_watch_only::update(self, ui),
+            Stage::WatchOnly => layout::watch_only::update(self, ui),
             Stage::Menu(menu) => layout::menu::update(self, menu.clone(), ui, frame),
             Stage::Command(cmd) => match cmd {
                 Command::Passphrase => layout::passphrase::update(self, ui),
                 Command::Sign => layout::sign::update(self, ui),
+                Command::Addresses => layout::addresses::update(self, ui),
                 Command::Export(export_type) => {
                     layout::export::update(self, export_type.clone(), ui)
                 }
                 Command::RenameKeychain => layout::setting::rename::update(self, ui),
                 Command::ChangePassword => layout::setting::change_password::update(self, ui),
                 Command::ViewSecrets => layout::advanced::danger::view_secrets::update(self, ui),
+                Command::ExportPrivateKey => {
+                    layout::advanced::danger::export_private_key::update(self, ui)
+                }
                 Command::WipeKeychain => layout::advanced::danger::wipe::update(self, ui),
                 Command::DeterministicEntropy => {
                     layout::advanced::deterministic_entropy::update(self, ui)
                 }
+                Command::SeedXor => layout::advanced::seed_xor::update(self, ui),
+                Command::Vanity => layout::advanced::vanity::update(self, ui),
             },
         });
     }